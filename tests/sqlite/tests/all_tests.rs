@@ -8,14 +8,22 @@ use welds::connections::TransactStart;
 use welds::state::{DbState, DbStatus};
 use welds::Syntax;
 
+pub mod audit;
 pub mod bulk_delete;
 pub mod bulk_update;
 pub mod callbacks;
+pub mod codecs;
+pub mod enums;
 pub mod extra_types;
+pub mod flatten;
 pub mod group_by;
+pub mod has_many_through;
 pub mod ignores;
 pub mod includes;
+pub mod json;
+pub mod links;
 pub mod migrations;
+pub mod polymorphic;
 pub mod select_col;
 pub mod streams;
 pub mod sub_query_tests;
@@ -269,6 +277,27 @@ fn should_be_able_to_create_a_new_product() {
     })
 }
 
+#[test]
+fn should_only_update_the_changed_columns_of_a_created_product() {
+    async_std::task::block_on(async {
+        let conn = get_conn().await;
+        let trans = conn.begin().await.unwrap();
+
+        let mut p1 = Product::new();
+        p1.name = "newyNewFace".to_owned();
+        p1.save(&trans).await.unwrap();
+
+        p1.description = Some("YES!".to_owned());
+        assert_eq!(p1.changes().changed_columns(), vec!["description"]);
+        p1.save(&trans).await.unwrap();
+
+        let found = Product::find_by_id(&trans, p1.id).await.unwrap().unwrap();
+        assert_eq!(found.description.as_deref(), Some("YES!"));
+
+        trans.rollback().await.unwrap();
+    })
+}
+
 #[test]
 fn should_be_able_to_scan_for_all_tables() {
     async_std::task::block_on(async {
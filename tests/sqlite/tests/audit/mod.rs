@@ -0,0 +1,71 @@
+use crate::get_conn;
+use welds::WeldsModel;
+use welds::connections::Client;
+use welds::connections::sqlite::SqliteClient;
+use welds::state::DbState;
+
+#[derive(Debug, WeldsModel, serde::Serialize)]
+#[welds(table = "Ledgers")]
+#[welds(audited)]
+pub struct Ledger {
+    #[welds(primary_key)]
+    pub id: i32,
+    pub amount: i32,
+}
+
+// audited, but its history table is missing
+#[derive(Debug, WeldsModel, serde::Serialize)]
+#[welds(table = "Journals")]
+#[welds(audited)]
+pub struct Journal {
+    #[welds(primary_key)]
+    pub id: i32,
+    pub amount: i32,
+}
+
+async fn add_ledgers(conn: &SqliteClient) {
+    let sql = "
+        CREATE TABLE Ledgers ( id INTEGER PRIMARY KEY, amount INTEGER NOT NULL );
+        CREATE TABLE Ledgers_history ( id INTEGER PRIMARY KEY, operation TEXT NOT NULL, changed_at TEXT NOT NULL, actor TEXT, old_values TEXT, new_values TEXT );
+        CREATE TABLE Journals ( id INTEGER PRIMARY KEY, amount INTEGER NOT NULL );
+    ";
+    conn.execute(sql, &[]).await.unwrap();
+}
+
+async fn history(conn: &SqliteClient) -> Vec<String> {
+    let rows = conn
+        .fetch_rows("SELECT operation FROM Ledgers_history ORDER BY id", &[])
+        .await
+        .unwrap();
+    rows.iter().map(|r| r.get("operation").unwrap()).collect()
+}
+
+#[test]
+fn should_write_a_history_row_for_each_change() {
+    async_std::task::block_on(async {
+        let conn = get_conn().await;
+        add_ledgers(&conn).await;
+
+        let mut ledger = DbState::new_uncreated(Ledger { id: 0, amount: 10 });
+        ledger.save(&conn).await.unwrap();
+        ledger.amount = 20;
+        ledger.save(&conn).await.unwrap();
+        ledger.delete(&conn).await.unwrap();
+
+        assert_eq!(history(&conn).await, vec!["INSERT", "UPDATE", "DELETE"]);
+    })
+}
+
+#[test]
+fn should_roll_back_the_change_when_its_history_can_not_be_written() {
+    async_std::task::block_on(async {
+        let conn = get_conn().await;
+        add_ledgers(&conn).await;
+
+        let mut journal = DbState::new_uncreated(Journal { id: 0, amount: 10 });
+        assert!(journal.save(&conn).await.is_err());
+
+        let count = Journal::all().count(&conn).await.unwrap();
+        assert_eq!(count, 0);
+    })
+}
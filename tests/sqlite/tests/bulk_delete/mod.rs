@@ -1,3 +1,5 @@
+use crate::get_conn;
+use sqlite_test::models::order::Order;
 use welds::Syntax;
 use welds::WeldsModel;
 
@@ -78,3 +80,21 @@ fn should_be_able_to_delete_with_wherein_with_limit() {
         );
     })
 }
+
+#[test]
+fn should_be_able_to_delete_returning_the_deleted_rows() {
+    async_std::task::block_on(async {
+        let conn = get_conn().await;
+        let rows = Order::where_col(|x| x.id.equal(1))
+            .delete_returning(&conn)
+            .await
+            .unwrap();
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].id, 1);
+        let remaining = Order::where_col(|x| x.id.equal(1))
+            .count(&conn)
+            .await
+            .unwrap();
+        assert_eq!(remaining, 0);
+    })
+}
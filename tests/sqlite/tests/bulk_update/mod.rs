@@ -1,3 +1,5 @@
+use crate::get_conn;
+use sqlite_test::models::product::Product;
 use welds::Syntax;
 use welds::WeldsModel;
 
@@ -84,3 +86,17 @@ fn should_be_able_to_update_with_just_limit() {
         );
     })
 }
+
+#[test]
+fn should_be_able_to_update_returning_the_updated_rows() {
+    async_std::task::block_on(async {
+        let conn = get_conn().await;
+        let rows = Product::where_col(|x| x.id.lte(2))
+            .set(|x| x.name, "Returned")
+            .run_returning(&conn)
+            .await
+            .unwrap();
+        assert_eq!(rows.len(), 2);
+        assert!(rows.iter().all(|p| p.name == "Returned"));
+    })
+}
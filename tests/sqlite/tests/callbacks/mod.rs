@@ -147,3 +147,66 @@ fn should_handle_all_callbacks() {
         clear_called();
     });
 }
+
+#[derive(WeldsModel, Clone)]
+#[welds(table = "Teams")]
+#[welds(AfterCreate(after_create_commit, commit = true))]
+pub struct CommittedTeam {
+    #[welds(primary_key)]
+    pub id: i32,
+    pub name: String,
+    pub city_id: i32,
+}
+
+static COMMITTED: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+fn after_create_commit(team: &CommittedTeam) {
+    COMMITTED.lock().unwrap().push(team.name.clone())
+}
+
+#[test]
+fn should_run_commit_hooks_only_once_the_transaction_commits() {
+    use welds::connections::TransactStart;
+    async_std::task::block_on(async {
+        let conn = get_conn().await;
+
+        let trans = conn.begin().await.unwrap();
+        let mut team = CommittedTeam::new();
+        team.name = "RolledBack".to_string();
+        team.city_id = 1;
+        team.save(&trans).await.unwrap();
+        trans.rollback().await.unwrap();
+        assert!(COMMITTED.lock().unwrap().is_empty());
+
+        let trans = conn.begin().await.unwrap();
+        let mut team = CommittedTeam::new();
+        team.name = "Committed".to_string();
+        team.city_id = 1;
+        team.save(&trans).await.unwrap();
+        assert!(COMMITTED.lock().unwrap().is_empty());
+        trans.commit().await.unwrap();
+        assert_eq!(COMMITTED.lock().unwrap().as_slice(), ["Committed"]);
+    });
+}
+
+#[test]
+fn should_run_rollback_callbacks_when_a_transaction_is_dropped() {
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use welds::connections::TransactStart;
+    async_std::task::block_on(async {
+        let conn = get_conn().await;
+        let rolled_back = Arc::new(AtomicBool::new(false));
+        let committed = Arc::new(AtomicBool::new(false));
+
+        let trans = conn.begin().await.unwrap();
+        let flag = rolled_back.clone();
+        trans.on_rollback(move || flag.store(true, Ordering::SeqCst));
+        let flag = committed.clone();
+        trans.on_commit(move || flag.store(true, Ordering::SeqCst));
+        drop(trans);
+
+        assert!(rolled_back.load(Ordering::SeqCst));
+        assert!(!committed.load(Ordering::SeqCst));
+    });
+}
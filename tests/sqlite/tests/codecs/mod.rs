@@ -0,0 +1,91 @@
+use crate::get_conn;
+use welds::connections::codec::{BoxError, Codec};
+use welds::connections::sqlite::SqliteClient;
+use welds::connections::Client;
+use welds::state::DbState;
+use welds::WeldsModel;
+
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Money(i64);
+
+// stores money as text, "12.34"
+pub struct CentsCodec;
+
+impl Codec<Money> for CentsCodec {
+    type Db = String;
+    fn encode(value: &Money) -> String {
+        format!("{}.{:02}", value.0 / 100, value.0 % 100)
+    }
+    fn decode(value: String) -> Result<Money, BoxError> {
+        let (dollars, cents) = value.split_once('.').ok_or("missing cents")?;
+        Ok(Money(dollars.parse::<i64>()? * 100 + cents.parse::<i64>()?))
+    }
+}
+
+#[derive(Debug, WeldsModel)]
+#[welds(table = "Invoices")]
+pub struct Invoice {
+    #[welds(primary_key)]
+    pub id: i32,
+    #[welds(with = "CentsCodec")]
+    pub total: Money,
+    #[welds(with = "CentsCodec")]
+    pub tip: Option<Money>,
+}
+
+async fn add_invoices(conn: &SqliteClient) {
+    let sql = "CREATE TABLE Invoices ( id INTEGER PRIMARY KEY, total TEXT NOT NULL, tip TEXT );";
+    conn.execute(sql, &[]).await.unwrap();
+}
+
+#[test]
+fn should_save_and_load_a_field_through_its_codec() {
+    async_std::task::block_on(async {
+        let conn = get_conn().await;
+        add_invoices(&conn).await;
+
+        let mut invoice = DbState::new_uncreated(Invoice {
+            id: 0,
+            total: Money(1234),
+            tip: None,
+        });
+        invoice.save(&conn).await.unwrap();
+
+        let rows = conn
+            .fetch_rows("SELECT total FROM Invoices", &[])
+            .await
+            .unwrap();
+        let total: String = rows[0].get("total").unwrap();
+        assert_eq!(total, "12.34");
+
+        let loaded = Invoice::find_by_id(&conn, invoice.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(loaded.total, Money(1234));
+        assert_eq!(loaded.tip, None);
+
+        invoice.total = Money(5000);
+        invoice.tip = Some(Money(705));
+        invoice.save(&conn).await.unwrap();
+
+        let loaded = Invoice::find_by_id(&conn, invoice.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(loaded.total, Money(5000));
+        assert_eq!(loaded.tip, Some(Money(705)));
+    })
+}
+
+#[test]
+fn should_fail_to_load_a_value_the_codec_can_not_decode() {
+    async_std::task::block_on(async {
+        let conn = get_conn().await;
+        add_invoices(&conn).await;
+        let sql = "INSERT INTO Invoices ( id, total ) VALUES ( 1, 'free' );";
+        conn.execute(sql, &[]).await.unwrap();
+
+        assert!(Invoice::find_by_id(&conn, 1).await.is_err());
+    })
+}
@@ -0,0 +1,103 @@
+use crate::get_conn;
+use welds::connections::db_enum::UnknownVariant;
+use welds::connections::sqlite::SqliteClient;
+use welds::connections::Client;
+use welds::errors::WeldsError;
+use welds::state::DbState;
+use welds::{WeldsEnum, WeldsModel};
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, WeldsEnum)]
+pub enum Status {
+    #[default]
+    Open,
+    #[welds(rename = "closed")]
+    Closed,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, WeldsEnum)]
+#[repr(i32)]
+pub enum Priority {
+    #[default]
+    Low = 1,
+    High = 5,
+}
+
+#[derive(Debug, WeldsModel)]
+#[welds(table = "Tickets")]
+pub struct Ticket {
+    #[welds(primary_key)]
+    pub id: i32,
+    pub status: Status,
+    pub priority: Priority,
+    pub escalated_to: Option<Priority>,
+}
+
+async fn add_tickets(conn: &SqliteClient) {
+    let sql = "CREATE TABLE Tickets ( id INTEGER PRIMARY KEY, status TEXT NOT NULL, priority INTEGER NOT NULL, escalated_to INTEGER );";
+    conn.execute(sql, &[]).await.unwrap();
+}
+
+#[test]
+fn should_save_and_load_enum_columns() {
+    async_std::task::block_on(async {
+        let conn = get_conn().await;
+        add_tickets(&conn).await;
+
+        let mut ticket = DbState::new_uncreated(Ticket {
+            id: 0,
+            status: Status::Closed,
+            priority: Priority::High,
+            escalated_to: None,
+        });
+        ticket.save(&conn).await.unwrap();
+
+        let rows = conn
+            .fetch_rows("SELECT status, priority FROM Tickets", &[])
+            .await
+            .unwrap();
+        let status: String = rows[0].get("status").unwrap();
+        let priority: i32 = rows[0].get("priority").unwrap();
+        assert_eq!(status, "closed");
+        assert_eq!(priority, 5);
+
+        let loaded = Ticket::find_by_id(&conn, ticket.id).await.unwrap().unwrap();
+        assert_eq!(loaded.status, Status::Closed);
+        assert_eq!(loaded.priority, Priority::High);
+        assert_eq!(loaded.escalated_to, None);
+
+        ticket.status = Status::Open;
+        ticket.escalated_to = Some(Priority::Low);
+        ticket.save(&conn).await.unwrap();
+
+        let loaded = Ticket::find_by_id(&conn, ticket.id).await.unwrap().unwrap();
+        assert_eq!(loaded.status, Status::Open);
+        assert_eq!(loaded.escalated_to, Some(Priority::Low));
+
+        let found = Ticket::where_col(|t| t.status.equal(Status::Open))
+            .count(&conn)
+            .await
+            .unwrap();
+        assert_eq!(found, 1);
+    })
+}
+
+#[test]
+fn should_fail_to_load_a_value_that_is_not_a_variant() {
+    async_std::task::block_on(async {
+        let conn = get_conn().await;
+        add_tickets(&conn).await;
+        let sql = "INSERT INTO Tickets ( id, status, priority ) VALUES ( 1, 'lost', 1 ), ( 2, 'Open', 9 );";
+        conn.execute(sql, &[]).await.unwrap();
+
+        for id in [1, 2] {
+            let err = Ticket::find_by_id(&conn, id).await.unwrap_err();
+            let source = match err {
+                WeldsError::Database(welds::connections::Error::Sqlx(
+                    sqlx::Error::ColumnDecode { source, .. },
+                )) => source,
+                err => panic!("expected a decode error, found: {err}"),
+            };
+            assert!(source.downcast_ref::<UnknownVariant>().is_some());
+        }
+    })
+}
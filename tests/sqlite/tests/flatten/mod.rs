@@ -0,0 +1,90 @@
+use crate::get_conn;
+use welds::connections::sqlite::SqliteClient;
+use welds::connections::Client;
+use welds::state::DbState;
+use welds::{WeldsEmbed, WeldsModel};
+
+#[derive(Debug, Default, Clone, PartialEq, WeldsEmbed)]
+pub struct Address {
+    pub street: String,
+    #[welds(rename = "town")]
+    pub city: String,
+    pub zip: Option<String>,
+}
+
+#[derive(Debug, WeldsModel)]
+#[welds(table = "Shops")]
+pub struct Shop {
+    #[welds(primary_key)]
+    pub id: i32,
+    pub name: String,
+    #[welds(flatten)]
+    pub address: Address,
+    #[welds(flatten(prefix = "billing_"))]
+    pub billing: Address,
+}
+
+async fn add_shops(conn: &SqliteClient) {
+    let sql = "
+        CREATE TABLE Shops (
+            id INTEGER PRIMARY KEY,
+            name TEXT NOT NULL,
+            street TEXT NOT NULL,
+            town TEXT NOT NULL,
+            zip TEXT,
+            billing_street TEXT NOT NULL,
+            billing_town TEXT NOT NULL,
+            billing_zip TEXT
+        );
+    ";
+    conn.execute(sql, &[]).await.unwrap();
+}
+
+fn address(street: &str, city: &str, zip: Option<&str>) -> Address {
+    Address {
+        street: street.to_owned(),
+        city: city.to_owned(),
+        zip: zip.map(|z| z.to_owned()),
+    }
+}
+
+#[test]
+fn should_save_and_load_an_embedded_struct_through_its_prefixed_columns() {
+    async_std::task::block_on(async {
+        let conn = get_conn().await;
+        add_shops(&conn).await;
+
+        let mut shop = DbState::new_uncreated(Shop {
+            id: 0,
+            name: "corner".to_owned(),
+            address: address("1 Main St", "Paris", Some("75001")),
+            billing: address("2 Side St", "Lyon", None),
+        });
+        shop.save(&conn).await.unwrap();
+
+        let rows = conn
+            .fetch_rows("SELECT town, billing_street, billing_town FROM Shops", &[])
+            .await
+            .unwrap();
+        let town: String = rows[0].get("town").unwrap();
+        let billing_street: String = rows[0].get("billing_street").unwrap();
+        let billing_town: String = rows[0].get("billing_town").unwrap();
+        assert_eq!(town, "Paris");
+        assert_eq!(billing_street, "2 Side St");
+        assert_eq!(billing_town, "Lyon");
+
+        let loaded = Shop::find_by_id(&conn, shop.id).await.unwrap().unwrap();
+        assert_eq!(loaded.address, address("1 Main St", "Paris", Some("75001")));
+        assert_eq!(loaded.billing, address("2 Side St", "Lyon", None));
+
+        shop.billing.zip = Some("69001".to_owned());
+        shop.save(&conn).await.unwrap();
+
+        let found = Shop::where_col(|s| s.billing.zip.equal(Some("69001".to_owned())))
+            .fetch_one(&conn)
+            .await
+            .unwrap();
+        assert_eq!(found.address, address("1 Main St", "Paris", Some("75001")));
+        assert_eq!(found.billing, address("2 Side St", "Lyon", Some("69001")));
+    })
+}
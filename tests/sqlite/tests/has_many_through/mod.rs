@@ -0,0 +1,105 @@
+use crate::get_conn;
+use welds::WeldsModel;
+use welds::connections::Client;
+use welds::connections::sqlite::SqliteClient;
+use welds::query::include::load_related;
+
+#[derive(Debug, WeldsModel)]
+#[welds(table = "Shoppers")]
+#[welds(HasMany(baskets, Basket, "shopper_id"))]
+#[welds(HasManyThrough(items, BasketItem, Basket, "shopper_id", "basket_id"))]
+pub struct Shopper {
+    #[welds(primary_key)]
+    pub id: i32,
+}
+#[derive(Debug, WeldsModel)]
+#[welds(table = "Baskets")]
+#[welds(BelongsTo(shopper, Shopper, "shopper_id"))]
+#[welds(HasMany(items, BasketItem, "basket_id"))]
+pub struct Basket {
+    #[welds(primary_key)]
+    pub id: i32,
+    pub shopper_id: i32,
+}
+#[derive(Debug, WeldsModel)]
+#[welds(table = "BasketItems")]
+#[welds(BelongsTo(order, Basket, "basket_id"))]
+pub struct BasketItem {
+    #[welds(primary_key)]
+    pub id: i32,
+    pub basket_id: i32,
+    pub sku: String,
+}
+
+// shopper 3 has a basket without any items
+async fn add_basket_items(conn: &SqliteClient) {
+    let sql = "
+        CREATE TABLE Shoppers ( id INTEGER PRIMARY KEY );
+        CREATE TABLE Baskets ( id INTEGER PRIMARY KEY, shopper_id INTEGER NOT NULL );
+        CREATE TABLE BasketItems ( id INTEGER PRIMARY KEY, basket_id INTEGER NOT NULL, sku TEXT NOT NULL );
+        INSERT INTO Shoppers (id) VALUES (1), (2), (3);
+        INSERT INTO Baskets (id, shopper_id) VALUES (1, 1), (2, 2), (3, 1), (4, 3);
+        INSERT INTO BasketItems (id, basket_id, sku) VALUES
+            (1, 1, 'apple'), (2, 1, 'pear'), (3, 2, 'apple'), (4, 3, 'plum');
+    ";
+    conn.execute(sql, &[]).await.unwrap();
+}
+
+#[test]
+fn should_load_included_through_the_intermediate_model() {
+    async_std::task::block_on(async {
+        let conn = get_conn().await;
+        add_basket_items(&conn).await;
+
+        let dataset = Shopper::all()
+            .include(|x| x.items)
+            .run(&conn)
+            .await
+            .unwrap();
+        let output = dataset
+            .iter()
+            .map(|shopper| {
+                let items = shopper.get(|x| x.items).into_iter().map(|x| x.id);
+                (shopper.id, items.collect::<Vec<i32>>())
+            })
+            .collect::<Vec<(i32, Vec<i32>)>>();
+        assert_eq!(output, vec![(1, vec![1, 2, 4]), (2, vec![3]), (3, vec![])]);
+
+        let shoppers = Shopper::all().run(&conn).await.unwrap();
+        let loaded = load_related(&conn, &shoppers[1..], |x| x.items)
+            .await
+            .unwrap();
+        let output = loaded
+            .iter()
+            .map(|shopper| {
+                let items = shopper.get(|x| x.items).into_iter().map(|x| x.id);
+                (shopper.id, items.collect::<Vec<i32>>())
+            })
+            .collect::<Vec<(i32, Vec<i32>)>>();
+        assert_eq!(output, vec![(2, vec![3]), (3, vec![])]);
+    })
+}
+
+#[test]
+fn should_filter_and_map_through_the_intermediate_model() {
+    async_std::task::block_on(async {
+        let conn = get_conn().await;
+        add_basket_items(&conn).await;
+
+        let items = Shopper::where_col(|x| x.id.equal(1))
+            .map_query(|x| x.items)
+            .run(&conn)
+            .await
+            .unwrap();
+        let ids: Vec<i32> = items.iter().map(|x| x.id).collect();
+        assert_eq!(ids, vec![1, 2, 4]);
+
+        let shoppers = Shopper::all()
+            .where_relation(|x| x.items, BasketItem::where_col(|l| l.sku.equal("apple")))
+            .run(&conn)
+            .await
+            .unwrap();
+        let ids: Vec<i32> = shoppers.iter().map(|x| x.id).collect();
+        assert_eq!(ids, vec![1, 2]);
+    })
+}
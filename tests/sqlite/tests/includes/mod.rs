@@ -1,5 +1,8 @@
 use crate::get_conn;
+use welds::connections::Client;
+use welds::connections::sqlite::SqliteClient;
 use welds::WeldsModel;
+use welds::query::include::load_related;
 
 #[derive(Debug, WeldsModel)]
 #[welds(table = "Users")]
@@ -18,7 +21,7 @@ pub struct Profile {
     pub user_id: i32,
     pub image_url: String,
 }
-#[derive(Debug, Clone, WeldsModel)]
+#[derive(Debug, Clone, WeldsModel, serde::Serialize)]
 #[welds(table = "Teams")]
 #[welds(HasMany(players, Player, "team_id"))]
 #[welds(BelongsTo(city, City, "city_id"))]
@@ -28,7 +31,7 @@ pub struct Team {
     pub city_id: i32,
     pub name: String,
 }
-#[derive(Debug, Clone, WeldsModel)]
+#[derive(Debug, Clone, WeldsModel, serde::Serialize)]
 #[welds(table = "Players")]
 #[welds(BelongsTo(team, Team, "team_id"))]
 pub struct Player {
@@ -37,7 +40,7 @@ pub struct Player {
     pub team_id: i32,
     pub name: String,
 }
-#[derive(Debug, WeldsModel)]
+#[derive(Debug, WeldsModel, serde::Serialize)]
 #[welds(table = "Cities")]
 #[welds(HasMany(teams, Team, "city_id"))]
 pub struct City {
@@ -251,3 +254,212 @@ fn should_allow_filtering_with_where_includes() {
     })
 }
 
+
+#[test]
+fn should_load_nested_includes() {
+    async_std::task::block_on(async {
+        let conn = get_conn().await;
+
+        let dataset = City::all()
+            .then_include(|x| x.teams, |teams| teams.include(|x| x.players))
+            .run(&conn)
+            .await
+            .unwrap();
+
+        let output = dataset
+            .iter()
+            .map(|city| {
+                let teams = city
+                    .get_nested(|x| x.teams)
+                    .into_iter()
+                    .map(|team| {
+                        let players = team.get(|x| x.players).into_iter().map(|x| x.id);
+                        (team.id, players.collect::<Vec<i32>>())
+                    })
+                    .collect::<Vec<(i32, Vec<i32>)>>();
+                (city.id, teams)
+            })
+            .collect::<Vec<(i32, Vec<(i32, Vec<i32>)>)>>();
+
+        let expected = vec![
+            (1, vec![]),
+            (2, vec![(1, vec![1])]),
+            (3, vec![(2, vec![2]), (3, vec![3, 4])]),
+        ];
+
+        assert_eq!(expected, output);
+    })
+}
+
+#[test]
+fn should_serialize_nested_includes_into_json() {
+    async_std::task::block_on(async {
+        let conn = get_conn().await;
+
+        let dataset = City::all()
+            .where_col(|x| x.id.gt(1))
+            .then_include(|x| x.teams, |teams| teams.include(|x| x.players))
+            .run(&conn)
+            .await
+            .unwrap();
+
+        let json = serde_json::to_value(&dataset).unwrap();
+        let expected = serde_json::json!([
+            {"id": 2, "name": "Liverpool", "teams": [
+                {"id": 1, "city_id": 2, "name": "Liverpool FC", "players": [
+                    {"id": 1, "team_id": 1, "name": "Andy Anderson"},
+                ]},
+            ]},
+            {"id": 3, "name": "Manchester", "teams": [
+                {"id": 2, "city_id": 3, "name": "Manchester City", "players": [
+                    {"id": 2, "team_id": 2, "name": "Bobby Biggs"},
+                ]},
+                {"id": 3, "city_id": 3, "name": "Manchester United", "players": [
+                    {"id": 3, "team_id": 3, "name": "Chris Christoferson"},
+                    {"id": 4, "team_id": 3, "name": "Danny Dier"},
+                ]},
+            ]},
+        ]);
+
+        assert_eq!(expected, json);
+    })
+}
+
+#[test]
+fn should_convert_included_into_owned_pairs() {
+    async_std::task::block_on(async {
+        let conn = get_conn().await;
+
+        let dataset = Team::all().include(|x| x.players).run(&conn).await.unwrap();
+        let output = dataset
+            .into_nested(|x| x.players)
+            .into_iter()
+            .map(|(team, players)| (team.id, players.iter().map(|x| x.id).collect()))
+            .collect::<Vec<(i32, Vec<i32>)>>();
+
+        let expected = vec![(1, vec![1]), (2, vec![2]), (3, vec![3, 4])];
+        assert_eq!(expected, output);
+    })
+}
+
+#[derive(Debug, WeldsModel)]
+#[welds(table = "Clubs")]
+#[welds(ManyToMany(members, Member, Membership))]
+pub struct Club {
+    #[welds(primary_key)]
+    pub id: i32,
+    pub name: String,
+}
+#[derive(Debug, WeldsModel)]
+#[welds(table = "Members")]
+#[welds(ManyToMany(clubs, Club, Membership))]
+pub struct Member {
+    #[welds(primary_key)]
+    pub id: i32,
+    pub name: String,
+}
+#[derive(Debug, WeldsModel)]
+#[welds(table = "Memberships")]
+#[welds(JoinTable(Club, "club_id", Member, "member_id"))]
+pub struct Membership {
+    #[welds(primary_key)]
+    pub id: i32,
+    pub club_id: i32,
+    pub member_id: i32,
+}
+
+async fn add_memberships(conn: &SqliteClient) {
+    let sql = "
+        CREATE TABLE Clubs ( id INTEGER PRIMARY KEY, name TEXT NOT NULL );
+        CREATE TABLE Members ( id INTEGER PRIMARY KEY, name TEXT NOT NULL );
+        CREATE TABLE Memberships ( id INTEGER PRIMARY KEY, club_id INTEGER NOT NULL, member_id INTEGER NOT NULL );
+        INSERT INTO Clubs (id, name) VALUES (1, 'Chess'), (2, 'Rowing'), (3, 'Darts');
+        INSERT INTO Members (id, name) VALUES (1, 'Ann'), (2, 'Bob'), (3, 'Cat');
+        INSERT INTO Memberships (club_id, member_id) VALUES (1, 1), (1, 2), (2, 2), (2, 3);
+    ";
+    conn.execute(sql, &[]).await.unwrap();
+}
+
+#[test]
+fn should_load_included_with_many_to_many() {
+    async_std::task::block_on(async {
+        let conn = get_conn().await;
+        add_memberships(&conn).await;
+
+        let dataset = Club::all()
+            .include(|x| x.members)
+            .run(&conn)
+            .await
+            .unwrap();
+
+        let output = dataset
+            .iter()
+            .map(|data| {
+                (
+                    data.id,
+                    data.get(|x| x.members)
+                        .into_iter()
+                        .map(|x| x.id)
+                        .collect::<Vec<i32>>(),
+                )
+            })
+            .collect::<Vec<(i32, Vec<i32>)>>();
+
+        let expected = vec![(1, vec![1, 2]), (2, vec![2, 3]), (3, vec![])];
+
+        assert_eq!(expected, output);
+
+        let clubs = Club::all()
+            .where_relation(|x| x.members, Member::where_col(|m| m.name.equal("Cat")))
+            .run(&conn)
+            .await
+            .unwrap();
+        let clubs: Vec<i32> = clubs.iter().map(|x| x.id).collect();
+        assert_eq!(clubs, vec![2]);
+    })
+}
+
+#[test]
+fn should_load_related_onto_rows_already_selected() {
+    async_std::task::block_on(async {
+        let conn = get_conn().await;
+
+        let mut cities = City::all().run(&conn).await.unwrap();
+        cities.retain(|x| x.name != "Liverpool");
+
+        let loaded = load_related(&conn, &cities, |x| x.teams).await.unwrap();
+        let output = loaded
+            .iter()
+            .map(|city| {
+                let teams = city.get(|x| x.teams).into_iter().map(|x| x.id);
+                (city.id, teams.collect::<Vec<i32>>())
+            })
+            .collect::<Vec<(i32, Vec<i32>)>>();
+
+        let expected = vec![(1, vec![]), (3, vec![2, 3])];
+        assert_eq!(expected, output);
+    })
+}
+
+#[test]
+fn should_load_related_many_to_many_onto_rows_already_selected() {
+    async_std::task::block_on(async {
+        let conn = get_conn().await;
+        add_memberships(&conn).await;
+
+        let mut clubs = Club::all().run(&conn).await.unwrap();
+        clubs.retain(|x| x.id != 1);
+
+        let loaded = load_related(&conn, &clubs, |x| x.members).await.unwrap();
+        let output = loaded
+            .iter()
+            .map(|club| {
+                let members = club.get(|x| x.members).into_iter().map(|x| x.id);
+                (club.id, members.collect::<Vec<i32>>())
+            })
+            .collect::<Vec<(i32, Vec<i32>)>>();
+
+        let expected = vec![(2, vec![2, 3]), (3, vec![])];
+        assert_eq!(expected, output);
+    })
+}
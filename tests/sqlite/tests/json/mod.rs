@@ -0,0 +1,75 @@
+use crate::get_conn;
+use serde::{Deserialize, Serialize};
+use welds::connections::sqlite::SqliteClient;
+use welds::connections::Client;
+use welds::state::DbState;
+use welds::{Json, WeldsModel};
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Settings {
+    pub theme: String,
+    pub tags: Vec<String>,
+}
+
+#[derive(Debug, WeldsModel)]
+#[welds(table = "Accounts")]
+pub struct Account {
+    #[welds(primary_key)]
+    pub id: i32,
+    pub settings: Json<Settings>,
+    pub extra: Option<Json<Settings>>,
+}
+
+async fn add_accounts(conn: &SqliteClient) {
+    let sql =
+        "CREATE TABLE Accounts ( id INTEGER PRIMARY KEY, settings TEXT NOT NULL, extra TEXT );";
+    conn.execute(sql, &[]).await.unwrap();
+}
+
+#[test]
+fn should_save_and_load_a_json_column() {
+    async_std::task::block_on(async {
+        let conn = get_conn().await;
+        add_accounts(&conn).await;
+
+        let settings = Settings {
+            theme: "dark".to_owned(),
+            tags: vec!["a".to_owned(), "b".to_owned()],
+        };
+        let mut account = DbState::new_uncreated(Account {
+            id: 0,
+            settings: Json(settings.clone()),
+            extra: None,
+        });
+        account.save(&conn).await.unwrap();
+
+        let rows = conn
+            .fetch_rows("SELECT settings FROM Accounts", &[])
+            .await
+            .unwrap();
+        let stored: String = rows[0].get("settings").unwrap();
+        let stored: serde_json::Value = serde_json::from_str(&stored).unwrap();
+        assert_eq!(
+            stored,
+            serde_json::json!({"theme": "dark", "tags": ["a", "b"]})
+        );
+
+        let loaded = Account::find_by_id(&conn, account.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(loaded.settings.0, settings);
+        assert_eq!(loaded.extra, None);
+
+        account.settings.theme = "light".to_owned();
+        account.extra = Some(Json(settings.clone()));
+        account.save(&conn).await.unwrap();
+
+        let loaded = Account::find_by_id(&conn, account.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(loaded.settings.theme, "light");
+        assert_eq!(loaded.extra, Some(Json(settings)));
+    })
+}
@@ -0,0 +1,135 @@
+use crate::get_conn;
+use welds::WeldsModel;
+use welds::connections::Client;
+use welds::connections::sqlite::SqliteClient;
+use welds::query::link;
+
+#[derive(Debug, WeldsModel)]
+#[welds(table = "Posts")]
+pub struct Post {
+    #[welds(primary_key)]
+    pub id: i32,
+}
+#[derive(Debug, WeldsModel)]
+#[welds(table = "Tags")]
+pub struct Tag {
+    #[welds(primary_key)]
+    pub id: i32,
+}
+#[derive(Debug, WeldsModel)]
+#[welds(table = "PostTags")]
+#[welds(JoinTable(Post, "post_id", Tag, "tag_id"))]
+pub struct PostTag {
+    #[welds(primary_key)]
+    pub id: i32,
+    pub post_id: i32,
+    pub tag_id: i32,
+    pub added_by: Option<String>,
+}
+
+async fn add_tags(conn: &SqliteClient) {
+    let sql = "
+        CREATE TABLE Posts ( id INTEGER PRIMARY KEY );
+        CREATE TABLE Tags ( id INTEGER PRIMARY KEY );
+        CREATE TABLE PostTags ( id INTEGER PRIMARY KEY, post_id INTEGER NOT NULL, tag_id INTEGER NOT NULL, added_by TEXT );
+        INSERT INTO Posts (id) VALUES (1), (2);
+        INSERT INTO Tags (id) VALUES (1), (2), (3), (4);
+        INSERT INTO PostTags (id, post_id, tag_id, added_by) VALUES (1, 1, 1, 'ann'), (2, 1, 2, 'ann'), (3, 2, 1, 'ann');
+    ";
+    conn.execute(sql, &[]).await.unwrap();
+}
+
+async fn tags_of(conn: &SqliteClient, post_id: i32) -> Vec<(i32, Option<String>)> {
+    let links = PostTag::where_col(|x| x.post_id.equal(post_id))
+        .order_by_asc(|x| x.tag_id)
+        .run(conn)
+        .await
+        .unwrap();
+    links
+        .iter()
+        .map(|x| (x.tag_id, x.added_by.clone()))
+        .collect()
+}
+
+fn tags(ids: &[i32]) -> Vec<Tag> {
+    ids.iter().map(|id| Tag { id: *id }).collect()
+}
+
+#[test]
+fn should_sync_the_links_to_exactly_the_given_models() {
+    async_std::task::block_on(async {
+        let conn = get_conn().await;
+        add_tags(&conn).await;
+        let post = Post { id: 1 };
+
+        link::sync::<PostTag, _, _>(&conn, &post, &tags(&[2, 3]))
+            .await
+            .unwrap();
+
+        let ann = Some("ann".to_owned());
+        assert_eq!(tags_of(&conn, 1).await, vec![(2, ann.clone()), (3, None)]);
+        // other posts are left alone
+        assert_eq!(tags_of(&conn, 2).await, vec![(1, ann)]);
+    })
+}
+
+#[test]
+fn should_sync_the_links_inside_an_open_transaction() {
+    use welds::TransactStart;
+    async_std::task::block_on(async {
+        let conn = get_conn().await;
+        add_tags(&conn).await;
+        let post = Post { id: 1 };
+
+        let trans = conn.begin().await.unwrap();
+        link::sync::<PostTag, _, _>(&trans, &post, &tags(&[3]))
+            .await
+            .unwrap();
+        trans.rollback().await.unwrap();
+
+        let ann = Some("ann".to_owned());
+        assert_eq!(tags_of(&conn, 1).await, vec![(1, ann.clone()), (2, ann)]);
+    })
+}
+
+#[test]
+fn should_sync_the_links_with_extra_columns() {
+    async_std::task::block_on(async {
+        let conn = get_conn().await;
+        add_tags(&conn).await;
+        let post = Post { id: 1 };
+
+        link::sync_with(&conn, &post, &tags(&[1, 4]), |post: &Post, tag: &Tag| {
+            PostTag {
+                id: 0,
+                post_id: post.id,
+                tag_id: tag.id,
+                added_by: Some("bob".to_owned()),
+            }
+        })
+        .await
+        .unwrap();
+
+        let expected = vec![(1, Some("ann".to_owned())), (4, Some("bob".to_owned()))];
+        assert_eq!(tags_of(&conn, 1).await, expected);
+    })
+}
+
+#[test]
+fn should_attach_and_detach_many_links() {
+    async_std::task::block_on(async {
+        let conn = get_conn().await;
+        add_tags(&conn).await;
+        let post = Post { id: 2 };
+
+        link::attach_many::<PostTag, _, _>(&conn, &post, &tags(&[1, 3, 4]))
+            .await
+            .unwrap();
+        let ids: Vec<i32> = tags_of(&conn, 2).await.iter().map(|x| x.0).collect();
+        assert_eq!(ids, vec![1, 3, 4]);
+
+        let removed = link::detach_all::<PostTag, _>(&conn, &post).await.unwrap();
+        assert_eq!(removed, 3);
+        assert!(tags_of(&conn, 2).await.is_empty());
+    })
+}
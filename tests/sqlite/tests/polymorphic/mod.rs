@@ -0,0 +1,111 @@
+use crate::get_conn;
+use welds::WeldsModel;
+use welds::connections::Client;
+use welds::connections::sqlite::SqliteClient;
+
+#[derive(Debug, WeldsModel)]
+#[welds(table = "Articles")]
+#[welds(HasManyPolymorphic(comments, Comment, "commentable"))]
+pub struct Article {
+    #[welds(primary_key)]
+    pub id: i32,
+}
+#[derive(Debug, WeldsModel)]
+#[welds(table = "Photos")]
+#[welds(HasManyPolymorphic(comments, Comment, "commentable", "Photo"))]
+pub struct Photo {
+    #[welds(primary_key)]
+    pub id: i32,
+}
+#[derive(Debug, WeldsModel)]
+#[welds(table = "Comments")]
+#[welds(BelongsToPolymorphic(article, Article, "commentable"))]
+#[welds(BelongsToPolymorphic(photo, Photo, "commentable", "Photo"))]
+pub struct Comment {
+    #[welds(primary_key)]
+    pub id: i32,
+    pub commentable_id: i32,
+    pub commentable_type: String,
+}
+
+// Articles and Photos share ids, only the type tells the comments apart
+async fn add_comments(conn: &SqliteClient) {
+    let sql = "
+        CREATE TABLE Articles ( id INTEGER PRIMARY KEY );
+        CREATE TABLE Photos ( id INTEGER PRIMARY KEY );
+        CREATE TABLE Comments ( id INTEGER PRIMARY KEY, commentable_id INTEGER NOT NULL, commentable_type TEXT NOT NULL );
+        INSERT INTO Articles (id) VALUES (1), (2);
+        INSERT INTO Photos (id) VALUES (1), (2);
+        INSERT INTO Comments (id, commentable_id, commentable_type) VALUES
+            (1, 1, 'Articles'), (2, 1, 'Photo'), (3, 2, 'Photo'), (4, 1, 'Articles');
+    ";
+    conn.execute(sql, &[]).await.unwrap();
+}
+
+#[test]
+fn should_load_included_with_polymorphic() {
+    async_std::task::block_on(async {
+        let conn = get_conn().await;
+        add_comments(&conn).await;
+
+        let dataset = Photo::all()
+            .include(|x| x.comments)
+            .run(&conn)
+            .await
+            .unwrap();
+        let output = dataset
+            .iter()
+            .map(|photo| {
+                let comments = photo.get(|x| x.comments).into_iter().map(|x| x.id);
+                (photo.id, comments.collect::<Vec<i32>>())
+            })
+            .collect::<Vec<(i32, Vec<i32>)>>();
+        assert_eq!(output, vec![(1, vec![2]), (2, vec![3])]);
+
+        let dataset = Comment::all()
+            .include(|x| x.article)
+            .run(&conn)
+            .await
+            .unwrap();
+        let output = dataset
+            .iter()
+            .map(|comment| {
+                let articles = comment.get(|x| x.article).into_iter().map(|x| x.id);
+                (comment.id, articles.collect::<Vec<i32>>())
+            })
+            .collect::<Vec<(i32, Vec<i32>)>>();
+        let expected = vec![(1, vec![1]), (2, vec![]), (3, vec![]), (4, vec![1])];
+        assert_eq!(output, expected);
+    })
+}
+
+#[test]
+fn should_filter_and_map_across_polymorphic() {
+    async_std::task::block_on(async {
+        let conn = get_conn().await;
+        add_comments(&conn).await;
+
+        let comments = Article::where_col(|x| x.id.equal(1))
+            .map_query(|x| x.comments)
+            .run(&conn)
+            .await
+            .unwrap();
+        let ids: Vec<i32> = comments.iter().map(|x| x.id).collect();
+        assert_eq!(ids, vec![1, 4]);
+
+        let articles = Article::all()
+            .where_relation(|x| x.comments, Comment::where_col(|c| c.id.equal(2)))
+            .run(&conn)
+            .await
+            .unwrap();
+        assert!(articles.is_empty());
+
+        let photos = Photo::all()
+            .where_relation(|x| x.comments, Comment::where_col(|c| c.id.equal(2)))
+            .run(&conn)
+            .await
+            .unwrap();
+        let ids: Vec<i32> = photos.iter().map(|x| x.id).collect();
+        assert_eq!(ids, vec![1]);
+    })
+}
@@ -0,0 +1,130 @@
+//! Clients that know who is making the changes.
+//!
+//! [`ActorClient`] wraps any client and gives it an actor,
+//! the user or process that audited models record in their history.
+//! ```rust,ignore
+//! let pool = welds::connections::connect(url).await?;
+//! let client = ActorClient::new(pool.clone(), "user:42");
+//!
+//! // the history row of this update has actor = "user:42"
+//! order.save(&client).await?;
+//!
+//! // transactions started from the wrapper keep the actor
+//! let trans = client.begin().await?;
+//! ```
+
+use super::{Client, ExecuteResult, Fetch, Param, Row, Syntax};
+use crate::errors::Result;
+use crate::{TransactStart, Transaction};
+use async_trait::async_trait;
+
+#[cfg(all(not(feature = "__sync"), feature = "unstable-api"))]
+use crate::StreamClient;
+#[cfg(all(not(feature = "__sync"), feature = "unstable-api"))]
+use futures_core::stream::BoxStream;
+
+/// A client that tags the changes made through it with an actor
+#[derive(Debug, Clone)]
+pub struct ActorClient<C> {
+    inner: C,
+    actor: String,
+}
+
+impl<C> ActorClient<C> {
+    /// Wraps a client, recording `actor` as the one making changes
+    pub fn new(inner: C, actor: impl Into<String>) -> Self {
+        Self {
+            inner,
+            actor: actor.into(),
+        }
+    }
+
+    /// The actor this client records
+    pub fn actor(&self) -> &str {
+        &self.actor
+    }
+
+    /// The wrapped client
+    pub fn inner(&self) -> &C {
+        &self.inner
+    }
+
+    /// Returns the wrapped client
+    pub fn into_inner(self) -> C {
+        self.inner
+    }
+}
+
+#[maybe_async::maybe_async]
+#[async_trait]
+impl<C> Client for ActorClient<C>
+where
+    C: Client,
+{
+    async fn execute(&self, sql: &str, params: &[&(dyn Param + Sync)]) -> Result<ExecuteResult> {
+        self.inner.execute(sql, params).await
+    }
+
+    async fn fetch_rows(&self, sql: &str, params: &[&(dyn Param + Sync)]) -> Result<Vec<Row>> {
+        self.inner.fetch_rows(sql, params).await
+    }
+
+    async fn fetch_many<'s, 'args, 't>(
+        &self,
+        args: &[Fetch<'s, 'args, 't>],
+    ) -> Result<Vec<Vec<Row>>> {
+        self.inner.fetch_many(args).await
+    }
+
+    fn syntax(&self) -> Syntax {
+        self.inner.syntax()
+    }
+
+    fn defer(&self, callback: Box<dyn FnOnce() + Send>) {
+        self.inner.defer(callback)
+    }
+
+    fn default_schema(&self) -> Option<&str> {
+        self.inner.default_schema()
+    }
+
+    fn actor(&self) -> Option<&str> {
+        Some(&self.actor)
+    }
+
+    async fn begin_if_needed<'t>(&'t self) -> Result<Option<Transaction<'t>>> {
+        let trans = self.inner.begin_if_needed().await?;
+        Ok(trans.map(|t| t.with_actor(self.actor.clone())))
+    }
+}
+
+#[cfg(all(not(feature = "__sync"), feature = "unstable-api"))]
+#[async_trait]
+impl<C> StreamClient for ActorClient<C>
+where
+    C: StreamClient,
+{
+    async fn stream<'client, 'e, 'params>(
+        &'client self,
+        sql: &str,
+        params: &[&'params (dyn Param + Sync)],
+    ) -> BoxStream<'e, Result<Row>>
+    where
+        'client: 'e,
+        'params: 'e,
+    {
+        self.inner.stream(sql, params).await
+    }
+}
+
+#[maybe_async::maybe_async]
+#[async_trait]
+impl<C> TransactStart for ActorClient<C>
+where
+    C: Client + TransactStart,
+{
+    async fn begin<'t>(&'t self) -> Result<Transaction<'t>> {
+        let trans = self.inner.begin().await?;
+        Ok(trans.with_actor(self.actor.clone()))
+    }
+}
@@ -34,6 +34,14 @@ pub enum AnyClient {
 #[maybe_async::maybe_async]
 #[async_trait]
 impl Client for AnyClient {
+    async fn begin_if_needed<'t>(&'t self) -> Result<Option<Transaction<'t>>> {
+        #[cfg(feature = "noop")]
+        if let AnyClient::Noop(_) = self {
+            return Ok(None);
+        }
+        Ok(Some(self.begin().await?))
+    }
+
     /// Execute a sql command. returns the number of rows that were affected
     async fn execute(&self, sql: &str, params: &[&(dyn Param + Sync)]) -> Result<ExecuteResult> {
         match self {
@@ -0,0 +1,178 @@
+//! Custom column codecs. `#[welds(with = "path")]`
+//!
+//! A codec converts a field to and from a type the database drivers already know how to
+//! read and write, so a newtype can be stored without implementing the traits of every backend.
+//! ```rust,ignore
+//! struct Money(rust_decimal::Decimal);
+//! struct MoneyCodec;
+//!
+//! impl Codec<Money> for MoneyCodec {
+//!     type Db = i64;
+//!     fn encode(value: &Money) -> i64 { /* to cents */ }
+//!     fn decode(value: i64) -> Result<Money, BoxError> { /* from cents */ }
+//! }
+//!
+//! #[derive(WeldsModel)]
+//! #[welds(table = "orders")]
+//! struct Order {
+//!     #[welds(primary_key)]
+//!     id: i32,
+//!     #[welds(with = "MoneyCodec")]
+//!     total: Money,
+//! }
+//! ```
+
+use std::marker::PhantomData;
+
+/// The error a codec hands back when a value from the database can't be decoded
+pub type BoxError = Box<dyn std::error::Error + Send + Sync>;
+
+/// Converts a field of type `T` to and from the type stored in the database
+pub trait Codec<T> {
+    /// The type stored in the database. Any type the drivers can read and write
+    type Db;
+
+    /// The value written to the database for a field
+    fn encode(value: &T) -> Self::Db;
+
+    /// The field for a value read from the database
+    fn decode(value: Self::Db) -> Result<T, BoxError>;
+}
+
+/// Runs a codec on the inner value of a nullable column.
+/// Used for `Option<T>` fields marked with `#[welds(with = "path")]`
+pub struct OptionCodec<C>(PhantomData<C>);
+
+impl<C, T> Codec<Option<T>> for OptionCodec<C>
+where
+    C: Codec<T>,
+{
+    type Db = Option<C::Db>;
+
+    fn encode(value: &Option<T>) -> Self::Db {
+        value.as_ref().map(C::encode)
+    }
+
+    fn decode(value: Self::Db) -> Result<Option<T>, BoxError> {
+        value.map(C::decode).transpose()
+    }
+}
+
+/// A field viewed through its codec. This is what is handed to the database drivers.
+///
+/// The field is encoded when the driver asks for its value
+#[repr(transparent)]
+pub struct Encoded<C, T> {
+    value: T,
+    _codec: PhantomData<fn() -> C>,
+}
+
+impl<C, T> Encoded<C, T>
+where
+    C: Codec<T>,
+{
+    /// Views a field through a codec
+    pub fn wrap(value: &T) -> &Self {
+        // SAFETY: Encoded is repr(transparent) over T
+        unsafe { &*(value as *const T as *const Self) }
+    }
+
+    /// The field encoded into the type stored in the database
+    pub fn encode(&self) -> C::Db {
+        C::encode(&self.value)
+    }
+}
+
+#[cfg(any(feature = "mysql", feature = "sqlite", feature = "postgres"))]
+mod sqlx_impls {
+    use super::{Codec, Encoded};
+    use sqlx::encode::IsNull;
+    use sqlx::error::BoxDynError;
+    use sqlx::{Database, Encode, Type};
+
+    impl<DB, C, T> Type<DB> for Encoded<C, T>
+    where
+        DB: Database,
+        C: Codec<T>,
+        C::Db: Type<DB>,
+    {
+        fn type_info() -> DB::TypeInfo {
+            <C::Db as Type<DB>>::type_info()
+        }
+        fn compatible(ty: &DB::TypeInfo) -> bool {
+            <C::Db as Type<DB>>::compatible(ty)
+        }
+    }
+
+    impl<'q, DB, C, T> Encode<'q, DB> for Encoded<C, T>
+    where
+        DB: Database,
+        C: Codec<T>,
+        C::Db: Encode<'q, DB>,
+    {
+        fn encode_by_ref(
+            &self,
+            buf: &mut <DB as Database>::ArgumentBuffer,
+        ) -> Result<IsNull, BoxDynError> {
+            self.encode().encode(buf)
+        }
+    }
+}
+
+#[cfg(feature = "mssql")]
+mod mssql_impls {
+    use super::{Codec, Encoded};
+    use tiberius::{ColumnData, IntoSql, ToSql};
+
+    impl<C, T> ToSql for Encoded<C, T>
+    where
+        C: Codec<T>,
+        C::Db: IntoSql<'static>,
+        T: Send + Sync,
+    {
+        fn to_sql(&self) -> ColumnData<'_> {
+            self.encode().into_sql()
+        }
+    }
+}
+
+#[cfg(feature = "sqlite-sync")]
+mod sqlite_sync_impls {
+    use super::{Codec, Encoded};
+    use rusqlite::types::ValueRef;
+    use rusqlite::types::{FromSql, FromSqlError, FromSqlResult, ToSql, ToSqlOutput, Value};
+
+    impl<C, T> ToSql for Encoded<C, T>
+    where
+        C: Codec<T>,
+        C::Db: ToSql,
+    {
+        fn to_sql(&self) -> rusqlite::Result<ToSqlOutput<'_>> {
+            let db = self.encode();
+            // The encoded value only lives for this call, hand back an owned copy
+            match db.to_sql()? {
+                ToSqlOutput::Borrowed(v) => Ok(ToSqlOutput::Owned(Value::from(v))),
+                ToSqlOutput::Owned(v) => Ok(ToSqlOutput::Owned(v)),
+                _ => Err(rusqlite::Error::ToSqlConversionFailure(
+                    "unsupported codec output".into(),
+                )),
+            }
+        }
+    }
+
+    // Needed to be a `Param` when using sqlite-sync
+    impl<C, T> FromSql for Encoded<C, T>
+    where
+        C: Codec<T>,
+        C::Db: FromSql,
+    {
+        fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
+            let db = C::Db::column_result(value)?;
+            let value = C::decode(db).map_err(FromSqlError::Other)?;
+            Ok(Encoded {
+                value,
+                _codec: Default::default(),
+            })
+        }
+    }
+}
@@ -0,0 +1,327 @@
+//! Support for Rust enums stored in a single text or integer column.
+//!
+//! Implemented with `#[derive(WeldsEnum)]`. The derive implements [`DbEnum`] and calls
+//! [`impl_db_enum!`](crate::impl_db_enum) which writes the encode/decode impls for every
+//! backend that is turned on.
+
+use std::fmt::Display;
+
+/// A Rust enum stored in a single column
+///
+/// The enum is converted to and from its `Repr` (`String` or an integer)
+/// when it is written to or read from the database
+pub trait DbEnum: Sized {
+    /// The type the enum is stored as in the database
+    type Repr;
+
+    /// The value written to the database for this variant
+    fn to_repr(&self) -> Self::Repr;
+
+    /// The variant for a value read from the database
+    fn from_repr(value: Self::Repr) -> std::result::Result<Self, UnknownVariant>;
+}
+
+/// A value from the database that doesn't match any of the variants of an enum
+#[derive(Debug)]
+pub struct UnknownVariant {
+    enum_name: &'static str,
+    value: String,
+}
+
+impl UnknownVariant {
+    pub fn new(enum_name: &'static str, value: impl Display) -> Self {
+        Self {
+            enum_name,
+            value: value.to_string(),
+        }
+    }
+}
+
+impl std::error::Error for UnknownVariant {}
+
+impl Display for UnknownVariant {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} is not a variant of {}", self.value, self.enum_name)
+    }
+}
+
+/// A nullable enum field as it is handed to the database drivers.
+///
+/// tiberius only knows how to write `Option` of its own types,
+/// so `Option<Enum>` fields are written through this wrapper
+#[cfg(all(feature = "mssql", not(feature = "sqlite-sync")))]
+#[repr(transparent)]
+pub struct NullableEnum<T>(Option<T>);
+
+/// Picks how the generated code binds an `Option` field.
+///
+/// call `param` on `&&BindOption` to prefer binding enums through [`NullableEnum`]
+#[doc(hidden)]
+pub mod bind {
+    use crate::Param;
+
+    pub struct BindOption<'a, T>(pub &'a Option<T>);
+
+    /// Used for any `Option` the drivers can write themselves
+    pub trait BindFallback<'a> {
+        fn param(&self) -> &'a (dyn Param + Sync);
+    }
+
+    impl<'a, T> BindFallback<'a> for BindOption<'a, T>
+    where
+        Option<T>: Param + Sync,
+    {
+        fn param(&self) -> &'a (dyn Param + Sync) {
+            self.0
+        }
+    }
+
+    /// Used for `Option<Enum>` fields
+    #[cfg(all(feature = "mssql", not(feature = "sqlite-sync")))]
+    pub trait BindEnum<'a> {
+        fn param(&self) -> &'a (dyn Param + Sync);
+    }
+
+    #[cfg(all(feature = "mssql", not(feature = "sqlite-sync")))]
+    impl<'a, T> BindEnum<'a> for &BindOption<'a, T>
+    where
+        T: super::DbEnum,
+        super::NullableEnum<T>: Param + Sync,
+    {
+        fn param(&self) -> &'a (dyn Param + Sync) {
+            let value: &'a Option<T> = self.0;
+            // SAFETY: NullableEnum is repr(transparent) over Option<T>
+            unsafe { &*(value as *const Option<T> as *const super::NullableEnum<T>) }
+        }
+    }
+}
+
+#[cfg(all(
+    feature = "mssql",
+    not(feature = "sqlite-sync"),
+    any(feature = "mysql", feature = "sqlite", feature = "postgres")
+))]
+mod nullable_sqlx_impls {
+    use super::NullableEnum;
+    use sqlx::encode::IsNull;
+    use sqlx::error::BoxDynError;
+    use sqlx::{Database, Encode, Type};
+
+    impl<DB, T> Type<DB> for NullableEnum<T>
+    where
+        DB: Database,
+        Option<T>: Type<DB>,
+    {
+        fn type_info() -> DB::TypeInfo {
+            <Option<T> as Type<DB>>::type_info()
+        }
+        fn compatible(ty: &DB::TypeInfo) -> bool {
+            <Option<T> as Type<DB>>::compatible(ty)
+        }
+    }
+
+    impl<'q, DB, T> Encode<'q, DB> for NullableEnum<T>
+    where
+        DB: Database,
+        Option<T>: Encode<'q, DB>,
+    {
+        fn encode_by_ref(
+            &self,
+            buf: &mut <DB as Database>::ArgumentBuffer,
+        ) -> Result<IsNull, BoxDynError> {
+            self.0.encode_by_ref(buf)
+        }
+    }
+}
+
+#[cfg(all(feature = "mssql", not(feature = "sqlite-sync")))]
+mod nullable_mssql_impls {
+    use super::{DbEnum, NullableEnum};
+    use tiberius::{ColumnData, IntoSql, ToSql};
+
+    // a NULL is written as the type the enum is stored as
+    impl<T> ToSql for NullableEnum<T>
+    where
+        T: DbEnum + ToSql,
+        Option<T::Repr>: IntoSql<'static>,
+    {
+        fn to_sql(&self) -> ColumnData<'_> {
+            match &self.0 {
+                Some(value) => value.to_sql(),
+                None => None::<T::Repr>.into_sql(),
+            }
+        }
+    }
+}
+
+/// The crates the generated impls are written against
+#[doc(hidden)]
+pub mod __private {
+    #[cfg(feature = "sqlite-sync")]
+    pub use rusqlite;
+    #[cfg(any(feature = "mysql", feature = "sqlite", feature = "postgres"))]
+    pub use sqlx;
+    #[cfg(feature = "mssql")]
+    pub use tiberius;
+}
+
+/// Implements the encode/decode traits of every enabled backend for a [`DbEnum`]
+///
+/// Called by `#[derive(WeldsEnum)]`, the type must already implement `DbEnum`
+#[macro_export]
+macro_rules! impl_db_enum {
+    ($t:ty, $repr:ty) => {
+        $crate::__db_enum_sqlx!($t, $repr);
+        $crate::__db_enum_mssql!($t, $repr);
+        $crate::__db_enum_sqlite_sync!($t, $repr);
+    };
+}
+
+#[cfg(any(feature = "mysql", feature = "sqlite", feature = "postgres"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __db_enum_sqlx {
+    ($t:ty, $repr:ty) => {
+        impl<DB> $crate::db_enum::__private::sqlx::Type<DB> for $t
+        where
+            DB: $crate::db_enum::__private::sqlx::Database,
+            $repr: $crate::db_enum::__private::sqlx::Type<DB>,
+        {
+            fn type_info() -> <DB as $crate::db_enum::__private::sqlx::Database>::TypeInfo {
+                <$repr as $crate::db_enum::__private::sqlx::Type<DB>>::type_info()
+            }
+            fn compatible(
+                ty: &<DB as $crate::db_enum::__private::sqlx::Database>::TypeInfo,
+            ) -> bool {
+                <$repr as $crate::db_enum::__private::sqlx::Type<DB>>::compatible(ty)
+            }
+        }
+
+        impl<'q, DB> $crate::db_enum::__private::sqlx::Encode<'q, DB> for $t
+        where
+            DB: $crate::db_enum::__private::sqlx::Database,
+            $repr: $crate::db_enum::__private::sqlx::Encode<'q, DB>,
+        {
+            fn encode_by_ref(
+                &self,
+                buf: &mut <DB as $crate::db_enum::__private::sqlx::Database>::ArgumentBuffer,
+            ) -> ::std::result::Result<
+                $crate::db_enum::__private::sqlx::encode::IsNull,
+                $crate::db_enum::__private::sqlx::error::BoxDynError,
+            > {
+                let repr = $crate::db_enum::DbEnum::to_repr(self);
+                $crate::db_enum::__private::sqlx::Encode::<'q, DB>::encode(repr, buf)
+            }
+        }
+
+        impl<'r, DB> $crate::db_enum::__private::sqlx::Decode<'r, DB> for $t
+        where
+            DB: $crate::db_enum::__private::sqlx::Database,
+            $repr: $crate::db_enum::__private::sqlx::Decode<'r, DB>,
+        {
+            fn decode(
+                value: <DB as $crate::db_enum::__private::sqlx::Database>::ValueRef<'r>,
+            ) -> ::std::result::Result<Self, $crate::db_enum::__private::sqlx::error::BoxDynError>
+            {
+                let repr =
+                    <$repr as $crate::db_enum::__private::sqlx::Decode<'r, DB>>::decode(value)?;
+                Ok(<$t as $crate::db_enum::DbEnum>::from_repr(repr)?)
+            }
+        }
+    };
+}
+
+#[cfg(not(any(feature = "mysql", feature = "sqlite", feature = "postgres")))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __db_enum_sqlx {
+    ($t:ty, $repr:ty) => {};
+}
+
+#[cfg(feature = "mssql")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __db_enum_mssql {
+    ($t:ty, $repr:ty) => {
+        impl $crate::db_enum::__private::tiberius::ToSql for $t {
+            fn to_sql(&self) -> $crate::db_enum::__private::tiberius::ColumnData<'_> {
+                let repr = $crate::db_enum::DbEnum::to_repr(self);
+                $crate::db_enum::__private::tiberius::IntoSql::into_sql(repr)
+            }
+        }
+
+        // read through by `Option<Enum>` columns
+        impl $crate::db_enum::__private::tiberius::FromSqlOwned for $t {
+            fn from_sql_owned(
+                value: $crate::db_enum::__private::tiberius::ColumnData<'static>,
+            ) -> $crate::db_enum::__private::tiberius::Result<Option<Self>> {
+                let repr: Option<$repr> =
+                    $crate::db_enum::__private::tiberius::FromSqlOwned::from_sql_owned(value)?;
+                repr.map(<$t as $crate::db_enum::DbEnum>::from_repr)
+                    .transpose()
+                    .map_err(|err| {
+                        $crate::db_enum::__private::tiberius::error::Error::Conversion(
+                            err.to_string().into(),
+                        )
+                    })
+            }
+        }
+
+        impl $crate::row::TiberiusDecode for $t {
+            fn read(
+                col: &$crate::db_enum::__private::tiberius::Column,
+                value: $crate::db_enum::__private::tiberius::ColumnData<'static>,
+            ) -> $crate::errors::Result<Self> {
+                let repr = <$repr as $crate::row::TiberiusDecode>::read(col, value)?;
+                <$t as $crate::db_enum::DbEnum>::from_repr(repr)
+                    .map_err(|err| $crate::Error::UnknownEnumVariant(err.to_string()))
+            }
+        }
+    };
+}
+
+#[cfg(not(feature = "mssql"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __db_enum_mssql {
+    ($t:ty, $repr:ty) => {};
+}
+
+#[cfg(feature = "sqlite-sync")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __db_enum_sqlite_sync {
+    ($t:ty, $repr:ty) => {
+        impl $crate::db_enum::__private::rusqlite::types::ToSql for $t {
+            fn to_sql(
+                &self,
+            ) -> $crate::db_enum::__private::rusqlite::Result<
+                $crate::db_enum::__private::rusqlite::types::ToSqlOutput<'_>,
+            > {
+                let repr = $crate::db_enum::DbEnum::to_repr(self);
+                Ok($crate::db_enum::__private::rusqlite::types::ToSqlOutput::from(repr))
+            }
+        }
+
+        impl $crate::db_enum::__private::rusqlite::types::FromSql for $t {
+            fn column_result(
+                value: $crate::db_enum::__private::rusqlite::types::ValueRef<'_>,
+            ) -> $crate::db_enum::__private::rusqlite::types::FromSqlResult<Self> {
+                let repr =
+                    <$repr as $crate::db_enum::__private::rusqlite::types::FromSql>::column_result(
+                        value,
+                    )?;
+                <$t as $crate::db_enum::DbEnum>::from_repr(repr).map_err(|err| {
+                    $crate::db_enum::__private::rusqlite::types::FromSqlError::Other(Box::new(err))
+                })
+            }
+        }
+    };
+}
+
+#[cfg(not(feature = "sqlite-sync"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __db_enum_sqlite_sync {
+    ($t:ty, $repr:ty) => {};
+}
@@ -19,6 +19,10 @@ pub enum Error {
     ColumnNotFound(String),
     UnexpectedNoneInColumn(String),
     JsonParseError(String, String),
+    JsonSerializeError(String, String),
+    UnknownEnumVariant(String),
+    ColumnCodec(String, String),
+    InvalidSchemaName(String),
 }
 
 impl std::error::Error for Error {}
@@ -45,6 +49,14 @@ impl Display for Error {
             Error::JsonParseError(col, json) => {
                 format!("unable to parse json in column: {col}. json: {json}")
             }
+            Error::JsonSerializeError(col, message) => {
+                format!("unable to write json for column: {col}. {message}")
+            }
+            Error::UnknownEnumVariant(message) => message.to_string(),
+            Error::ColumnCodec(col, message) => {
+                format!("unable to decode column: {col}. {message}")
+            }
+            Error::InvalidSchemaName(name) => format!("Not a valid schema name: {name}"),
         };
 
         f.write_str(&message)?;
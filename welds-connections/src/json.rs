@@ -0,0 +1,303 @@
+//! Typed JSON columns.
+//!
+//! [`Json<T>`] stores any serde type in a JSON column.
+//! The column is `JSONB` in Postgres, `JSON` in MySQL, `TEXT` in Sqlite and `NVARCHAR(MAX)` in MSSQL
+//! ```rust,ignore
+//! #[derive(Serialize, Deserialize, Default, Debug, Clone, PartialEq)]
+//! struct Settings {
+//!     theme: String,
+//! }
+//!
+//! #[derive(WeldsModel)]
+//! #[welds(table = "users")]
+//! struct User {
+//!     #[welds(primary_key)]
+//!     id: i32,
+//!     settings: welds::Json<Settings>,
+//!     extra: Option<welds::Json<Settings>>,
+//! }
+//! ```
+
+#[cfg(feature = "json")]
+use std::ops::{Deref, DerefMut};
+
+/// A value stored as JSON in a single column.
+///
+/// The value is written with serde when saved and parsed when it is read back
+#[cfg(feature = "json")]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Json<T>(pub T);
+
+#[cfg(feature = "json")]
+impl<T> Json<T> {
+    /// Returns the value inside the JSON column
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+#[cfg(feature = "json")]
+impl<T> From<T> for Json<T> {
+    fn from(value: T) -> Self {
+        Json(value)
+    }
+}
+
+#[cfg(feature = "json")]
+impl<T> Deref for Json<T> {
+    type Target = T;
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+#[cfg(feature = "json")]
+impl<T> DerefMut for Json<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.0
+    }
+}
+
+#[cfg(feature = "json")]
+impl<T> serde::Serialize for Json<T>
+where
+    T: serde::Serialize,
+{
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.0.serialize(serializer)
+    }
+}
+
+#[cfg(feature = "json")]
+impl<'de, T> serde::Deserialize<'de> for Json<T>
+where
+    T: serde::Deserialize<'de>,
+{
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        T::deserialize(deserializer).map(Json)
+    }
+}
+
+/// A nullable JSON field as it is handed to the database drivers.
+///
+/// tiberius only knows how to write `Option` of its own types,
+/// so `Option<Json<T>>` fields are written through this wrapper
+#[cfg(all(feature = "json", feature = "mssql", not(feature = "sqlite-sync")))]
+#[repr(transparent)]
+pub struct NullableJson<T>(Option<Json<T>>);
+
+/// Used by the generated code to bind `Json<T>` fields.
+///
+/// tiberius can't fail to write a value, so the value is serialized up front
+/// and an error is returned if it can't be written as JSON
+#[cfg(all(feature = "json", feature = "mssql"))]
+#[doc(hidden)]
+pub fn checked<'a, T: serde::Serialize>(
+    column: &str,
+    value: &'a Json<T>,
+) -> crate::errors::Result<&'a Json<T>> {
+    match serde_json::to_string(&value.0) {
+        Ok(_) => Ok(value),
+        Err(err) => Err(crate::Error::JsonSerializeError(
+            column.to_owned(),
+            err.to_string(),
+        )),
+    }
+}
+
+/// Used by the generated code to bind `Json<T>` fields
+#[cfg(not(all(feature = "json", feature = "mssql")))]
+#[doc(hidden)]
+pub fn checked<'a, T>(_column: &str, value: &'a T) -> crate::errors::Result<&'a T> {
+    Ok(value)
+}
+
+/// Used by the generated code to bind `Option<Json<T>>` fields
+#[cfg(all(feature = "json", feature = "mssql", not(feature = "sqlite-sync")))]
+#[doc(hidden)]
+pub fn nullable<'a, T: serde::Serialize>(
+    column: &str,
+    value: &'a Option<Json<T>>,
+) -> crate::errors::Result<&'a NullableJson<T>> {
+    if let Some(json) = value {
+        checked(column, json)?;
+    }
+    // SAFETY: NullableJson is repr(transparent) over Option<Json<T>>
+    Ok(unsafe { &*(value as *const Option<Json<T>> as *const NullableJson<T>) })
+}
+
+/// Used by the generated code to bind `Option<Json<T>>` fields
+#[cfg(not(all(feature = "json", feature = "mssql", not(feature = "sqlite-sync"))))]
+#[doc(hidden)]
+pub fn nullable<'a, T>(_column: &str, value: &'a T) -> crate::errors::Result<&'a T> {
+    Ok(value)
+}
+
+#[cfg(all(
+    feature = "json",
+    any(feature = "mysql", feature = "sqlite", feature = "postgres")
+))]
+mod sqlx_impls {
+    use super::Json;
+    use sqlx::encode::IsNull;
+    use sqlx::error::BoxDynError;
+    use sqlx::{Database, Decode, Encode, Type};
+
+    impl<DB, T> Type<DB> for Json<T>
+    where
+        DB: Database,
+        sqlx::types::Json<T>: Type<DB>,
+    {
+        fn type_info() -> DB::TypeInfo {
+            <sqlx::types::Json<T> as Type<DB>>::type_info()
+        }
+        fn compatible(ty: &DB::TypeInfo) -> bool {
+            <sqlx::types::Json<T> as Type<DB>>::compatible(ty)
+        }
+    }
+
+    impl<'q, DB, T> Encode<'q, DB> for Json<T>
+    where
+        DB: Database,
+        for<'a> sqlx::types::Json<&'a T>: Encode<'q, DB>,
+    {
+        fn encode_by_ref(
+            &self,
+            buf: &mut <DB as Database>::ArgumentBuffer,
+        ) -> Result<IsNull, BoxDynError> {
+            sqlx::types::Json(&self.0).encode_by_ref(buf)
+        }
+    }
+
+    impl<'r, DB, T> Decode<'r, DB> for Json<T>
+    where
+        DB: Database,
+        sqlx::types::Json<T>: Decode<'r, DB>,
+    {
+        fn decode(value: <DB as Database>::ValueRef<'r>) -> Result<Self, BoxDynError> {
+            let json = <sqlx::types::Json<T> as Decode<'r, DB>>::decode(value)?;
+            Ok(Json(json.0))
+        }
+    }
+
+    #[cfg(all(feature = "mssql", not(feature = "sqlite-sync")))]
+    impl<DB, T> Type<DB> for super::NullableJson<T>
+    where
+        DB: Database,
+        Option<Json<T>>: Type<DB>,
+    {
+        fn type_info() -> DB::TypeInfo {
+            <Option<Json<T>> as Type<DB>>::type_info()
+        }
+        fn compatible(ty: &DB::TypeInfo) -> bool {
+            <Option<Json<T>> as Type<DB>>::compatible(ty)
+        }
+    }
+
+    #[cfg(all(feature = "mssql", not(feature = "sqlite-sync")))]
+    impl<'q, DB, T> Encode<'q, DB> for super::NullableJson<T>
+    where
+        DB: Database,
+        Option<Json<T>>: Encode<'q, DB>,
+    {
+        fn encode_by_ref(
+            &self,
+            buf: &mut <DB as Database>::ArgumentBuffer,
+        ) -> Result<IsNull, BoxDynError> {
+            self.0.encode_by_ref(buf)
+        }
+    }
+}
+
+#[cfg(all(feature = "json", feature = "mssql"))]
+mod mssql_impls {
+    use super::Json;
+    use crate::Error;
+    use crate::errors::Result;
+    use crate::row::TiberiusDecode;
+    use std::borrow::Cow;
+    use tiberius::{Column, ColumnData, FromSqlOwned, ToSql};
+
+    // MSSQL has no JSON type, JSON is stored as NVARCHAR(MAX)
+    // tiberius can't fail to encode a value, model fields are checked with `checked` when they are bound
+    impl<T> ToSql for Json<T>
+    where
+        T: serde::Serialize + Send + Sync,
+    {
+        fn to_sql(&self) -> ColumnData<'_> {
+            let json = serde_json::to_string(&self.0).expect("Unable to serialize value to json");
+            ColumnData::String(Some(Cow::Owned(json)))
+        }
+    }
+
+    #[cfg(not(feature = "sqlite-sync"))]
+    impl<T> ToSql for super::NullableJson<T>
+    where
+        T: serde::Serialize + Send + Sync,
+    {
+        fn to_sql(&self) -> ColumnData<'_> {
+            match &self.0 {
+                Some(json) => json.to_sql(),
+                None => ColumnData::String(None),
+            }
+        }
+    }
+
+    impl<T> FromSqlOwned for Json<T>
+    where
+        T: serde::de::DeserializeOwned,
+    {
+        fn from_sql_owned(value: ColumnData<'static>) -> tiberius::Result<Option<Self>> {
+            let json: Option<String> = FromSqlOwned::from_sql_owned(value)?;
+            let json = match json {
+                Some(json) => json,
+                None => return Ok(None),
+            };
+            serde_json::from_str(&json)
+                .map(|v| Some(Json(v)))
+                .map_err(|err| tiberius::error::Error::Conversion(err.to_string().into()))
+        }
+    }
+
+    impl<T> TiberiusDecode for Json<T>
+    where
+        T: serde::de::DeserializeOwned,
+    {
+        fn read(col: &Column, value: ColumnData<'static>) -> Result<Self> {
+            let json: Option<String> = FromSqlOwned::from_sql_owned(value)?;
+            let json = json.ok_or_else(|| Error::UnexpectedNoneInColumn(col.name().to_owned()))?;
+            serde_json::from_str(&json)
+                .map(Json)
+                .map_err(|_| Error::JsonParseError(col.name().to_owned(), json))
+        }
+    }
+}
+
+#[cfg(all(feature = "json", feature = "sqlite-sync"))]
+mod sqlite_sync_impls {
+    use super::Json;
+    use rusqlite::types::{FromSql, FromSqlError, FromSqlResult, ToSql, ToSqlOutput, ValueRef};
+
+    impl<T> ToSql for Json<T>
+    where
+        T: serde::Serialize,
+    {
+        fn to_sql(&self) -> rusqlite::Result<ToSqlOutput<'_>> {
+            let json = serde_json::to_string(&self.0)
+                .map_err(|err| rusqlite::Error::ToSqlConversionFailure(Box::new(err)))?;
+            Ok(ToSqlOutput::from(json))
+        }
+    }
+
+    impl<T> FromSql for Json<T>
+    where
+        T: serde::de::DeserializeOwned,
+    {
+        fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
+            let json = value.as_str()?;
+            serde_json::from_str(json)
+                .map(Json)
+                .map_err(|err| FromSqlError::Other(Box::new(err)))
+        }
+    }
+}
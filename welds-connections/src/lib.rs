@@ -3,8 +3,12 @@ use crate::errors::Result;
 use async_trait::async_trait;
 pub use row::Row;
 pub use transaction::Transaction;
+pub mod actor;
 pub mod any;
+pub mod codec;
+pub mod db_enum;
 pub mod errors;
+pub mod json;
 #[cfg(feature = "mssql")]
 pub mod mssql;
 #[cfg(feature = "mysql")]
@@ -14,6 +18,7 @@ pub mod noop;
 #[cfg(feature = "postgres")]
 pub mod postgres;
 pub mod row;
+pub mod schema;
 #[cfg(feature = "sqlite")]
 pub mod sqlite;
 #[cfg(feature = "sqlite-sync")]
@@ -57,6 +62,32 @@ pub trait Client: AsyncNeedsSyncSend {
 
     // Returns what syntax (dialect) of SQL the backend is expecting
     fn syntax(&self) -> Syntax;
+
+    /// The schema used for tables that are not given one. See [`schema::SchemaClient`]
+    fn default_schema(&self) -> Option<&str> {
+        None
+    }
+
+    /// Who is making the changes, recorded in audit history. See [`actor::ActorClient`]
+    fn actor(&self) -> Option<&str> {
+        None
+    }
+
+    /// Runs the callback once the changes made through this client are committed.
+    ///
+    /// Transactions hold the callback until they commit, see [`Transaction::on_commit`].
+    /// Other clients have nothing to wait for and run it right away
+    fn defer(&self, callback: Box<dyn FnOnce() + Send>) {
+        callback()
+    }
+
+    /// Starts a transaction so several statements are committed all together.
+    ///
+    /// Returns `None` when this client is already a transaction, the statements are part of it.
+    /// Clients that can't start a transaction also return `None`
+    async fn begin_if_needed<'t>(&'t self) -> Result<Option<Transaction<'t>>> {
+        Ok(None)
+    }
 }
 
 #[cfg(feature = "unstable-api")]
@@ -45,6 +45,10 @@ pub async fn connect(cs: &str) -> Result<MssqlClient> {
 
 #[async_trait]
 impl Client for MssqlClient {
+    async fn begin_if_needed<'t>(&'t self) -> Result<Option<Transaction<'t>>> {
+        Ok(Some(self.begin().await?))
+    }
+
     async fn execute(&self, sql: &str, params: &[&(dyn Param + Sync)]) -> Result<ExecuteResult> {
         let conn = self.pool.get().await?;
         conn.execute(sql, params).await
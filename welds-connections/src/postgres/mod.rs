@@ -55,6 +55,10 @@ use sqlx::types::Type;
 
 #[async_trait]
 impl Client for PostgresClient {
+    async fn begin_if_needed<'t>(&'t self) -> Result<Option<Transaction<'t>>> {
+        Ok(Some(self.begin().await?))
+    }
+
     async fn execute(&self, sql: &str, params: &[&(dyn Param + Sync)]) -> Result<ExecuteResult> {
         log::trace!("POSTGRES EXECUTE: {}", sql);
         let mut query = sqlx::query::<Postgres>(sqlx::AssertSqlSafe(sql));
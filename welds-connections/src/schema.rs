@@ -0,0 +1,165 @@
+//! Clients pinned to a database schema.
+//!
+//! [`SchemaClient`] wraps any client and gives it a default schema.
+//! Welds writes the schema in front of every table that doesn't have one,
+//! so the same models and queries can be pointed at a different schema per tenant.
+//! ```rust,ignore
+//! let pool = welds::connections::connect(url).await?;
+//! let tenant = SchemaClient::new(pool.clone(), "tenant_42")?;
+//!
+//! // SELECT ... FROM tenant_42.orders t1
+//! let orders = Order::all().run(&tenant).await?;
+//!
+//! // transactions started from the wrapper use the same schema
+//! let trans = tenant.begin().await?;
+//! ```
+//! In Postgres transactions started from the wrapper also run `SET LOCAL search_path`,
+//! so raw SQL inside them finds the tenant's tables.
+//! Raw SQL ran directly on the wrapper is sent as is.
+
+use super::{Client, ExecuteResult, Fetch, Param, Row, Syntax};
+use crate::errors::{Error, Result};
+use crate::{TransactStart, Transaction};
+use async_trait::async_trait;
+
+#[cfg(all(not(feature = "__sync"), feature = "unstable-api"))]
+use crate::StreamClient;
+#[cfg(all(not(feature = "__sync"), feature = "unstable-api"))]
+use futures_core::stream::BoxStream;
+
+/// Checks a schema or table name given at runtime.
+///
+/// These names are written into SQL without quotes,
+/// so only ASCII letters, numbers, `_` and `$` are allowed
+pub fn is_plain_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$')
+}
+
+/// A client that places tables without a schema in a default schema
+#[derive(Debug, Clone)]
+pub struct SchemaClient<C> {
+    inner: C,
+    schema: String,
+}
+
+impl<C> SchemaClient<C> {
+    /// Wraps a client, using `schema` for tables that are not given one.
+    ///
+    /// Returns `Error::InvalidSchemaName` unless the schema [`is_plain_name`]
+    pub fn new(inner: C, schema: impl Into<String>) -> Result<Self> {
+        let schema: String = schema.into();
+        if !is_plain_name(&schema) {
+            return Err(Error::InvalidSchemaName(schema));
+        }
+        Ok(Self { inner, schema })
+    }
+
+    /// The schema this client is pinned to
+    pub fn schema(&self) -> &str {
+        &self.schema
+    }
+
+    /// The wrapped client
+    pub fn inner(&self) -> &C {
+        &self.inner
+    }
+
+    /// Returns the wrapped client
+    pub fn into_inner(self) -> C {
+        self.inner
+    }
+}
+
+#[maybe_async::maybe_async]
+impl<C> SchemaClient<C> {
+    // Pins a transaction started from the wrapped client to the schema
+    async fn pin<'t>(&self, trans: Transaction<'t>) -> Result<Transaction<'t>> {
+        let trans = trans.with_default_schema(self.schema.clone());
+        if trans.syntax() == Syntax::Postgres {
+            // LOCAL keeps the search_path from leaking back into the pool.
+            // Unquoted, the same way the schema is written in front of tables
+            let sql = format!("SET LOCAL search_path TO {}", self.schema);
+            trans.execute(&sql, &[]).await?;
+        }
+        Ok(trans)
+    }
+}
+
+#[maybe_async::maybe_async]
+#[async_trait]
+impl<C> Client for SchemaClient<C>
+where
+    C: Client,
+{
+    async fn execute(&self, sql: &str, params: &[&(dyn Param + Sync)]) -> Result<ExecuteResult> {
+        self.inner.execute(sql, params).await
+    }
+
+    async fn fetch_rows(&self, sql: &str, params: &[&(dyn Param + Sync)]) -> Result<Vec<Row>> {
+        self.inner.fetch_rows(sql, params).await
+    }
+
+    async fn fetch_many<'s, 'args, 't>(
+        &self,
+        args: &[Fetch<'s, 'args, 't>],
+    ) -> Result<Vec<Vec<Row>>> {
+        self.inner.fetch_many(args).await
+    }
+
+    fn syntax(&self) -> Syntax {
+        self.inner.syntax()
+    }
+
+    fn defer(&self, callback: Box<dyn FnOnce() + Send>) {
+        self.inner.defer(callback)
+    }
+
+    fn default_schema(&self) -> Option<&str> {
+        Some(&self.schema)
+    }
+
+    async fn begin_if_needed<'t>(&'t self) -> Result<Option<Transaction<'t>>> {
+        match self.inner.begin_if_needed().await? {
+            Some(trans) => Ok(Some(self.pin(trans).await?)),
+            None => Ok(None),
+        }
+    }
+
+    fn actor(&self) -> Option<&str> {
+        self.inner.actor()
+    }
+}
+
+#[cfg(all(not(feature = "__sync"), feature = "unstable-api"))]
+#[async_trait]
+impl<C> StreamClient for SchemaClient<C>
+where
+    C: StreamClient,
+{
+    async fn stream<'client, 'e, 'params>(
+        &'client self,
+        sql: &str,
+        params: &[&'params (dyn Param + Sync)],
+    ) -> BoxStream<'e, Result<Row>>
+    where
+        'client: 'e,
+        'params: 'e,
+    {
+        self.inner.stream(sql, params).await
+    }
+}
+
+#[maybe_async::maybe_async]
+#[async_trait]
+impl<C> TransactStart for SchemaClient<C>
+where
+    C: Client + TransactStart,
+{
+    async fn begin<'t>(&'t self) -> Result<Transaction<'t>> {
+        let trans = self.inner.begin().await?;
+        self.pin(trans).await
+    }
+}
@@ -40,6 +40,10 @@ impl SqliteSyncOwnedRow {
 }
 
 impl Client for SqliteClient {
+    fn begin_if_needed<'t>(&'t self) -> Result<Option<Transaction<'t>>> {
+        Ok(Some(self.begin()?))
+    }
+
     fn execute(&self, sql: &str, params: &[&(dyn Param + Sync)]) -> Result<ExecuteResult> {
         log::trace!("SQLITE EXECUTE: {}", sql);
         let mut p = Vec::new();
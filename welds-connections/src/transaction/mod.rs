@@ -14,6 +14,18 @@ use crate::sqlite_sync::SqliteSyncTransaction;
 pub struct Transaction<'t> {
     inner: Mutex<Option<TransT<'t>>>,
     syntax: crate::Syntax,
+    schema: Option<String>,
+    actor: Option<String>,
+    callbacks: Mutex<Callbacks>,
+}
+
+/// Work waiting on the transaction to finish
+type Callback = Box<dyn FnOnce() + Send>;
+
+#[derive(Default)]
+struct Callbacks {
+    on_commit: Vec<Callback>,
+    on_rollback: Vec<Callback>,
 }
 
 #[maybe_async::maybe_async]
@@ -35,18 +47,82 @@ impl<'t> Transaction<'t> {
         Self {
             syntax,
             inner: Mutex::new(Some(inner)),
+            schema: None,
+            actor: None,
+            callbacks: Mutex::default(),
         }
     }
 
+    /// Sets the schema used for tables that are not given one
+    pub(crate) fn with_default_schema(mut self, schema: impl Into<String>) -> Self {
+        self.schema = Some(schema.into());
+        self
+    }
+
+    /// Sets who is making the changes in this transaction
+    pub(crate) fn with_actor(mut self, actor: impl Into<String>) -> Self {
+        self.actor = Some(actor.into());
+        self
+    }
+
+    /// Rolls back the transaction, then runs the `on_rollback` callbacks
     pub async fn rollback(self) -> Result<()> {
         let inner = self.take_conn();
-        inner.rollback().await?;
-        Ok(())
+        let result = inner.rollback().await;
+        run_all(self.take_callbacks().on_rollback);
+        result
     }
+
+    /// Commits the transaction, then runs the `on_commit` callbacks.
+    /// The `on_rollback` callbacks are ran instead if the commit fails
     pub async fn commit(self) -> Result<()> {
         let inner = self.take_conn();
-        inner.commit().await?;
-        Ok(())
+        let result = inner.commit().await;
+        let callbacks = self.take_callbacks();
+        match result {
+            Ok(_) => run_all(callbacks.on_commit),
+            Err(_) => run_all(callbacks.on_rollback),
+        }
+        result
+    }
+}
+
+impl Transaction<'_> {
+    /// Runs the callback after the transaction commits.
+    ///
+    /// Useful for work that shouldn't happen if the changes are thrown away, like sending emails
+    pub fn on_commit(&self, callback: impl FnOnce() + Send + 'static) {
+        let mut callbacks = self.callbacks.lock().unwrap_or_else(|err| err.into_inner());
+        callbacks.on_commit.push(Box::new(callback));
+    }
+
+    /// Runs the callback after the transaction rolls back,
+    /// including when it is dropped without being committed
+    pub fn on_rollback(&self, callback: impl FnOnce() + Send + 'static) {
+        let mut callbacks = self.callbacks.lock().unwrap_or_else(|err| err.into_inner());
+        callbacks.on_rollback.push(Box::new(callback));
+    }
+
+    fn take_callbacks(&self) -> Callbacks {
+        let mut callbacks = self.callbacks.lock().unwrap_or_else(|err| err.into_inner());
+        std::mem::take(&mut callbacks)
+    }
+}
+
+// A transaction dropped without a commit is rolled back
+impl Drop for Transaction<'_> {
+    fn drop(&mut self) {
+        let callbacks = self
+            .callbacks
+            .get_mut()
+            .unwrap_or_else(|err| err.into_inner());
+        run_all(std::mem::take(callbacks).on_rollback);
+    }
+}
+
+fn run_all(callbacks: Vec<Callback>) {
+    for callback in callbacks {
+        callback();
     }
 }
 
@@ -138,6 +214,18 @@ impl Client for Transaction<'_> {
         self.syntax
     }
 
+    fn default_schema(&self) -> Option<&str> {
+        self.schema.as_deref()
+    }
+
+    fn actor(&self) -> Option<&str> {
+        self.actor.as_deref()
+    }
+
+    fn defer(&self, callback: Box<dyn FnOnce() + Send>) {
+        self.on_commit(callback)
+    }
+
     async fn execute(&self, sql: &str, params: &[&(dyn Param + Sync)]) -> Result<ExecuteResult> {
         if sql.trim().is_empty() {
             return Ok(ExecuteResult::new(0));
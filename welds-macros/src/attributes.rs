@@ -2,13 +2,15 @@ use crate::errors::Result;
 use crate::utils::as_typepath;
 use crate::{
     column::Column,
+    embed::Embed,
     hook::{Hook, HookKind},
     relation::Relation,
+    validation::{Rule, Validation},
 };
 use proc_macro2::{Ident, Span};
-use syn::{Attribute, Field, Type};
 use syn::punctuated::Punctuated;
 use syn::token::Comma;
+use syn::{Attribute, Field, Type};
 
 pub(crate) fn get_columns(ast: &syn::DeriveInput) -> Vec<Column> {
     let struct_def = match &ast.data {
@@ -20,6 +22,7 @@ pub(crate) fn get_columns(ast: &syn::DeriveInput) -> Vec<Column> {
     fields
         .iter()
         .filter(|f| f.ident.is_some())
+        .filter(|f| read_flatten(&f.attrs).is_none())
         .map(|f| {
             let fieldname = f.ident.as_ref().unwrap().to_string();
             let dbname = read_rename(f).unwrap_or(fieldname);
@@ -33,6 +36,7 @@ pub(crate) fn get_columns(ast: &syn::DeriveInput) -> Vec<Column> {
             let selectable = !ignores.contains(&Ignores::Select);
             let updateable = !ignores.contains(&Ignores::Update);
             let insertable = !ignores.contains(&Ignores::Insert);
+            let codec = read_codec(f);
 
             Column {
                 field,
@@ -42,6 +46,7 @@ pub(crate) fn get_columns(ast: &syn::DeriveInput) -> Vec<Column> {
                 dbname,
                 field_type,
                 is_option,
+                codec,
             }
         })
         .collect()
@@ -79,20 +84,213 @@ pub(crate) fn get_pks(ast: &syn::DeriveInput) -> Vec<Column> {
                 dbname,
                 field_type,
                 is_option,
+                codec: read_codec(f),
             }
         })
         .collect()
 }
 
+/// The fields marked with #[welds(flatten)], their columns come from an embedded struct
+pub(crate) fn get_embeds(ast: &syn::DeriveInput) -> Result<Vec<Embed>> {
+    let struct_def = match &ast.data {
+        syn::Data::Struct(d) => d,
+        syn::Data::Enum(_) => panic!("Only Structs are supported by WeldsModel"),
+        syn::Data::Union(_) => panic!("Only Structs are supported by WeldsModel"),
+    };
+    let mut embeds = Vec::default();
+    for f in &struct_def.fields {
+        let (field, prefix) = match (&f.ident, read_flatten(&f.attrs)) {
+            (Some(field), Some(prefix)) => (field, prefix),
+            _ => continue,
+        };
+        if as_option_inner(&f.ty).is_some() {
+            return Err(format!(
+                "#[welds(flatten)] field can not be an Option: {}",
+                field
+            ));
+        }
+        embeds.push(Embed {
+            field: field.clone(),
+            ty: f.ty.clone(),
+            prefix,
+        });
+    }
+    Ok(embeds)
+}
+
+/// The prefix of a #[welds(flatten)] or #[welds(flatten(prefix = "billing_"))]
+/// None if the field isn't flattened
+fn read_flatten(attrs: &[Attribute]) -> Option<String> {
+    let flatten = welds_meta(attrs)
+        .iter()
+        .flat_map(as_metalist_nested_meta)
+        .find(|m| m.path().is_ident("flatten"))?;
+    let list = match &flatten {
+        syn::Meta::List(list) => list,
+        _ => return Some(String::default()),
+    };
+    let prefix = as_metalist_nested_meta(list)
+        .iter()
+        .filter_map(as_meta_namevalue_ref)
+        .filter(|m| m.path.is_ident("prefix"))
+        .filter_map(|nv| lit_as_litstr(&nv.value))
+        .map(|x| x.value())
+        .next();
+    Some(prefix.unwrap_or_default())
+}
+
+/// The column marked with #[welds(version)] used for optimistic locking
+pub(crate) fn get_version(ast: &syn::DeriveInput, columns: &[Column]) -> Result<Option<Column>> {
+    let col = get_flagged_column(ast, columns, "version")?;
+    // rows are found by `version = ?`, which never matches a NULL
+    if let Some(col) = col.as_ref().filter(|c| c.is_option) {
+        return Err(format!(
+            "#[welds(version)] field can not be an Option: {}",
+            col.field
+        ));
+    }
+    Ok(col)
+}
+
+/// The column marked with #[welds(soft_delete)] used to flag rows as deleted
+pub(crate) fn get_soft_delete(
+    ast: &syn::DeriveInput,
+    columns: &[Column],
+) -> Result<Option<Column>> {
+    get_flagged_column(ast, columns, "soft_delete")
+}
+
+/// The column marked with #[welds(created_at)], unless the database fills it in
+pub(crate) fn get_created_at(ast: &syn::DeriveInput, columns: &[Column]) -> Result<Option<Column>> {
+    get_auto_timestamp(ast, columns, "created_at")
+}
+
+/// The column marked with #[welds(updated_at)], unless the database fills it in
+pub(crate) fn get_updated_at(ast: &syn::DeriveInput, columns: &[Column]) -> Result<Option<Column>> {
+    get_auto_timestamp(ast, columns, "updated_at")
+}
+
+/// A timestamp column welds should set.
+/// #[welds(created_at(db_default))] is left to the database
+fn get_auto_timestamp(
+    ast: &syn::DeriveInput,
+    columns: &[Column],
+    flag: &str,
+) -> Result<Option<Column>> {
+    let col = get_flagged_column(ast, columns, flag)?;
+    // db_default columns are readonly, welds never writes them
+    Ok(col.filter(|c| c.insertable || c.updateable))
+}
+
+/// The columns marked with #[welds(db_default)]
+/// These are left out of an INSERT while the field is at its default value
+pub(crate) fn get_db_defaults(ast: &syn::DeriveInput, columns: &[Column]) -> Vec<Column> {
+    get_columns_where(ast, columns, |attrs| is_welds_flag(attrs, "db_default"))
+}
+
+/// The columns the database writes to, read back after a save.
+/// #[welds(db_default)], #[welds(generated)] and #[welds(created_at(db_default))]
+pub(crate) fn get_db_managed(ast: &syn::DeriveInput, columns: &[Column]) -> Vec<Column> {
+    get_columns_where(ast, columns, |attrs| {
+        is_welds_flag(attrs, "db_default")
+            || is_welds_flag(attrs, "generated")
+            || is_welds_timestamp_db_default(attrs)
+    })
+}
+
+/// The one column marked with #[welds(flag)]
+fn get_flagged_column(
+    ast: &syn::DeriveInput,
+    columns: &[Column],
+    flag: &str,
+) -> Result<Option<Column>> {
+    let mut flagged = get_columns_where(ast, columns, |attrs| is_welds_flag(attrs, flag));
+    if flagged.len() > 1 {
+        return Err(format!(
+            "Only one field can be marked as #[welds({})]",
+            flag
+        ));
+    }
+    Ok(flagged.pop())
+}
+
+/// The columns of the fields whose attributes match
+fn get_columns_where(
+    ast: &syn::DeriveInput,
+    columns: &[Column],
+    matches: impl Fn(&[Attribute]) -> bool,
+) -> Vec<Column> {
+    let struct_def = match &ast.data {
+        syn::Data::Struct(d) => d,
+        syn::Data::Enum(_) => panic!("Only Structs are supported by WeldsModel"),
+        syn::Data::Union(_) => panic!("Only Structs are supported by WeldsModel"),
+    };
+    struct_def
+        .fields
+        .iter()
+        .filter(|x| !is_welds_full_ignore(&x.attrs))
+        .filter(|x| matches(&x.attrs))
+        .filter_map(|f| f.ident.as_ref())
+        .filter_map(|ident| columns.iter().find(|c| &c.field == ident))
+        .cloned()
+        .collect()
+}
+
+/// The fields with #[welds(validate(...))] rules
+pub(crate) fn get_validations(
+    ast: &syn::DeriveInput,
+    columns: &[Column],
+) -> Result<Vec<Validation>> {
+    let struct_def = match &ast.data {
+        syn::Data::Struct(d) => d,
+        syn::Data::Enum(_) => panic!("Only Structs are supported by WeldsModel"),
+        syn::Data::Union(_) => panic!("Only Structs are supported by WeldsModel"),
+    };
+    let mut validations = Vec::default();
+    for f in &struct_def.fields {
+        let rules: Vec<syn::Meta> = welds_meta(&f.attrs)
+            .iter()
+            .flat_map(as_metalist_nested_meta)
+            .filter(|m| m.path().is_ident("validate"))
+            .filter_map(|m| as_metalist_ref(&m).map(as_metalist_nested_meta))
+            .flatten()
+            .collect();
+        if rules.is_empty() {
+            continue;
+        }
+        let column = f
+            .ident
+            .as_ref()
+            .and_then(|ident| columns.iter().find(|c| &c.field == ident));
+        let column = match column {
+            Some(column) => column.clone(),
+            None => return Err("#[welds(validate(..))] can only be used on columns".to_owned()),
+        };
+        let rules: Result<Vec<Rule>> = rules.iter().map(Rule::new).collect();
+        validations.push(Validation {
+            column,
+            rules: rules?,
+        });
+    }
+    Ok(validations)
+}
+
 fn read_rename(field: &Field) -> Option<String> {
-    let metas: Vec<_> = field
-        .attrs
+    read_rename_attrs(&field.attrs)
+}
+
+/// The name from a #[welds(rename = "name")]
+pub(crate) fn read_rename_attrs(attrs: &[Attribute]) -> Option<String> {
+    let metas: Vec<_> = attrs
         .iter()
         .filter(|a| a.path().is_ident("welds"))
         .filter_map(|a| a.meta.require_list().ok())
         .collect();
     // Read out the inner meta from [welds(this, and_this)]
-    let inners: Vec<syn::Meta> = metas.into_iter().flat_map(as_metalist_nested_meta).collect();
+    let inners: Vec<syn::Meta> = metas
+        .into_iter()
+        .flat_map(as_metalist_nested_meta)
+        .collect();
     // find the first rename="name"
     let db_col_name: Option<String> = inners
         .iter()
@@ -104,6 +302,21 @@ fn read_rename(field: &Field) -> Option<String> {
     db_col_name
 }
 
+/// The codec from a #[welds(with = "path")]
+fn read_codec(field: &Field) -> Option<Type> {
+    welds_meta(&field.attrs)
+        .iter()
+        .flat_map(as_metalist_nested_meta)
+        .filter_map(|m| as_meta_namevalue_ref(&m).cloned())
+        .filter(|m| m.path.is_ident("with"))
+        .filter_map(|nv| lit_as_litstr(&nv.value).cloned())
+        .map(|x| {
+            x.parse::<Type>()
+                .expect("#[welds(with = \"path\")] must be the path to a codec")
+        })
+        .next()
+}
+
 /// returns the `inner_type`:  the `T` type inside the `Option<T>`
 fn as_option_inner(ftype: &Type) -> Option<&Type> {
     let tp = as_typepath(ftype)?;
@@ -169,12 +382,41 @@ pub(crate) fn get_relations(ast: &syn::DeriveInput) -> Result<Vec<Relation>> {
         .collect();
     let mut relations5 = relations5?;
 
+    let relations6: Result<Vec<_>> = inners
+        .iter()
+        .filter_map(|m| as_metalist_ref(m))
+        .filter(|m| m.path.is_ident("ManyToMany"))
+        .map(Relation::new_many_to_many)
+        .collect();
+    let mut relations6 = relations6?;
+
+    let relations7: Result<Vec<_>> = inners
+        .iter()
+        .filter_map(|m| as_metalist_ref(m))
+        .filter(|m| {
+            m.path.is_ident("HasManyPolymorphic") || m.path.is_ident("BelongsToPolymorphic")
+        })
+        .map(Relation::new_polymorphic)
+        .collect();
+    let mut relations7 = relations7?;
+
+    let relations8: Result<Vec<_>> = inners
+        .iter()
+        .filter_map(|m| as_metalist_ref(m))
+        .filter(|m| m.path.is_ident("HasManyThrough"))
+        .map(Relation::new_has_many_through)
+        .collect();
+    let mut relations8 = relations8?;
+
     let relations: Vec<_> = relations1
         .drain(..)
         .chain(relations2.drain(..))
         .chain(relations3.drain(..))
         .chain(relations4.drain(..))
         .chain(relations5.drain(..))
+        .chain(relations6.drain(..))
+        .chain(relations7.drain(..))
+        .chain(relations8.drain(..))
         .flatten()
         .collect();
 
@@ -226,6 +468,26 @@ pub(crate) fn get_hooks(ast: &syn::DeriveInput) -> Result<Vec<Hook>> {
         .map(|m| Hook::new(m, HookKind::AfterDelete))
         .collect();
 
+    // hooks for bulk_insert, UpdateBuilder::run and QueryBuilder::delete
+    let bulk_kinds = [
+        ("BeforeBulkInsert", HookKind::BeforeBulkInsert),
+        ("AfterBulkInsert", HookKind::AfterBulkInsert),
+        ("BeforeBulkUpdate", HookKind::BeforeBulkUpdate),
+        ("AfterBulkUpdate", HookKind::AfterBulkUpdate),
+        ("BeforeBulkDelete", HookKind::BeforeBulkDelete),
+        ("AfterBulkDelete", HookKind::AfterBulkDelete),
+    ];
+    let mut bulk = Vec::default();
+    for (name, kind) in bulk_kinds {
+        let hooks: Result<Vec<_>> = inners
+            .iter()
+            .filter_map(|m| as_metalist_ref(m))
+            .filter(|m| m.path.is_ident(name))
+            .map(|m| Hook::new(m, kind))
+            .collect();
+        bulk.push(hooks?);
+    }
+
     let hooks: Vec<_> = vec![
         before_create?,
         after_create?,
@@ -235,6 +497,7 @@ pub(crate) fn get_hooks(ast: &syn::DeriveInput) -> Result<Vec<Hook>> {
         after_delete?,
     ]
     .drain(..)
+    .chain(bulk)
     .flatten()
     .collect();
     Ok(hooks)
@@ -295,6 +558,12 @@ pub(crate) fn get_readonly(ast: &syn::DeriveInput) -> bool {
     inners.iter().any(|m| m.path().is_ident("readonly"))
 }
 
+pub(crate) fn get_audited(ast: &syn::DeriveInput) -> bool {
+    let metas = welds_meta(&ast.attrs);
+    // Read out the inner meta from [welds(this, and_this)]
+    let inners: Vec<syn::Meta> = metas.iter().flat_map(as_metalist_nested_meta).collect();
+    inners.iter().any(|m| m.path().is_ident("audited"))
+}
 
 fn as_metalist_ref(meta: &syn::Meta) -> Option<&syn::MetaList> {
     match meta {
@@ -357,10 +626,18 @@ fn welds_ignores(attrs: &[Attribute]) -> Vec<Ignores> {
     if is_welds_full_ignore(attrs) {
         return vec![Ignores::Select, Ignores::Update, Ignores::Insert];
     }
-    if is_welds_col_readonly(attrs) {
+    if is_welds_col_readonly(attrs)
+        || is_welds_flag(attrs, "generated")
+        || is_welds_timestamp_db_default(attrs)
+    {
         return vec![Ignores::Update, Ignores::Insert];
     }
-    welds_ignore_subs(attrs)
+    let mut ignores = welds_ignore_subs(attrs);
+    // The database owns the value once the row exists
+    if is_welds_flag(attrs, "db_default") {
+        ignores.push(Ignores::Update);
+    }
+    ignores
 }
 
 fn is_welds_full_ignore(attrs: &[Attribute]) -> bool {
@@ -422,6 +699,29 @@ fn is_welds_col_readonly(attrs: &[Attribute]) -> bool {
         .any(|m| m.path().is_ident("readonly"))
 }
 
+/// #[welds(created_at(db_default))] or #[welds(updated_at(db_default))]
+fn is_welds_timestamp_db_default(attrs: &[Attribute]) -> bool {
+    let metas = welds_meta(attrs);
+    metas
+        .iter()
+        .flat_map(as_metalist_nested_meta)
+        .filter(|m| m.path().is_ident("created_at") || m.path().is_ident("updated_at"))
+        .flat_map(|m| match m {
+            syn::Meta::List(l) => Some(l),
+            _ => None,
+        })
+        .flat_map(|m| as_metalist_nested_meta(&m))
+        .any(|m| m.path().is_ident("db_default"))
+}
+
+fn is_welds_flag(attrs: &[Attribute], flag: &str) -> bool {
+    let metas = welds_meta(attrs);
+    metas
+        .iter()
+        .flat_map(as_metalist_nested_meta)
+        .any(|m| m.path().is_ident(flag))
+}
+
 fn is_welds_pk(attrs: &[Attribute]) -> bool {
     let metas = welds_meta(attrs);
     metas
@@ -461,4 +761,71 @@ mod tests {
         assert!(subs.contains(&Ignores::Select));
         assert!(subs.contains(&Ignores::Update));
     }
+    #[test]
+    fn should_read_the_flatten_prefix() {
+        let attr1: Attribute = parse_quote!(#[welds(flatten)]);
+        assert_eq!(read_flatten(&[attr1]), Some(String::default()));
+        let attr2: Attribute = parse_quote!(#[welds(flatten(prefix = "billing_"))]);
+        assert_eq!(read_flatten(&[attr2]), Some("billing_".to_owned()));
+        let attr3: Attribute = parse_quote!(#[welds(rename = "x")]);
+        assert_eq!(read_flatten(&[attr3]), None);
+    }
+
+    #[test]
+    fn should_read_validation_rules() {
+        let ast: syn::DeriveInput = parse_quote! {
+            struct User {
+                #[welds(validate(not_empty, length(max = 50), regex = "^a"))]
+                name: String,
+                #[welds(validate(range(min = 0)))]
+                age: Option<i32>,
+                other: i32,
+            }
+        };
+        let validations = get_validations(&ast, &get_columns(&ast)).unwrap();
+        assert_eq!(validations.len(), 2);
+        assert_eq!(validations[0].rules.len(), 3);
+        assert!(validations[1].column.is_option);
+
+        let ast: syn::DeriveInput = parse_quote! {
+            struct User {
+                #[welds(validate(length))]
+                name: String,
+            }
+        };
+        assert!(get_validations(&ast, &get_columns(&ast)).is_err());
+
+        let ast: syn::DeriveInput = parse_quote! {
+            struct User {
+                #[welds(validate(regex = "^(a"))]
+                name: String,
+            }
+        };
+        assert!(get_validations(&ast, &get_columns(&ast)).is_err());
+    }
+
+    #[test]
+    fn should_reject_an_optional_version() {
+        let ast: syn::DeriveInput = parse_quote! {
+            struct Account {
+                #[welds(version)]
+                lock_version: Option<i32>,
+            }
+        };
+        assert!(get_version(&ast, &get_columns(&ast)).is_err());
+    }
+
+    #[test]
+    fn should_only_insert_db_default_columns() {
+        let attr1: Attribute = parse_quote!(#[welds(db_default)]);
+        let ignores = welds_ignores(&[attr1]);
+        assert_eq!(ignores, vec![Ignores::Update]);
+    }
+
+    #[test]
+    fn should_never_write_generated_columns() {
+        let attr1: Attribute = parse_quote!(#[welds(generated)]);
+        let ignores = welds_ignores(&[attr1]);
+        assert_eq!(ignores, vec![Ignores::Update, Ignores::Insert]);
+    }
 }
@@ -0,0 +1,85 @@
+use crate::column::Column;
+use crate::info::Info;
+use proc_macro2::TokenStream;
+use quote::quote;
+
+pub(crate) fn write(info: &Info) -> TokenStream {
+    let def = &info.defstruct;
+    let wp = &info.welds_path;
+
+    let created_at = write_touch(info, &info.created_at);
+    let updated_at = write_touch(info, &info.updated_at);
+
+    quote! {
+        impl #wp::model_traits::AutoTimestamps for #def {
+            fn touch_created_at(&mut self) {
+                #created_at
+            }
+            fn touch_updated_at(&mut self) {
+                #updated_at
+            }
+        }
+    }
+}
+
+fn write_touch(info: &Info, col: &Option<Column>) -> TokenStream {
+    let wp = &info.welds_path;
+    let col = match col {
+        Some(col) => col,
+        None => return quote! {},
+    };
+    let field = &col.field;
+    if col.is_option {
+        quote! { self.#field = Some(#wp::model_traits::Timestamp::now()); }
+    } else {
+        quote! { self.#field = #wp::model_traits::Timestamp::now(); }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_write_nothing_without_timestamp_columns() {
+        let info = Info::mock().add_pk("id", "i64");
+        let ts = write(&info);
+        let code = ts.to_string();
+
+        let expected: &str = r#"
+            impl welds::model_traits::AutoTimestamps for Mock {
+                fn touch_created_at(&mut self) { }
+                fn touch_updated_at(&mut self) { }
+            }
+        "#;
+
+        assert_eq!(cleaned(&code), cleaned(expected));
+    }
+
+    #[test]
+    fn should_set_the_timestamp_columns_to_now() {
+        let info = Info::mock()
+            .add_pk("id", "i64")
+            .add_created_at("created_at", "DateTime<Utc>", false)
+            .add_updated_at("updated_at", "DateTime<Utc>", true);
+        let ts = write(&info);
+        let code = ts.to_string();
+
+        let expected: &str = r#"
+            impl welds::model_traits::AutoTimestamps for Mock {
+                fn touch_created_at(&mut self) {
+                    self.created_at = welds::model_traits::Timestamp::now();
+                }
+                fn touch_updated_at(&mut self) {
+                    self.updated_at = Some(welds::model_traits::Timestamp::now());
+                }
+            }
+        "#;
+
+        assert_eq!(cleaned(&code), cleaned(expected));
+    }
+
+    fn cleaned(input: &str) -> String {
+        input.chars().filter(|c| !c.is_whitespace()).collect()
+    }
+}
@@ -21,6 +21,22 @@ pub(crate) fn write(info: &Info) -> TokenStream {
         .map(|x| default_fields(info, x))
         .collect();
 
+    // The columns of #[welds(flatten)] fields are grouped under the field. `c.address.city`
+    let wp = &info.welds_path;
+    let fields = fields.into_iter().chain(info.embeds.iter().map(|e| {
+        let name = &e.field;
+        let embedded = e.embedded(wp);
+        quote! { pub #name: #embedded::Schema }
+    }));
+    let default_fields = default_fields
+        .into_iter()
+        .chain(info.embeds.iter().map(|e| {
+            let name = &e.field;
+            let embedded = e.embedded(wp);
+            let prefix = e.prefix(wp);
+            quote! { #name: #embedded::schema(#prefix) }
+        }));
+
     quote! {
 
         #[derive(Copy,Clone)]
@@ -43,14 +59,22 @@ fn def_field(info: &Info, col: &Column) -> TokenStream {
     let name = &col.field;
     let wp = &info.welds_path;
     let type_inner = &col.field_type;
-    let ty = quote! { #type_inner };
-    let clause = get_clause(type_inner, col.is_option);
+    let clause = get_clause(col);
+    // Columns with a codec are filtered by the value stored in the database
+    let ty = match &col.codec {
+        Some(codec) => quote! { <#codec as #wp::connections::codec::Codec<#type_inner>>::Db },
+        None => quote! { #type_inner },
+    };
     let full_type = quote! { #wp::query::clause::#clause<#ty> };
     quote! { pub #name: #full_type }
 }
 
-fn get_clause(ty: &syn::Type, nullable: bool) -> TokenStream {
-    let clasename = crate::utils::get_clause(ty, nullable);
+pub(crate) fn get_clause(col: &Column) -> TokenStream {
+    let clasename = match col.codec {
+        Some(_) if col.is_option => "BasicOpt".to_owned(),
+        Some(_) => "Basic".to_owned(),
+        None => crate::utils::get_clause(&col.field_type, col.is_option),
+    };
     let id = Ident::new(clasename.as_str(), Span::call_site());
     quote! { #id }
 }
@@ -58,8 +82,7 @@ fn get_clause(ty: &syn::Type, nullable: bool) -> TokenStream {
 fn default_fields(info: &Info, col: &Column) -> TokenStream {
     let wp = &info.welds_path;
     let name = &col.field;
-    let type_inner = &col.field_type;
-    let clause = get_clause(type_inner, col.is_option);
+    let clause = get_clause(col);
     let dbname = col.dbname.as_str();
     let fieldname: String = col.field.to_string();
     quote! { #name: #wp::query::clause::#clause::new(#dbname, #fieldname) }
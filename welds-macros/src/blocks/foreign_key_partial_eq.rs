@@ -19,10 +19,12 @@ pub(crate) fn write(info: &Info) -> TokenStream {
     // restruct the value T for ALL type. That would mean PartialEq for need to be true for all
 
     // find all the FKs we need to support.
+    // polymorphic relationships also compare the column holding the type
     let fk_fields: Vec<_> = info
         .relations
         .iter()
-        .map(|r| r.foreign_key_db.as_str())
+        .flat_map(|r| std::iter::once(&r.foreign_key_db).chain(&r.type_column_db))
+        .map(|fk| fk.as_str())
         .collect();
 
     // Map them into columns so we know the Types
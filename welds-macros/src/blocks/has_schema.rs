@@ -11,6 +11,10 @@ pub(crate) fn write(info: &Info) -> TokenStream {
 
         impl #wp::model_traits::HasSchema for #defstruct {
             type Schema = #schemastruct;
+
+            fn tracker() -> Option<fn(&Self) -> #wp::model_traits::Snapshot> {
+                Some(#wp::model_traits::changes::track::<Self>)
+            }
         }
 
     }
@@ -29,6 +33,10 @@ mod tests {
         let expected: &str = r#"
             impl welds::model_traits::HasSchema for Mock {
                 type Schema = MockSchema;
+
+                fn tracker() -> Option<fn(&Self) -> welds::model_traits::Snapshot> {
+                    Some(welds::model_traits::changes::track::<Self>)
+                }
             }
         "#;
         assert_eq!(cleaned(&code), cleaned(expected));
@@ -0,0 +1,107 @@
+use crate::info::Info;
+use proc_macro2::TokenStream;
+use quote::quote;
+
+pub(crate) fn write(info: &Info) -> TokenStream {
+    let def = &info.defstruct;
+    let wp = &info.welds_path;
+
+    let (column, bump, restore) = match &info.version {
+        Some(col) => {
+            let dbname = col.dbname.as_str();
+            let field = &col.field;
+            let ty = &col.field_type;
+            (
+                quote! { Some(#dbname) },
+                quote! {
+                    let next = #wp::model_traits::NextVersion::next_version(&self.#field);
+                    let old = std::mem::replace(&mut self.#field, next);
+                    Some(Box::new(old))
+                },
+                quote! {
+                    if let Ok(old) = old.into_any().downcast::<#ty>() {
+                        self.#field = *old;
+                    }
+                },
+            )
+        }
+        None => (quote! { None }, quote! { None }, quote! {}),
+    };
+
+    quote! {
+        impl #wp::model_traits::HasVersion for #def {
+            fn version_column() -> Option<&'static str> {
+                #column
+            }
+            fn bump_version(&mut self) -> Option<Box<dyn #wp::model_traits::version::VersionValue>> {
+                #bump
+            }
+            #[allow(unused_variables)]
+            fn restore_version(&mut self, old: Box<dyn #wp::model_traits::version::VersionValue>) {
+                #restore
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_write_none_without_a_version_column() {
+        let info = Info::mock().add_pk("id", "i64");
+        let ts = write(&info);
+        let code = ts.to_string();
+
+        let expected: &str = r#"
+            impl welds::model_traits::HasVersion for Mock {
+                fn version_column() -> Option<&'static str> {
+                    None
+                }
+                fn bump_version(&mut self) -> Option<Box<dyn welds::model_traits::version::VersionValue>> {
+                    None
+                }
+                #[allow(unused_variables)]
+                fn restore_version(&mut self, old: Box<dyn welds::model_traits::version::VersionValue>) {
+                }
+            }
+        "#;
+
+        assert_eq!(cleaned(&code), cleaned(expected));
+    }
+
+    #[test]
+    fn should_bump_the_version_column() {
+        let info = Info::mock()
+            .add_pk("id", "i64")
+            .add_version("lock_version", "i32");
+        let ts = write(&info);
+        let code = ts.to_string();
+
+        let expected: &str = r#"
+            impl welds::model_traits::HasVersion for Mock {
+                fn version_column() -> Option<&'static str> {
+                    Some("lock_version")
+                }
+                fn bump_version(&mut self) -> Option<Box<dyn welds::model_traits::version::VersionValue>> {
+                    let next = welds::model_traits::NextVersion::next_version(&self.lock_version);
+                    let old = std::mem::replace(&mut self.lock_version, next);
+                    Some(Box::new(old))
+                }
+                #[allow(unused_variables)]
+                fn restore_version(&mut self, old: Box<dyn welds::model_traits::version::VersionValue>) {
+                    if let Ok(old) = old.into_any().downcast::<i32>() {
+                        self.lock_version = *old;
+                    }
+                }
+            }
+        "#;
+
+        assert_eq!(cleaned(&code), cleaned(expected));
+    }
+
+    fn cleaned(input: &str) -> String {
+        input.chars().filter(|c| !c.is_whitespace()).collect()
+    }
+}
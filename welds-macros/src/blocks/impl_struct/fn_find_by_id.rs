@@ -10,8 +10,15 @@ pub(crate) fn write(info: &Info) -> TokenStream {
         return quote!();
     }
 
-    let id_params: Vec<_> = pks.iter().map(id_param).collect();
-    let id_params = quote! { #(#id_params),* };
+    // A composite primary key is passed in as a tuple, in the order the keys are declared
+    let id_params = match pks {
+        [pk] => id_param(pk),
+        _ => {
+            let names = pks.iter().map(|c| &c.field);
+            let types = pks.iter().map(|c| &c.field_type);
+            quote! { (#(#names),*): (#(impl Into<#types>),*) }
+        }
+    };
 
     let converts: Vec<_> = pks.iter().map(convert).collect();
     let converts = quote! {#(#converts)* };
@@ -66,3 +73,20 @@ fn convert(col: &Column) -> TokenStream {
     let ty = &col.field_type;
     quote! { let #name: #ty = #name.into(); }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_take_a_tuple_for_a_composite_key() {
+        let info = Info::mock().add_pk("a", "i32").add_pk("b", "String");
+        let code = write(&info).to_string();
+        let expected = "(a,b):(implInto<i32>,implInto<String>)";
+        assert!(cleaned(&code).contains(expected), "{}", code);
+    }
+
+    fn cleaned(input: &str) -> String {
+        input.chars().filter(|c| !c.is_whitespace()).collect()
+    }
+}
@@ -11,6 +11,10 @@ pub(crate) fn write(info: &Info) -> TokenStream {
             let name = &c.field;
             quote! { #name: Default::default() }
         })
+        .chain(info.embeds.iter().map(|e| {
+            let name = &e.field;
+            quote! { #name: Default::default() }
+        }))
         .collect();
     let cols = quote! { #(#cols),* };
 
@@ -54,8 +54,8 @@ fn write_link_unlink(info: &Info, relation_a: &Relation, relation_b: &Relation)
             pub #async_token fn link(
                 model_a: &mut #wp::state::DbState<#struct_a>,
                 model_b: &mut #wp::state::DbState<#struct_b>,
-                client: &dyn Client,
-            ) -> welds::errors::Result<()> {
+                client: &dyn #wp::Client,
+            ) -> #wp::errors::Result<()> {
                 // save if needed
                 if model_a.db_status() != #wp::state::DbStatus::NotModified {
                     model_a.save(client)#await_token?;
@@ -71,8 +71,8 @@ fn write_link_unlink(info: &Info, relation_a: &Relation, relation_b: &Relation)
             pub #async_token fn unlink(
                 model_a: &#struct_a,
                 model_b: &#struct_b,
-                client: &dyn Client,
-            ) -> welds::errors::Result<()> {
+                client: &dyn #wp::Client,
+            ) -> #wp::errors::Result<()> {
                 let a: &#struct_a = model_a;
                 let b: &#struct_b = model_b;
                 #wp::query::link::delete::<#model_struct, _, _>(client, a, b)#await_token?;
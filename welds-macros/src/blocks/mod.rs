@@ -6,10 +6,16 @@ mod table_columns;
 mod table_info;
 mod unique_identifier;
 //mod write_bulk_array_to_args;
+mod auto_timestamps;
 mod foreign_key_partial_eq;
+mod has_version;
 mod jointable;
+mod soft_delete;
+mod track_changes;
 mod try_from_row;
 mod update_from_row;
+mod validate;
+mod write_bulk_hooks;
 mod write_col_default_check;
 mod write_hooks;
 mod write_primary_key_value;
@@ -23,11 +29,23 @@ pub(crate) use table_columns::write as table_columns;
 pub(crate) use table_info::write as table_info;
 pub(crate) use unique_identifier::write as unique_identifier;
 //pub(crate) use write_bulk_array_to_args::write as write_bulk_array_to_args;
+pub(crate) use auto_timestamps::write as auto_timestamps;
 pub(crate) use foreign_key_partial_eq::write as foreign_key_partial_eq;
+pub(crate) use has_version::write as has_version;
 pub(crate) use jointable::write as jointable;
+pub(crate) use soft_delete::write as soft_delete;
+pub(crate) use track_changes::write as track_changes;
 pub(crate) use try_from_row::write as try_from_row;
 pub(crate) use update_from_row::write as update_from_row;
+pub(crate) use validate::write as validate;
+pub(crate) use write_bulk_hooks::write as write_bulk_hooks;
 pub(crate) use write_col_default_check::write as write_col_default_check;
 pub(crate) use write_hooks::write as write_hooks;
 pub(crate) use write_primary_key_value::write as write_primary_key_value;
 pub(crate) use write_to_args::write as write_to_args;
+
+// Shared with #[derive(WeldsEmbed)]
+pub(crate) use define_schema::get_clause;
+pub(crate) use try_from_row::read_column;
+pub(crate) use write_col_default_check::col_switch;
+pub(crate) use write_to_args::write_col_normal;
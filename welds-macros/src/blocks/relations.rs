@@ -15,6 +15,7 @@ pub(crate) fn write(info: &Info) -> TokenStream {
     let struct_fields = quote! { #(#struct_fields), * };
     let default_fields: Vec<_> = relations.iter().map(|x| defaultdef(info, x)).collect();
     let default_fields = quote! { #(#default_fields), * };
+    let describes: Vec<_> = relations.iter().map(|x| describedef(info, x)).collect();
 
     // panic when code is invalid to give nice errors
     compiletime_asserts(info);
@@ -24,6 +25,14 @@ pub(crate) fn write(info: &Info) -> TokenStream {
         // build the HasRelations struct used for lambda selection of relationships
         impl #wp::relations::HasRelations for #defstruct {
             type Relation = #relations_struct;
+
+            fn describe_relation(ship: &dyn ::std::any::Any) -> Option<#wp::relations::describe::RelationInfo> {
+                #[allow(unused_imports)]
+                use #wp::relations::describe::*;
+                let relations = <#relations_struct as ::std::default::Default>::default();
+                #(#describes)*
+                None
+            }
         }
 
         pub struct #relations_struct {
@@ -43,13 +52,44 @@ pub(crate) fn write(info: &Info) -> TokenStream {
 
 // write the definition of a HasRelations field
 fn fielddef(info: &Info, relation: &Relation) -> TokenStream {
+    let field = &relation.field;
+    let ship = shiptype(info, relation);
+    quote! {
+        pub #field: #ship
+    }
+}
+
+// the type of the HasRelations field
+fn shiptype(info: &Info, relation: &Relation) -> TokenStream {
     let wp = &info.welds_path;
     let model_struct = &info.defstruct;
     let kind = &relation.kind;
+    let other = &relation.foreign_struct;
+    if let Some(link) = &relation.through {
+        return quote! { #wp::relations::#kind<#model_struct, #other, #link> };
+    }
+    quote! { #wp::relations::#kind<#model_struct, #other> }
+}
+
+// write the lookup of a HasRelations field in describe_relation
+fn describedef(info: &Info, relation: &Relation) -> TokenStream {
+    let model_struct = &info.defstruct;
     let field = &relation.field;
+    let name = field.to_string();
+    let name = name.trim_start_matches("r#");
     let other = &relation.foreign_struct;
+    let ship = shiptype(info, relation);
     quote! {
-        pub #field: #wp::relations::#kind<#model_struct, #other>
+        if let Some(x) = ship.downcast_ref::<#ship>() {
+            if *x == relations.#field {
+                return Some(RelationInfo::new(
+                    #name,
+                    (&&Probe::<(#model_struct, #other, #ship)>::new()).compare_fn(),
+                    (&&Probe::<#other>::new()).json_fn(),
+                    (&&Probe::<#other>::new()).describe_fn(),
+                ));
+            }
+        }
     }
 }
 
@@ -70,6 +110,33 @@ fn defaultdef(info: &Info, relation: &Relation) -> TokenStream {
                 #field: #wp::relations::#kind::using(#self_key, #fk)
             }
         }
+        "ManyToMany" => {
+            quote! {
+                #field: #wp::relations::#kind::new()
+            }
+        }
+        "HasManyThrough" => {
+            let through_key = relation
+                .through_key_db
+                .clone()
+                .expect("Through key should have value");
+            quote! {
+                #field: #wp::relations::#kind::using(#through_key, #fk)
+            }
+        }
+        "HasManyPolymorphic" | "BelongsToPolymorphic" => {
+            let type_column = relation
+                .type_column_db
+                .clone()
+                .expect("Type column should have value");
+            let type_name = match &relation.type_name {
+                Some(name) => quote! { Some(#name) },
+                None => quote! { None },
+            };
+            quote! {
+                #field: #wp::relations::#kind::using(#fk, #type_column, #type_name)
+            }
+        }
         _ => {
             quote! {
                 #field: #wp::relations::#kind::using(#fk)
@@ -105,6 +172,43 @@ fn compiletime_asserts(info: &Info) {
                 defstruct,
             );
         }
+        // Compile time check that the columns exists.
+        if &relation_kind_name == "BelongsToPolymorphic" {
+            let type_column = relation.type_column_db.as_deref().unwrap_or_default();
+            for column in [relation.foreign_key_db.as_str(), type_column] {
+                let found = columns.iter().find(|x| x.dbname == column);
+                assert!(
+                    found.is_some(),
+                    "The model {} has a BelongsToPolymorphic relationships using the column {}, but this column is not defined on the model",
+                    defstruct,
+                    column
+                );
+            }
+        }
+        // Compile time check that the pk column exists.
+        if &relation_kind_name == "HasManyPolymorphic" {
+            assert!(
+                !info.pks.is_empty(),
+                "The model {} has a HasManyPolymorphic relationships defined, but no primary_key column is defined",
+                defstruct,
+            );
+        }
+        // Compile time check that the pk column exists.
+        if &relation_kind_name == "HasManyThrough" {
+            assert!(
+                info.pks.len() == 1,
+                "The model {} has a HasManyThrough relationships defined, but doesn't have exactly one primary_key column",
+                defstruct,
+            );
+        }
+        // Compile time check that the pk column exists.
+        if &relation_kind_name == "ManyToMany" {
+            assert!(
+                info.pks.len() == 1,
+                "The model {} has a ManyToMany relationships defined, but doesn't have exactly one primary_key column",
+                defstruct,
+            );
+        }
         // Compile time check that the column exists.
         if &relation_kind_name == "HasOne" {
             assert!(
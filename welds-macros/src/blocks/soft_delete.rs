@@ -0,0 +1,107 @@
+use crate::info::Info;
+use proc_macro2::TokenStream;
+use quote::quote;
+
+pub(crate) fn write(info: &Info) -> TokenStream {
+    let def = &info.defstruct;
+    let wp = &info.welds_path;
+
+    let (deleted, restored, unmark) = match &info.soft_delete {
+        Some(col) => {
+            let field = &col.field;
+            let ty = &col.field_type;
+            let ty = match col.is_option {
+                true => quote! { Option<#ty> },
+                false => quote! { #ty },
+            };
+            (
+                quote! {
+                    let old = std::mem::replace(&mut self.#field, #wp::model_traits::SoftDeleteValue::deleted());
+                    Some(Box::new(old))
+                },
+                quote! {
+                    let old = std::mem::replace(&mut self.#field, #wp::model_traits::SoftDeleteValue::restored());
+                    Some(Box::new(old))
+                },
+                quote! {
+                    if let Ok(old) = old.downcast::<#ty>() {
+                        self.#field = *old;
+                    }
+                },
+            )
+        }
+        None => (quote! { None }, quote! { None }, quote! {}),
+    };
+
+    quote! {
+        impl #wp::model_traits::SoftDelete for #def {
+            fn mark_deleted(&mut self) -> Option<Box<dyn std::any::Any + Send>> {
+                #deleted
+            }
+            fn mark_restored(&mut self) -> Option<Box<dyn std::any::Any + Send>> {
+                #restored
+            }
+            #[allow(unused_variables)]
+            fn unmark(&mut self, old: Box<dyn std::any::Any + Send>) {
+                #unmark
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_write_nothing_without_a_soft_delete_column() {
+        let info = Info::mock().add_pk("id", "i64");
+        let ts = write(&info);
+        let code = ts.to_string();
+
+        let expected: &str = r#"
+            impl welds::model_traits::SoftDelete for Mock {
+                fn mark_deleted(&mut self) -> Option<Box<dyn std::any::Any + Send>> { None }
+                fn mark_restored(&mut self) -> Option<Box<dyn std::any::Any + Send>> { None }
+                #[allow(unused_variables)]
+                fn unmark(&mut self, old: Box<dyn std::any::Any + Send>) { }
+            }
+        "#;
+
+        assert_eq!(cleaned(&code), cleaned(expected));
+    }
+
+    #[test]
+    fn should_set_the_soft_delete_column() {
+        let info = Info::mock()
+            .add_pk("id", "i64")
+            .add_soft_delete("deleted", "bool", false);
+        let ts = write(&info);
+        let code = ts.to_string();
+
+        let expected: &str = r#"
+            impl welds::model_traits::SoftDelete for Mock {
+                fn mark_deleted(&mut self) -> Option<Box<dyn std::any::Any + Send>> {
+                    let old = std::mem::replace(&mut self.deleted, welds::model_traits::SoftDeleteValue::deleted());
+                    Some(Box::new(old))
+                }
+                fn mark_restored(&mut self) -> Option<Box<dyn std::any::Any + Send>> {
+                    let old = std::mem::replace(&mut self.deleted, welds::model_traits::SoftDeleteValue::restored());
+                    Some(Box::new(old))
+                }
+                #[allow(unused_variables)]
+                fn unmark(&mut self, old: Box<dyn std::any::Any + Send>) {
+                    if let Ok(old) = old.downcast::<bool>() {
+                        self.deleted = *old;
+                    }
+                }
+            }
+        "#;
+
+        assert_eq!(cleaned(&code), cleaned(expected));
+    }
+
+    fn cleaned(input: &str) -> String {
+        input.chars().filter(|c| !c.is_whitespace()).collect()
+    }
+}
@@ -6,9 +6,9 @@ use quote::quote;
 pub(crate) fn write(info: &Info) -> TokenStream {
     let colstruct = write_colstruct(info);
 
-    let read_columns = read_cols(&info.columns);
-    let update_columns = update_cols(&info.columns);
-    let insert_columns = insert_cols(&info.columns);
+    let read_columns = with_embeds(info, read_cols(&info.columns));
+    let update_columns = with_embeds(info, update_cols(&info.columns));
+    let insert_columns = with_embeds(info, insert_cols(&info.columns));
 
     let pks = any_write_cols(&info.pks);
     write_for_db(
@@ -68,6 +68,24 @@ pub(crate) fn write_colstruct(info: &Info) -> TokenStream {
     }
 }
 
+/// Adds the columns of the #[welds(flatten)] fields
+fn with_embeds(info: &Info, columns: TokenStream) -> TokenStream {
+    if info.embeds.is_empty() {
+        return columns;
+    }
+    let wp = &info.welds_path;
+    let embeds = info.embeds.iter().map(|e| {
+        let embedded = e.embedded(wp);
+        let prefix = e.prefix(wp);
+        quote! { cols.extend(#embedded::columns(#prefix)); }
+    });
+    quote! {{
+        let mut cols: Vec<#wp::model_traits::Column> = #columns;
+        #(#embeds)*
+        cols
+    }}
+}
+
 pub(crate) fn read_cols(columns: &[Column]) -> TokenStream {
     let parts: Vec<_> = columns
         .iter()
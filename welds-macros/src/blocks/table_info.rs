@@ -1,5 +1,6 @@
+use crate::column::Column;
 use crate::info::Info;
-use proc_macro2::TokenStream;
+use proc_macro2::{Ident, Span, TokenStream};
 use quote::quote;
 
 pub(crate) fn write(info: &Info) -> TokenStream {
@@ -14,6 +15,11 @@ pub(crate) fn write(info: &Info) -> TokenStream {
 
     let parts: Vec<_> = parts.drain(..).rev().collect();
     let schema = &info.schemastruct;
+    let soft_delete = write_soft_delete_column(info);
+    let created_at = write_timestamp_column(info, &info.created_at, "created_at_column");
+    let updated_at = write_timestamp_column(info, &info.updated_at, "updated_at_column");
+    let db_defaults = write_column_names(&info.db_defaults, "db_default_columns");
+    let db_managed = write_column_names(&info.db_managed, "db_managed_columns");
 
     quote! {
 
@@ -21,11 +27,65 @@ pub(crate) fn write(info: &Info) -> TokenStream {
             fn identifier() -> &'static [&'static str] {
                 &[#(#parts),*]
             }
+            #soft_delete
+            #created_at
+            #updated_at
+            #db_defaults
+            #db_managed
         }
 
     }
 }
 
+fn write_soft_delete_column(info: &Info) -> TokenStream {
+    let wp = &info.welds_path;
+    let col = match &info.soft_delete {
+        Some(col) => col,
+        None => return quote!(),
+    };
+    let dbname = col.dbname.as_str();
+    let ty = &col.field_type;
+    let ty = if col.is_option {
+        quote! { Option<#ty> }
+    } else {
+        quote! { #ty }
+    };
+    quote! {
+        fn soft_delete_column() -> Option<#wp::model_traits::SoftDeleteColumn> {
+            Some(#wp::model_traits::SoftDeleteColumn::new::<#ty>(#dbname))
+        }
+    }
+}
+
+fn write_timestamp_column(info: &Info, col: &Option<Column>, method: &str) -> TokenStream {
+    let wp = &info.welds_path;
+    let col = match col {
+        Some(col) => col,
+        None => return quote!(),
+    };
+    let method = Ident::new(method, Span::call_site());
+    let dbname = col.dbname.as_str();
+    let ty = &col.field_type;
+    quote! {
+        fn #method() -> Option<#wp::model_traits::TimestampColumn> {
+            Some(#wp::model_traits::TimestampColumn::new::<#ty>(#dbname))
+        }
+    }
+}
+
+fn write_column_names(cols: &[Column], method: &str) -> TokenStream {
+    if cols.is_empty() {
+        return quote!();
+    }
+    let method = Ident::new(method, Span::call_site());
+    let names = cols.iter().map(|c| c.dbname.as_str());
+    quote! {
+        fn #method() -> &'static [&'static str] {
+            &[#(#names),*]
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -46,6 +106,78 @@ mod tests {
         assert_eq!(cleaned(&code), cleaned(expected), "CODE: \n\n{}\n\n", code);
     }
 
+    #[test]
+    fn should_write_the_soft_delete_column() {
+        let info =
+            Info::mock()
+                .add_pk("id", "i64")
+                .add_soft_delete("deleted_at", "DateTime<Utc>", true);
+        let ts = write(&info);
+        let code = ts.to_string();
+
+        let expected: &str = r#"
+        impl welds::model_traits::TableInfo for MockSchema {
+            fn identifier() -> &'static [&'static str] {
+                &[ "daschema","datables"]
+            }
+            fn soft_delete_column() -> Option<welds::model_traits::SoftDeleteColumn> {
+                Some(welds::model_traits::SoftDeleteColumn::new::<Option<DateTime<Utc>> >("deleted_at"))
+            }
+        }
+        "#;
+        assert_eq!(cleaned(&code), cleaned(expected), "CODE: \n\n{}\n\n", code);
+    }
+
+    #[test]
+    fn should_write_the_timestamp_columns() {
+        let info = Info::mock()
+            .add_pk("id", "i64")
+            .add_created_at("created_at", "DateTime<Utc>", false)
+            .add_updated_at("updated_at", "DateTime<Utc>", true);
+        let ts = write(&info);
+        let code = ts.to_string();
+
+        let expected: &str = r#"
+        impl welds::model_traits::TableInfo for MockSchema {
+            fn identifier() -> &'static [&'static str] {
+                &[ "daschema","datables"]
+            }
+            fn created_at_column() -> Option<welds::model_traits::TimestampColumn> {
+                Some(welds::model_traits::TimestampColumn::new::<DateTime<Utc> >("created_at"))
+            }
+            fn updated_at_column() -> Option<welds::model_traits::TimestampColumn> {
+                Some(welds::model_traits::TimestampColumn::new::<DateTime<Utc> >("updated_at"))
+            }
+        }
+        "#;
+        assert_eq!(cleaned(&code), cleaned(expected), "CODE: \n\n{}\n\n", code);
+    }
+
+    #[test]
+    fn should_write_the_columns_the_database_writes() {
+        let info = Info::mock()
+            .add_pk("id", "i64")
+            .add_db_default("code", "String", false)
+            .add_generated("total", "i64", false);
+        let ts = write(&info);
+        let code = ts.to_string();
+
+        let expected: &str = r#"
+        impl welds::model_traits::TableInfo for MockSchema {
+            fn identifier() -> &'static [&'static str] {
+                &[ "daschema","datables"]
+            }
+            fn db_default_columns() -> &'static [&'static str] {
+                &["code"]
+            }
+            fn db_managed_columns() -> &'static [&'static str] {
+                &["code", "total"]
+            }
+        }
+        "#;
+        assert_eq!(cleaned(&code), cleaned(expected), "CODE: \n\n{}\n\n", code);
+    }
+
     fn cleaned(input: &str) -> String {
         input.chars().filter(|c| !c.is_whitespace()).collect()
     }
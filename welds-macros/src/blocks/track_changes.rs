@@ -0,0 +1,162 @@
+use crate::column::Column;
+use crate::info::Info;
+use proc_macro2::TokenStream;
+use quote::quote;
+
+pub(crate) fn write(info: &Info) -> TokenStream {
+    let values: Vec<_> = info
+        .columns
+        .iter()
+        .filter(|x| x.updateable)
+        .map(|c| snapshot_value(info, c))
+        .collect();
+    let keys: Vec<_> = info.pks.iter().map(|c| snapshot_value(info, c)).collect();
+
+    write_for_db(info, &values, &keys)
+}
+
+pub(crate) fn snapshot_value(info: &Info, col: &Column) -> TokenStream {
+    let wp = &info.welds_path;
+    let dbname = col.dbname.as_str();
+    let field = &col.field;
+    quote! {
+        (#dbname, (&&#wp::model_traits::changes::FieldProbe(&self.#field)).snapshot_value())
+    }
+}
+
+pub(crate) fn write_for_db(
+    info: &Info,
+    values: &[TokenStream],
+    keys: &[TokenStream],
+) -> TokenStream {
+    let def = &info.defstruct;
+    let wp = &info.welds_path;
+    let mut values = quote! { vec![ #(#values),* ] };
+    if !info.embeds.is_empty() {
+        let embeds = info.embeds.iter().map(|e| {
+            let field = &e.field;
+            let embedded = e.embedded(wp);
+            let prefix = e.prefix(wp);
+            quote! { values.extend(#embedded::snapshot_values(&self.#field, #prefix)); }
+        });
+        values = quote! {{
+            let mut values = #values;
+            #(#embeds)*
+            values
+        }};
+    }
+
+    // models without a primary key use the default, an empty snapshot
+    let primary_key_snapshot = match keys.is_empty() {
+        true => quote! {},
+        false => quote! {
+            fn primary_key_snapshot(&self) -> #wp::model_traits::Snapshot {
+                #[allow(unused_imports)]
+                use #wp::model_traits::changes::{TrackedField, UntrackedField};
+                #wp::model_traits::Snapshot::new(vec![ #(#keys),* ])
+            }
+        },
+    };
+
+    let audit_values = write_audit_values(info);
+
+    quote! {
+        impl #wp::model_traits::TrackChanges for #def {
+            fn snapshot(&self) -> #wp::model_traits::Snapshot {
+                #[allow(unused_imports)]
+                use #wp::model_traits::changes::{TrackedField, UntrackedField};
+                #wp::model_traits::Snapshot::new(#values)
+            }
+            #primary_key_snapshot
+            #audit_values
+        }
+    }
+}
+
+// #[welds(audited)] models write all their columns into the history table
+fn write_audit_values(info: &Info) -> TokenStream {
+    let wp = &info.welds_path;
+    if !info.audited {
+        return quote! {};
+    }
+    let columns = info.columns.iter().map(|c| {
+        let dbname = c.dbname.as_str();
+        let field = &c.field;
+        quote! { values.add(#dbname, &self.#field)?; }
+    });
+    let embeds = info.embeds.iter().map(|e| {
+        let field = &e.field;
+        let name = field.to_string();
+        quote! { values.add(#name, &self.#field)?; }
+    });
+    quote! {
+        const AUDITED: bool = true;
+        fn audit_values(&self) -> Result<Option<String>, #wp::audit::AuditValueError> {
+            let mut values = #wp::audit::AuditValues::new();
+            #(#columns)*
+            #(#embeds)*
+            Ok(Some(values.into_json()))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_snapshot_each_updateable_column() {
+        let info = Info::mock()
+            .add_pk("id", "i64")
+            .add_column("name", "String", true);
+        let ts = write(&info);
+        let code = ts.to_string();
+
+        let expected: &str = r#"
+            impl welds::model_traits::TrackChanges for Mock {
+                fn snapshot(&self) -> welds::model_traits::Snapshot {
+                    #[allow(unused_imports)]
+                    use welds::model_traits::changes::{TrackedField, UntrackedField};
+                    welds::model_traits::Snapshot::new(vec![
+                        ("id", (&&welds::model_traits::changes::FieldProbe(&self.id)).snapshot_value()),
+                        ("name", (&&welds::model_traits::changes::FieldProbe(&self.name)).snapshot_value())
+                    ])
+                }
+                fn primary_key_snapshot(&self) -> welds::model_traits::Snapshot {
+                    #[allow(unused_imports)]
+                    use welds::model_traits::changes::{TrackedField, UntrackedField};
+                    welds::model_traits::Snapshot::new(vec![
+                        ("id", (&&welds::model_traits::changes::FieldProbe(&self.id)).snapshot_value())
+                    ])
+                }
+            }
+        "#;
+
+        assert_eq!(cleaned(&code), cleaned(expected));
+    }
+
+    #[test]
+    fn should_write_all_the_columns_of_audited_models() {
+        let mut info = Info::mock()
+            .add_pk("id", "i64")
+            .add_column("name", "String", true);
+        info.audited = true;
+        let code = write(&info).to_string();
+
+        let expected: &str = r#"
+            const AUDITED: bool = true;
+            fn audit_values(&self) -> Result<Option<String>, welds::audit::AuditValueError> {
+                let mut values = welds::audit::AuditValues::new();
+                values.add("id", &self.id)?;
+                values.add("name", &self.name)?;
+                Ok(Some(values.into_json()))
+            }
+        "#;
+
+        assert!(cleaned(&code).contains(&cleaned(expected)));
+    }
+
+    fn cleaned(input: &str) -> String {
+        input.chars().filter(|c| !c.is_whitespace()).collect()
+    }
+}
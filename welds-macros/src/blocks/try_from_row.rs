@@ -8,9 +8,15 @@ pub(crate) fn write(info: &Info) -> TokenStream {
         .columns
         .iter()
         .filter(|x| x.selectable)
-        .map(setfield)
+        .map(|x| setfield(info, x))
         .collect();
-    let fields = quote! { #(#fields)* };
+    let embeds = info.embeds.iter().map(|e| {
+        let field = &e.field;
+        let embedded = e.embedded(&info.welds_path);
+        let prefix = e.prefix(&info.welds_path);
+        quote! { #field: #embedded::from_row(&row, #prefix)?, }
+    });
+    let fields = quote! { #(#fields)* #(#embeds)* };
 
     // Get all the columns that are ignored
     let ignored: Vec<_> = info.columns.iter().filter(|&x| !x.selectable).collect();
@@ -18,10 +24,22 @@ pub(crate) fn write(info: &Info) -> TokenStream {
     write_for_db(info, &fields, &ignored)
 }
 
-pub(crate) fn setfield(col: &Column) -> TokenStream {
+pub(crate) fn setfield(info: &Info, col: &Column) -> TokenStream {
     let dbname = col.dbname.as_str();
     let field = &col.field;
-    quote! { #field: row.get(#dbname)?, }
+    let value = read_column(&info.welds_path, col, &quote! { #dbname });
+    quote! { #field: #value, }
+}
+
+/// Reads a column out of the row, through its `#[welds(with = "path")]` codec if it has one
+pub(crate) fn read_column(wp: &syn::Path, col: &Column, name: &TokenStream) -> TokenStream {
+    match col.codec(wp) {
+        Some((codec, ty)) => quote! {
+            <#codec as #wp::connections::codec::Codec<#ty>>::decode(row.get(#name)?)
+                .map_err(|err| #wp::connections::Error::ColumnCodec(#name.to_owned(), err.to_string()))?
+        },
+        None => quote! { row.get(#name)? },
+    }
 }
 
 pub(crate) fn write_for_db(
@@ -83,6 +101,60 @@ mod tests {
         assert_eq!(cleaned(&code), cleaned(expected));
     }
 
+    #[test]
+    fn should_decode_codec_columns_through_the_codec() {
+        let info = Info::mock().add_pk("id", "i64").add_codec_column(
+            "total",
+            "Money",
+            false,
+            "MoneyCodec",
+        );
+        let ts = write(&info);
+        let code = ts.to_string();
+
+        let expected: &str = r#"
+            impl TryFrom<welds::Row> for Mock {
+                type Error = welds::WeldsError;
+                fn try_from(row: welds::Row) -> std::result::Result<Self, Self::Error> {
+                    Ok(Mock {
+                        id: row.get("id")?,
+                        total: <MoneyCodec as welds::connections::codec::Codec<Money>>::decode(row.get("total")?)
+                            .map_err(|err| welds::connections::Error::ColumnCodec("total".to_owned(), err.to_string()))?,
+                    })
+                }
+            }
+        "#;
+
+        assert_eq!(cleaned(&code), cleaned(expected));
+    }
+
+    #[test]
+    fn should_read_flattened_fields_from_their_struct() {
+        let info = Info::mock()
+            .add_pk("id", "i64")
+            .add_embed("billing", "Address", "billing_");
+        let ts = write(&info);
+        let code = ts.to_string();
+
+        let expected: &str = r#"
+            impl TryFrom<welds::Row> for Mock {
+                type Error = welds::WeldsError;
+                fn try_from(row: welds::Row) -> std::result::Result<Self, Self::Error> {
+                    Ok(Mock {
+                        id: row.get("id")?,
+                        billing: <Address as welds::model_traits::Embedded>::from_row(&row, {
+                            static PREFIX: welds::model_traits::embedded::Prefix =
+                                welds::model_traits::embedded::Prefix::new("billing_");
+                            &PREFIX
+                        })?,
+                    })
+                }
+            }
+        "#;
+
+        assert_eq!(cleaned(&code), cleaned(expected));
+    }
+
     fn cleaned(input: &str) -> String {
         input.chars().filter(|c| !c.is_whitespace()).collect()
     }
@@ -1,3 +1,4 @@
+use super::try_from_row::read_column;
 use crate::column::Column;
 use crate::info::Info;
 use proc_macro2::TokenStream;
@@ -8,18 +9,25 @@ pub(crate) fn write(info: &Info) -> TokenStream {
         .columns
         .iter()
         .filter(|x| x.selectable)
-        .map(setfield)
+        .map(|x| setfield(info, x))
         .collect();
-    let fields = quote! { #(#fields)* };
+    let embeds = info.embeds.iter().map(|e| {
+        let field = &e.field;
+        let embedded = e.embedded(&info.welds_path);
+        let prefix = e.prefix(&info.welds_path);
+        quote! { #embedded::update_from_row(&mut self.#field, row, #prefix)?; }
+    });
+    let fields = quote! { #(#fields)* #(#embeds)* };
 
     write_for_db(info, &fields)
 }
 
-pub(crate) fn setfield(col: &Column) -> TokenStream {
+pub(crate) fn setfield(info: &Info, col: &Column) -> TokenStream {
     let dbname = col.dbname.as_str();
     let field = &col.field;
+    let value = read_column(&info.welds_path, col, &quote! { #dbname });
     quote! {
-        if row.has(#dbname) { self.#field = row.get(#dbname)?; }
+        if row.has(#dbname) { self.#field = #value; }
     }
 }
 
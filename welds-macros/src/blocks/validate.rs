@@ -0,0 +1,206 @@
+use crate::info::Info;
+use crate::validation::{Rule, Validation};
+use proc_macro2::TokenStream;
+use quote::quote;
+
+pub(crate) fn write(info: &Info) -> TokenStream {
+    let def = &info.defstruct;
+    let wp = &info.welds_path;
+
+    if info.validations.is_empty() {
+        return quote! {
+            impl #wp::model_traits::Validate for #def {
+                fn validate(&self) -> std::result::Result<(), #wp::model_traits::ValidationErrors> {
+                    Ok(())
+                }
+            }
+        };
+    }
+
+    let fields = info.validations.iter().map(|v| write_field(wp, v));
+
+    quote! {
+        impl #wp::model_traits::Validate for #def {
+            fn validate(&self) -> std::result::Result<(), #wp::model_traits::ValidationErrors> {
+                let mut errors = #wp::model_traits::ValidationErrors::new();
+                #(#fields)*
+                errors.into_result()
+            }
+        }
+    }
+}
+
+// checks each rule of a field, Option fields are only checked when they have a value
+fn write_field(wp: &syn::Path, validation: &Validation) -> TokenStream {
+    let field = &validation.column.field;
+    let fieldname = field.to_string();
+    let rules = quote! { #wp::model_traits::validate::rules };
+
+    let checks = validation.rules.iter().map(|rule| {
+        let check = match rule {
+            Rule::NotEmpty => quote! { #rules::not_empty(value) },
+            Rule::Length { min, max } => {
+                let min = as_option(min);
+                let max = as_option(max);
+                quote! { #rules::length(value, #min, #max) }
+            }
+            Rule::Range { min, max } => {
+                let min = as_option(min);
+                let max = as_option(max);
+                quote! { #rules::range(value, #min, #max) }
+            }
+            Rule::Regex(pattern) => quote! {{
+                static PATTERN: std::sync::OnceLock<#rules::Regex> = std::sync::OnceLock::new();
+                #rules::regex(value, &PATTERN, #pattern)
+            }},
+            Rule::Custom(func) => quote! { #func(value) },
+        };
+        quote! {
+            if let Err(message) = #check {
+                errors.add(#fieldname, message);
+            }
+        }
+    });
+
+    if validation.column.is_option {
+        quote! {
+            if let Some(value) = &self.#field {
+                #(#checks)*
+            }
+        }
+    } else {
+        quote! {
+            {
+                let value = &self.#field;
+                #(#checks)*
+            }
+        }
+    }
+}
+
+fn as_option(expr: &Option<syn::Expr>) -> TokenStream {
+    match expr {
+        Some(expr) => quote! { Some(#expr) },
+        None => quote! { None },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::validation::Rule;
+    use syn::parse_quote;
+
+    #[test]
+    fn should_write_ok_without_rules() {
+        let info = Info::mock().add_pk("id", "i64");
+        let ts = write(&info);
+        let code = ts.to_string();
+
+        let expected: &str = r#"
+            impl welds::model_traits::Validate for Mock {
+                fn validate(&self) -> std::result::Result<(), welds::model_traits::ValidationErrors> {
+                    Ok(())
+                }
+            }
+        "#;
+
+        assert_eq!(cleaned(&code), cleaned(expected));
+    }
+
+    #[test]
+    fn should_check_each_rule_of_a_field() {
+        let info = Info::mock()
+            .add_pk("id", "i64")
+            .add_validation(
+                "name",
+                "String",
+                false,
+                vec![
+                    Rule::NotEmpty,
+                    Rule::Length {
+                        min: None,
+                        max: Some(parse_quote!(50)),
+                    },
+                ],
+            )
+            .add_validation(
+                "age",
+                "i32",
+                true,
+                vec![
+                    Rule::Range {
+                        min: Some(parse_quote!(0)),
+                        max: None,
+                    },
+                    Rule::Custom(parse_quote!(checks::age)),
+                ],
+            );
+        let ts = write(&info);
+        let code = ts.to_string();
+
+        let expected: &str = r#"
+            impl welds::model_traits::Validate for Mock {
+                fn validate(&self) -> std::result::Result<(), welds::model_traits::ValidationErrors> {
+                    let mut errors = welds::model_traits::ValidationErrors::new();
+                    {
+                        let value = &self.name;
+                        if let Err(message) = welds::model_traits::validate::rules::not_empty(value) {
+                            errors.add("name", message);
+                        }
+                        if let Err(message) = welds::model_traits::validate::rules::length(value, None, Some(50)) {
+                            errors.add("name", message);
+                        }
+                    }
+                    if let Some(value) = &self.age {
+                        if let Err(message) = welds::model_traits::validate::rules::range(value, Some(0), None) {
+                            errors.add("age", message);
+                        }
+                        if let Err(message) = checks::age(value) {
+                            errors.add("age", message);
+                        }
+                    }
+                    errors.into_result()
+                }
+            }
+        "#;
+
+        assert_eq!(cleaned(&code), cleaned(expected));
+    }
+
+    #[test]
+    fn should_compile_each_regex_once_per_field() {
+        let info = Info::mock().add_pk("id", "i64").add_validation(
+            "email",
+            "String",
+            false,
+            vec![Rule::Regex("^[^@]+@[^@]+$".to_owned())],
+        );
+        let ts = write(&info);
+        let code = ts.to_string();
+
+        let expected: &str = r#"
+            impl welds::model_traits::Validate for Mock {
+                fn validate(&self) -> std::result::Result<(), welds::model_traits::ValidationErrors> {
+                    let mut errors = welds::model_traits::ValidationErrors::new();
+                    {
+                        let value = &self.email;
+                        if let Err(message) = {
+                            static PATTERN: std::sync::OnceLock<welds::model_traits::validate::rules::Regex> = std::sync::OnceLock::new();
+                            welds::model_traits::validate::rules::regex(value, &PATTERN, "^[^@]+@[^@]+$")
+                        } {
+                            errors.add("email", message);
+                        }
+                    }
+                    errors.into_result()
+                }
+            }
+        "#;
+
+        assert_eq!(cleaned(&code), cleaned(expected));
+    }
+
+    fn cleaned(input: &str) -> String {
+        input.chars().filter(|c| !c.is_whitespace()).collect()
+    }
+}
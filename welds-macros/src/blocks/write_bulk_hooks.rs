@@ -0,0 +1,168 @@
+use crate::hook::HookKind;
+use crate::info::Info;
+use proc_macro2::TokenStream;
+use quote::quote;
+
+pub(crate) fn write(info: &Info) -> TokenStream {
+    // Unlike the save hooks these are written for readonly models too,
+    // bulk updates and deletes are still allowed on them
+    let wp = &info.welds_path;
+
+    let query = quote! { query: &#wp::query::builder::QueryBuilder<Self> };
+    let update = quote! { query: &#wp::query::builder::UpdateBuilder<Self> };
+    let affected = quote! { affected: &#wp::model_traits::hooks::BulkAffected<'_, Self> };
+
+    let before_insert = write_hook(
+        info,
+        HookKind::BeforeBulkInsert,
+        quote! { BeforeBulkInsert },
+        quote! { before(&self) },
+        quote! { self },
+    );
+    let after_insert = write_hook(
+        info,
+        HookKind::AfterBulkInsert,
+        quote! { AfterBulkInsert },
+        quote! { after(&self) },
+        quote! { self },
+    );
+    let before_update = write_hook(
+        info,
+        HookKind::BeforeBulkUpdate,
+        quote! { BeforeBulkUpdate },
+        quote! { before(#update) },
+        quote! { query },
+    );
+    let after_update = write_hook(
+        info,
+        HookKind::AfterBulkUpdate,
+        quote! { AfterBulkUpdate },
+        quote! { after(#update, #affected) },
+        quote! { query, affected },
+    );
+    let before_delete = write_hook(
+        info,
+        HookKind::BeforeBulkDelete,
+        quote! { BeforeBulkDelete },
+        quote! { before(#query) },
+        quote! { query },
+    );
+    let after_delete = write_hook(
+        info,
+        HookKind::AfterBulkDelete,
+        quote! { AfterBulkDelete },
+        quote! { after(#query, #affected) },
+        quote! { query, affected },
+    );
+
+    quote! {
+        #before_insert
+        #after_insert
+        #before_update
+        #after_update
+        #before_delete
+        #after_delete
+    }
+}
+
+// Before hooks can cancel the action with an Err, after hooks are only informed
+fn write_hook(
+    info: &Info,
+    kind: HookKind,
+    trait_name: TokenStream,
+    signature: TokenStream,
+    args: TokenStream,
+) -> TokenStream {
+    let def = &info.defstruct;
+    let wp = &info.welds_path;
+    let is_before = matches!(
+        kind,
+        HookKind::BeforeBulkInsert | HookKind::BeforeBulkUpdate | HookKind::BeforeBulkDelete
+    );
+
+    // build the inner content to call all the callback functions
+    let hook_calls: Vec<_> = info
+        .hooks
+        .iter()
+        .filter(|h| h.kind == kind)
+        .map(|h| {
+            let func = &h.callback;
+            match (h.is_async, is_before) {
+                (true, true) => quote! { #func(#args).await?; },
+                (false, true) => quote! { #func(#args)?; },
+                (true, false) => quote! { #func(#args).await; },
+                (false, false) => quote! { #func(#args); },
+            }
+        })
+        .collect();
+    let hook_calls = quote! { #(#hook_calls)* };
+
+    let async_token = if cfg!(feature = "__sync") {
+        quote! {}
+    } else {
+        quote! { async }
+    };
+
+    quote! {
+        impl #wp::model_traits::hooks::#trait_name for #def {
+            #[allow(unused_variables)]
+            #async_token fn #signature -> #wp::errors::Result<()> {
+                #hook_calls
+                Ok(())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hook::Hook;
+    use syn::parse_quote;
+
+    #[test]
+    fn should_call_the_bulk_update_hooks_with_the_query() {
+        let mut info = Info::mock().add_pk("id", "i64");
+        info.hooks.push(Hook {
+            kind: HookKind::BeforeBulkUpdate,
+            callback: parse_quote!(audit::before),
+            is_async: false,
+            with_changes: false,
+            on_commit: false,
+        });
+        info.hooks.push(Hook {
+            kind: HookKind::AfterBulkUpdate,
+            callback: parse_quote!(audit::after),
+            is_async: true,
+            with_changes: false,
+            on_commit: false,
+        });
+        let code = write(&info).to_string();
+
+        let expected: &str = r#"
+            impl welds::model_traits::hooks::BeforeBulkUpdate for Mock {
+                #[allow(unused_variables)]
+                async fn before(query: &welds::query::builder::UpdateBuilder<Self>) -> welds::errors::Result<()> {
+                    audit::before(query)?;
+                    Ok(())
+                }
+            }
+            impl welds::model_traits::hooks::AfterBulkUpdate for Mock {
+                #[allow(unused_variables)]
+                async fn after(
+                    query: &welds::query::builder::UpdateBuilder<Self>,
+                    affected: &welds::model_traits::hooks::BulkAffected<'_, Self>
+                ) -> welds::errors::Result<()> {
+                    audit::after(query, affected).await;
+                    Ok(())
+                }
+            }
+        "#;
+
+        assert!(cleaned(&code).contains(&cleaned(expected)));
+    }
+
+    fn cleaned(input: &str) -> String {
+        input.chars().filter(|c| !c.is_whitespace()).collect()
+    }
+}
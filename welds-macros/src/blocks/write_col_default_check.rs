@@ -44,6 +44,17 @@ pub(crate) fn col_switch(col: &Column) -> TokenStream {
 pub(crate) fn write_default_check_impl(info: &Info, matches: &TokenStream) -> TokenStream {
     let def = &info.defstruct;
     let wp = &info.welds_path;
+    // columns of #[welds(flatten)] fields are checked by the embedded struct
+    let embeds = info.embeds.iter().map(|e| {
+        let field = &e.field;
+        let embedded = e.embedded(wp);
+        let prefix = e.prefix.as_str();
+        quote! {
+            if let Some(v) = #embedded::col_is_default(&self.#field, #prefix, column) {
+                return Ok(v);
+            }
+        }
+    });
 
     quote! {
 
@@ -56,6 +67,7 @@ pub(crate) fn write_default_check_impl(info: &Info, matches: &TokenStream) -> To
             let v = match column {
                 #matches
                 _ => {
+                    #(#embeds)*
                     return Err(#wp::errors::WeldsError::MissingDbColumn(
                         column.to_owned(),
                     ).into())
@@ -28,6 +28,18 @@ pub(crate) fn write(info: &Info) -> TokenStream {
     }
 }
 
+// The #[welds(validate(..))] rules are checked after the user's hooks,
+// so values the hooks fill in are checked too
+fn write_validate_call(info: &Info) -> TokenStream {
+    let wp = &info.welds_path;
+    if info.validations.is_empty() {
+        return quote! {};
+    }
+    quote! {
+        #wp::model_traits::Validate::validate(self).map_err(#wp::errors::WeldsError::Validation)?;
+    }
+}
+
 // Create
 
 pub(crate) fn write_before_create(info: &Info) -> TokenStream {
@@ -49,6 +61,7 @@ pub(crate) fn write_before_create(info: &Info) -> TokenStream {
         })
         .collect();
     let hook_calls = quote! { #(#hook_calls)* };
+    let validate_call = write_validate_call(info);
 
     let async_token = if cfg!(feature = "__sync") {
         quote! {}
@@ -60,6 +73,7 @@ pub(crate) fn write_before_create(info: &Info) -> TokenStream {
         impl #wp::model_traits::hooks::BeforeCreate for #def {
             #async_token fn before(&mut self) -> #wp::errors::Result<()> {
                 #hook_calls
+                #validate_call
                 Ok(())
             }
         }
@@ -74,7 +88,7 @@ pub(crate) fn write_after_create(info: &Info) -> TokenStream {
     let hook_calls: Vec<_> = info
         .hooks
         .iter()
-        .filter(|h| h.kind == HookKind::AfterCreate)
+        .filter(|h| h.kind == HookKind::AfterCreate && !h.on_commit)
         .map(|h| {
             let func = &h.callback;
             if h.is_async {
@@ -86,6 +100,8 @@ pub(crate) fn write_after_create(info: &Info) -> TokenStream {
         .collect();
     let hook_calls = quote! { #(#hook_calls)* };
 
+    let after_commit = write_after_commit(info, HookKind::AfterCreate);
+
     let async_token = if cfg!(feature = "__sync") {
         quote! {}
     } else {
@@ -98,6 +114,7 @@ pub(crate) fn write_after_create(info: &Info) -> TokenStream {
                 #hook_calls
                 Ok(())
             }
+            #after_commit
         }
     }
 }
@@ -112,7 +129,7 @@ pub(crate) fn write_before_update(info: &Info) -> TokenStream {
     let hook_calls: Vec<_> = info
         .hooks
         .iter()
-        .filter(|h| h.kind == HookKind::BeforeUpdate)
+        .filter(|h| h.kind == HookKind::BeforeUpdate && !h.with_changes)
         .map(|h| {
             let func = &h.callback;
             if h.is_async {
@@ -123,6 +140,7 @@ pub(crate) fn write_before_update(info: &Info) -> TokenStream {
         })
         .collect();
     let hook_calls = quote! { #(#hook_calls)* };
+    let validate_call = write_validate_call(info);
 
     let async_token = if cfg!(feature = "__sync") {
         quote! {}
@@ -130,12 +148,42 @@ pub(crate) fn write_before_update(info: &Info) -> TokenStream {
         quote! { async }
     };
 
+    // the `changes = true` callbacks are also given the ChangeSet
+    let changes_calls: Vec<_> = info
+        .hooks
+        .iter()
+        .filter(|h| h.kind == HookKind::BeforeUpdate && h.with_changes)
+        .map(|h| {
+            let func = &h.callback;
+            if h.is_async {
+                quote! { #func(self, changes).await?; }
+            } else {
+                quote! { #func(self, changes)?; }
+            }
+        })
+        .collect();
+    let before_with_changes = if changes_calls.is_empty() {
+        quote! {}
+    } else {
+        quote! {
+            #async_token fn before_with_changes(
+                &mut self,
+                changes: &#wp::model_traits::ChangeSet<'_>,
+            ) -> #wp::errors::Result<()> {
+                #(#changes_calls)*
+                Ok(())
+            }
+        }
+    };
+
     quote! {
         impl #wp::model_traits::hooks::BeforeUpdate for #def {
             #async_token fn before(&mut self) -> #wp::errors::Result<()> {
                 #hook_calls
+                #validate_call
                 Ok(())
             }
+            #before_with_changes
         }
     }
 }
@@ -148,7 +196,7 @@ pub(crate) fn write_after_update(info: &Info) -> TokenStream {
     let hook_calls: Vec<_> = info
         .hooks
         .iter()
-        .filter(|h| h.kind == HookKind::AfterUpdate)
+        .filter(|h| h.kind == HookKind::AfterUpdate && !h.on_commit)
         .map(|h| {
             let func = &h.callback;
             if h.is_async {
@@ -160,6 +208,8 @@ pub(crate) fn write_after_update(info: &Info) -> TokenStream {
         .collect();
     let hook_calls = quote! { #(#hook_calls)* };
 
+    let after_commit = write_after_commit(info, HookKind::AfterUpdate);
+
     let async_token = if cfg!(feature = "__sync") {
         quote! {}
     } else {
@@ -172,6 +222,7 @@ pub(crate) fn write_after_update(info: &Info) -> TokenStream {
                 #hook_calls
                 Ok(())
             }
+            #after_commit
         }
     }
 }
@@ -222,7 +273,7 @@ pub(crate) fn write_after_delete(info: &Info) -> TokenStream {
     let hook_calls: Vec<_> = info
         .hooks
         .iter()
-        .filter(|h| h.kind == HookKind::AfterDelete)
+        .filter(|h| h.kind == HookKind::AfterDelete && !h.on_commit)
         .map(|h| {
             let func = &h.callback;
             if h.is_async {
@@ -234,6 +285,8 @@ pub(crate) fn write_after_delete(info: &Info) -> TokenStream {
         .collect();
     let hook_calls = quote! { #(#hook_calls)* };
 
+    let after_commit = write_after_commit(info, HookKind::AfterDelete);
+
     let async_token = if cfg!(feature = "__sync") {
         quote! {}
     } else {
@@ -246,6 +299,116 @@ pub(crate) fn write_after_delete(info: &Info) -> TokenStream {
                 #hook_calls
                 Ok(())
             }
+            #after_commit
+        }
+    }
+}
+
+// `commit = true` hooks are handed to the client, transactions hold them until they commit.
+// The callback gets a clone of the model since it runs after the save has returned
+fn write_after_commit(info: &Info, kind: HookKind) -> TokenStream {
+    let wp = &info.welds_path;
+    let funcs: Vec<_> = info
+        .hooks
+        .iter()
+        .filter(|h| h.kind == kind && h.on_commit)
+        .map(|h| &h.callback)
+        .collect();
+    if funcs.is_empty() {
+        return quote! {};
+    }
+    quote! {
+        fn after_commit(&self, client: &dyn #wp::Client) {
+            let model = ::std::clone::Clone::clone(self);
+            client.defer(Box::new(move || {
+                #(#funcs(&model);)*
+            }));
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hook::Hook;
+    use syn::parse_quote;
+
+    #[test]
+    fn should_defer_commit_hooks_to_the_client() {
+        let mut info = Info::mock().add_pk("id", "i64");
+        info.hooks.push(Hook {
+            kind: HookKind::AfterCreate,
+            callback: parse_quote!(log_created),
+            is_async: false,
+            with_changes: false,
+            on_commit: false,
+        });
+        info.hooks.push(Hook {
+            kind: HookKind::AfterCreate,
+            callback: parse_quote!(send_email),
+            is_async: false,
+            with_changes: false,
+            on_commit: true,
+        });
+        let code = write_after_create(&info).to_string();
+
+        let expected: &str = r#"
+            impl welds::model_traits::hooks::AfterCreate for Mock {
+                async fn after(&self) -> welds::errors::Result<()> {
+                    log_created(self);
+                    Ok(())
+                }
+                fn after_commit(&self, client: &dyn welds::Client) {
+                    let model = ::std::clone::Clone::clone(self);
+                    client.defer(Box::new(move || {
+                        send_email(&model);
+                    }));
+                }
+            }
+        "#;
+
+        assert_eq!(cleaned(&code), cleaned(expected));
+    }
+
+    #[test]
+    fn should_only_give_the_changes_to_hooks_that_ask_for_them() {
+        let mut info = Info::mock().add_pk("id", "i64");
+        info.hooks.push(Hook {
+            kind: HookKind::BeforeUpdate,
+            callback: parse_quote!(touch),
+            is_async: false,
+            with_changes: false,
+            on_commit: false,
+        });
+        info.hooks.push(Hook {
+            kind: HookKind::BeforeUpdate,
+            callback: parse_quote!(log_changes),
+            is_async: false,
+            with_changes: true,
+            on_commit: false,
+        });
+        let code = write_before_update(&info).to_string();
+
+        let expected: &str = r#"
+            impl welds::model_traits::hooks::BeforeUpdate for Mock {
+                async fn before(&mut self) -> welds::errors::Result<()> {
+                    touch(self)?;
+                    Ok(())
+                }
+                async fn before_with_changes(
+                    &mut self,
+                    changes: &welds::model_traits::ChangeSet<'_>,
+                ) -> welds::errors::Result<()> {
+                    log_changes(self, changes)?;
+                    Ok(())
+                }
+            }
+        "#;
+
+        assert_eq!(cleaned(&code), cleaned(expected));
+    }
+
+    fn cleaned(input: &str) -> String {
+        input.chars().filter(|c| !c.is_whitespace()).collect()
+    }
+}
@@ -1,5 +1,6 @@
 use crate::column::Column;
 use crate::info::Info;
+use crate::utils::is_json;
 use proc_macro2::TokenStream;
 use quote::quote;
 
@@ -13,22 +14,71 @@ pub(crate) fn write(info: &Info) -> TokenStream {
         .columns
         .iter()
         .filter(|c| c.updateable || c.insertable)
-        .map(write_col_normal)
+        .map(|c| write_col_normal(&info.welds_path, c))
         .collect();
     let fields = quote! { #(#fields)* };
 
-    write_for_db(info, &fields)
+    let keys: Vec<_> = info
+        .columns
+        .iter()
+        .filter(|c| c.updateable || c.insertable)
+        .map(|c| write_col_key(&info.welds_path, c))
+        .collect();
+    let keys = quote! { #(#keys)* };
+
+    write_for_db(info, &fields, &keys)
 }
 
-pub(crate) fn write_col_normal(col: &Column) -> TokenStream {
+pub(crate) fn write_col_key(wp: &syn::Path, col: &Column) -> TokenStream {
     let dbname = col.dbname.as_str();
     let field = &col.field;
+    quote! { #dbname => (&&#wp::model_traits::changes::FieldProbe(&self.#field)).column_key(), }
+}
+
+pub(crate) fn write_col_normal(wp: &syn::Path, col: &Column) -> TokenStream {
+    let dbname = col.dbname.as_str();
+    let field = &col.field;
+    // Fields with a codec are encoded when the driver reads them
+    if let Some((codec, ty)) = col.codec(wp) {
+        return quote! {
+            #dbname => args.push(#wp::connections::codec::Encoded::<#codec, #ty>::wrap(&self.#field)),
+        };
+    }
+    // tiberius can't write an Option of a type it doesn't own
+    if col.is_option && is_json(&col.field_type) {
+        return quote! { #dbname => args.push(#wp::connections::json::nullable(#dbname, &self.#field)?), };
+    }
+    // JSON is serialized while binding so a value that can't be written is an error
+    if is_json(&col.field_type) {
+        return quote! { #dbname => args.push(#wp::connections::json::checked(#dbname, &self.#field)?), };
+    }
+    // enums are picked out when the model is compiled, see `db_enum::bind`
+    if col.is_option {
+        return quote! {
+            #dbname => args.push({
+                use #wp::connections::db_enum::bind::*;
+                (&&BindOption(&self.#field)).param()
+            }),
+        };
+    }
     quote! { #dbname => args.push(&self.#field), }
 }
 
-pub(crate) fn write_for_db(info: &Info, matches: &TokenStream) -> TokenStream {
+pub(crate) fn write_for_db(info: &Info, matches: &TokenStream, keys: &TokenStream) -> TokenStream {
     let def = &info.defstruct;
     let wp = &info.welds_path;
+    // columns of #[welds(flatten)] fields are bound by the embedded struct
+    let embeds = info.embeds.iter().map(|e| {
+        let field = &e.field;
+        let embedded = e.embedded(wp);
+        let prefix = e.prefix.as_str();
+        quote! {
+            if #embedded::bind(&self.#field, #prefix, column, args)? {
+                return Ok(());
+            }
+        }
+    });
+    let embeds = quote! { #(#embeds)* };
 
     quote! {
 
@@ -45,6 +95,7 @@ pub(crate) fn write_for_db(info: &Info, matches: &TokenStream) -> TokenStream {
             match column {
                 #matches
                 _ => {
+                    #embeds
                     return Err(#wp::errors::WeldsError::MissingDbColumn(
                         column.to_owned(),
                     ).into())
@@ -53,6 +104,14 @@ pub(crate) fn write_for_db(info: &Info, matches: &TokenStream) -> TokenStream {
 
             Ok(())
         }
+
+        fn column_key(&self, column: &str) -> Option<#wp::model_traits::ColumnKey> {
+            use #wp::model_traits::changes::{KeyedField, UnkeyedField};
+            match column {
+                #keys
+                _ => None,
+            }
+        }
     }
 
     }
@@ -88,6 +147,203 @@ mod tests {
                     }
                     Ok(())
                 }
+
+                fn column_key(&self, column: &str) -> Option<welds::model_traits::ColumnKey> {
+                    use welds::model_traits::changes::{KeyedField, UnkeyedField};
+                    match column {
+                        "id" => (&&welds::model_traits::changes::FieldProbe(&self.id)).column_key(),
+                        _ => None,
+                    }
+                }
+            }
+
+        "#;
+
+        assert_eq!(cleaned(&code), cleaned(expected));
+    }
+
+    #[test]
+    fn should_bind_codec_columns_through_the_codec() {
+        let info = Info::mock()
+            .add_pk("id", "i64")
+            .add_codec_column("total", "Money", false, "MoneyCodec")
+            .add_codec_column("tip", "Money", true, "MoneyCodec");
+        let ts = write(&info);
+        let code = ts.to_string();
+
+        let expected: &str = r#"
+            impl welds::model_traits::WriteToArgs for Mock {
+                fn bind<'s, 'c, 'a, 'p>(
+                    &'s self,
+                    column: &'c str,
+                    args: &'a mut welds::query::clause::ParamArgs<'p>,
+                ) -> welds::errors::Result<()>
+                where
+                    's: 'p,
+                {
+                    match column {
+                        "id" => args.push(&self.id),
+                        "total" => args.push(welds::connections::codec::Encoded::<MoneyCodec, Money>::wrap(&self.total)),
+                        "tip" => args.push(welds::connections::codec::Encoded::<
+                            welds::connections::codec::OptionCodec<MoneyCodec>,
+                            Option<Money>
+                        >::wrap(&self.tip)),
+                        _ => {
+                            return Err(welds::errors::WeldsError::MissingDbColumn(
+                                column.to_owned(),
+                            ).into())
+                        }
+                    }
+                    Ok(())
+                }
+
+                fn column_key(&self, column: &str) -> Option<welds::model_traits::ColumnKey> {
+                    use welds::model_traits::changes::{KeyedField, UnkeyedField};
+                    match column {
+                        "id" => (&&welds::model_traits::changes::FieldProbe(&self.id)).column_key(),
+                        "total" => (&&welds::model_traits::changes::FieldProbe(&self.total)).column_key(),
+                        "tip" => (&&welds::model_traits::changes::FieldProbe(&self.tip)).column_key(),
+                        _ => None,
+                    }
+                }
+            }
+
+        "#;
+
+        assert_eq!(cleaned(&code), cleaned(expected));
+    }
+
+    #[test]
+    fn should_bind_nullable_json_columns_through_the_wrapper() {
+        let info =
+            Info::mock()
+                .add_pk("id", "i64")
+                .add_column("settings", "welds::Json<Settings>", true);
+        let ts = write(&info);
+        let code = ts.to_string();
+
+        let expected: &str = r#"
+            impl welds::model_traits::WriteToArgs for Mock {
+                fn bind<'s, 'c, 'a, 'p>(
+                    &'s self,
+                    column: &'c str,
+                    args: &'a mut welds::query::clause::ParamArgs<'p>,
+                ) -> welds::errors::Result<()>
+                where
+                    's: 'p,
+                {
+                    match column {
+                        "id" => args.push(&self.id),
+                        "settings" => args.push(welds::connections::json::nullable("settings", &self.settings)?),
+                        _ => {
+                            return Err(welds::errors::WeldsError::MissingDbColumn(
+                                column.to_owned(),
+                            ).into())
+                        }
+                    }
+                    Ok(())
+                }
+
+                fn column_key(&self, column: &str) -> Option<welds::model_traits::ColumnKey> {
+                    use welds::model_traits::changes::{KeyedField, UnkeyedField};
+                    match column {
+                        "id" => (&&welds::model_traits::changes::FieldProbe(&self.id)).column_key(),
+                        "settings" => (&&welds::model_traits::changes::FieldProbe(&self.settings)).column_key(),
+                        _ => None,
+                    }
+                }
+            }
+
+        "#;
+
+        assert_eq!(cleaned(&code), cleaned(expected));
+    }
+
+    #[test]
+    fn should_check_json_columns_when_they_are_bound() {
+        let info =
+            Info::mock()
+                .add_pk("id", "i64")
+                .add_column("settings", "welds::Json<Settings>", false);
+        let ts = write(&info);
+        let code = ts.to_string();
+
+        let expected: &str = r#"
+            impl welds::model_traits::WriteToArgs for Mock {
+                fn bind<'s, 'c, 'a, 'p>(
+                    &'s self,
+                    column: &'c str,
+                    args: &'a mut welds::query::clause::ParamArgs<'p>,
+                ) -> welds::errors::Result<()>
+                where
+                    's: 'p,
+                {
+                    match column {
+                        "id" => args.push(&self.id),
+                        "settings" => args.push(welds::connections::json::checked("settings", &self.settings)?),
+                        _ => {
+                            return Err(welds::errors::WeldsError::MissingDbColumn(
+                                column.to_owned(),
+                            ).into())
+                        }
+                    }
+                    Ok(())
+                }
+
+                fn column_key(&self, column: &str) -> Option<welds::model_traits::ColumnKey> {
+                    use welds::model_traits::changes::{KeyedField, UnkeyedField};
+                    match column {
+                        "id" => (&&welds::model_traits::changes::FieldProbe(&self.id)).column_key(),
+                        "settings" => (&&welds::model_traits::changes::FieldProbe(&self.settings)).column_key(),
+                        _ => None,
+                    }
+                }
+            }
+
+        "#;
+
+        assert_eq!(cleaned(&code), cleaned(expected));
+    }
+
+    #[test]
+    fn should_bind_flattened_columns_through_their_struct() {
+        let info = Info::mock()
+            .add_pk("id", "i64")
+            .add_embed("address", "Address", "");
+        let ts = write(&info);
+        let code = ts.to_string();
+
+        let expected: &str = r#"
+            impl welds::model_traits::WriteToArgs for Mock {
+                fn bind<'s, 'c, 'a, 'p>(
+                    &'s self,
+                    column: &'c str,
+                    args: &'a mut welds::query::clause::ParamArgs<'p>,
+                ) -> welds::errors::Result<()>
+                where
+                    's: 'p,
+                {
+                    match column {
+                        "id" => args.push(&self.id),
+                        _ => {
+                            if <Address as welds::model_traits::Embedded>::bind(&self.address, "", column, args)? {
+                                return Ok(());
+                            }
+                            return Err(welds::errors::WeldsError::MissingDbColumn(
+                                column.to_owned(),
+                            ).into())
+                        }
+                    }
+                    Ok(())
+                }
+
+                fn column_key(&self, column: &str) -> Option<welds::model_traits::ColumnKey> {
+                    use welds::model_traits::changes::{KeyedField, UnkeyedField};
+                    match column {
+                        "id" => (&&welds::model_traits::changes::FieldProbe(&self.id)).column_key(),
+                        _ => None,
+                    }
+                }
             }
 
         "#;
@@ -1,3 +1,5 @@
+use proc_macro2::TokenStream;
+use quote::quote;
 use syn::Ident;
 use syn::Type;
 
@@ -10,4 +12,21 @@ pub(crate) struct Column {
     pub(crate) dbname: String,
     pub(crate) field_type: Type,
     pub(crate) is_option: bool,
+    pub(crate) codec: Option<Type>,
+}
+
+impl Column {
+    /// The `#[welds(with = "path")]` codec of the field, and the full type of the field.
+    /// Option fields run the codec on the inner value
+    pub(crate) fn codec(&self, wp: &syn::Path) -> Option<(TokenStream, TokenStream)> {
+        let codec = self.codec.as_ref()?;
+        let ty = &self.field_type;
+        if self.is_option {
+            return Some((
+                quote! { #wp::connections::codec::OptionCodec<#codec> },
+                quote! { Option<#ty> },
+            ));
+        }
+        Some((quote! { #codec }, quote! { #ty }))
+    }
 }
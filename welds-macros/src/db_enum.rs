@@ -0,0 +1,189 @@
+use crate::attributes::{get_welds_path, read_rename_attrs};
+use crate::errors::Result;
+use proc_macro2::{Ident, TokenStream};
+use quote::quote;
+
+/// Writes the impls for `#[derive(WeldsEnum)]`
+///
+/// Enums with a `#[repr(i16)]`, `#[repr(i32)]` or `#[repr(i64)]` are stored as their discriminant.
+/// All other enums are stored as text, the name of the variant or its `#[welds(rename = "..")]`
+pub(crate) fn write(ast: &syn::DeriveInput) -> Result<TokenStream> {
+    let data = match &ast.data {
+        syn::Data::Enum(d) => d,
+        _ => return Err("WeldsEnum can only be derived for enums".to_owned()),
+    };
+    let name = &ast.ident;
+    let wp = get_welds_path(ast);
+
+    let mut variants = Vec::default();
+    for v in &data.variants {
+        if !matches!(v.fields, syn::Fields::Unit) {
+            return Err(format!(
+                "WeldsEnum variants can not hold data: {}::{}",
+                name, v.ident
+            ));
+        }
+        variants.push(v);
+    }
+
+    let (repr, to_repr, from_repr) = match get_int_repr(ast)? {
+        Some(repr) => {
+            let to_arms = variants.iter().map(|v| {
+                let ident = &v.ident;
+                quote! { #name::#ident => #name::#ident as #repr }
+            });
+            let from_arms = variants.iter().map(|v| {
+                let ident = &v.ident;
+                quote! { x if x == #name::#ident as #repr => Ok(#name::#ident) }
+            });
+            (
+                quote! { #repr },
+                quote! { match self { #(#to_arms,)* } },
+                quote! { match value { #(#from_arms,)* _ => Err(#wp::connections::db_enum::UnknownVariant::new(stringify!(#name), value)) } },
+            )
+        }
+        None => {
+            let names: Vec<String> = variants
+                .iter()
+                .map(|v| read_rename_attrs(&v.attrs).unwrap_or_else(|| v.ident.to_string()))
+                .collect();
+            let to_arms = variants.iter().zip(&names).map(|(v, text)| {
+                let ident = &v.ident;
+                quote! { #name::#ident => #text.to_owned() }
+            });
+            let from_arms = variants.iter().zip(&names).map(|(v, text)| {
+                let ident = &v.ident;
+                quote! { #text => Ok(#name::#ident) }
+            });
+            (
+                quote! { String },
+                quote! { match self { #(#to_arms,)* } },
+                quote! { match value.as_str() { #(#from_arms,)* _ => Err(#wp::connections::db_enum::UnknownVariant::new(stringify!(#name), value)) } },
+            )
+        }
+    };
+
+    Ok(quote! {
+        impl #wp::connections::db_enum::DbEnum for #name {
+            type Repr = #repr;
+            fn to_repr(&self) -> #repr {
+                #to_repr
+            }
+            fn from_repr(value: #repr) -> std::result::Result<Self, #wp::connections::db_enum::UnknownVariant> {
+                #from_repr
+            }
+        }
+        #wp::connections::impl_db_enum!(#name, #repr);
+    })
+}
+
+/// The integer from a `#[repr(i32)]`, None for enums stored as text
+fn get_int_repr(ast: &syn::DeriveInput) -> Result<Option<Ident>> {
+    let reprs: Vec<Ident> = ast
+        .attrs
+        .iter()
+        .filter(|a| a.path().is_ident("repr"))
+        .filter_map(|a| a.parse_args::<Ident>().ok())
+        .collect();
+    for repr in reprs {
+        match repr.to_string().as_str() {
+            "i16" | "i32" | "i64" => return Ok(Some(repr)),
+            "C" => {}
+            other => {
+                return Err(format!(
+                    "WeldsEnum supports #[repr(i16)], #[repr(i32)] and #[repr(i64)], found: {}",
+                    other
+                ));
+            }
+        }
+    }
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use syn::parse_quote;
+
+    #[test]
+    fn should_store_enums_as_text() {
+        let ast: syn::DeriveInput = parse_quote! {
+            enum Status {
+                Open,
+                #[welds(rename = "shut")]
+                Closed,
+            }
+        };
+        let code = write(&ast).unwrap().to_string();
+
+        let expected: &str = r#"
+            impl welds::connections::db_enum::DbEnum for Status {
+                type Repr = String;
+                fn to_repr(&self) -> String {
+                    match self {
+                        Status::Open => "Open".to_owned(),
+                        Status::Closed => "shut".to_owned(),
+                    }
+                }
+                fn from_repr(value: String) -> std::result::Result<Self, welds::connections::db_enum::UnknownVariant> {
+                    match value.as_str() {
+                        "Open" => Ok(Status::Open),
+                        "shut" => Ok(Status::Closed),
+                        _ => Err(welds::connections::db_enum::UnknownVariant::new(stringify!(Status), value))
+                    }
+                }
+            }
+            welds::connections::impl_db_enum!(Status, String);
+        "#;
+
+        assert_eq!(cleaned(&code), cleaned(expected));
+    }
+
+    #[test]
+    fn should_store_enums_with_an_int_repr_as_numbers() {
+        let ast: syn::DeriveInput = parse_quote! {
+            #[repr(i32)]
+            enum Priority {
+                Low = 1,
+                High = 5,
+            }
+        };
+        let code = write(&ast).unwrap().to_string();
+
+        let expected: &str = r#"
+            impl welds::connections::db_enum::DbEnum for Priority {
+                type Repr = i32;
+                fn to_repr(&self) -> i32 {
+                    match self {
+                        Priority::Low => Priority::Low as i32,
+                        Priority::High => Priority::High as i32,
+                    }
+                }
+                fn from_repr(value: i32) -> std::result::Result<Self, welds::connections::db_enum::UnknownVariant> {
+                    match value {
+                        x if x == Priority::Low as i32 => Ok(Priority::Low),
+                        x if x == Priority::High as i32 => Ok(Priority::High),
+                        _ => Err(welds::connections::db_enum::UnknownVariant::new(stringify!(Priority), value))
+                    }
+                }
+            }
+            welds::connections::impl_db_enum!(Priority, i32);
+        "#;
+
+        assert_eq!(cleaned(&code), cleaned(expected));
+    }
+
+    #[test]
+    fn should_not_allow_variants_with_data() {
+        let ast: syn::DeriveInput = parse_quote! {
+            enum Status {
+                Open(i32),
+            }
+        };
+        assert!(write(&ast).is_err());
+    }
+
+    fn cleaned(input: &str) -> String {
+        input.chars().filter(|c| !c.is_whitespace()).collect()
+    }
+}
@@ -0,0 +1,176 @@
+use crate::attributes::{get_columns, get_welds_path};
+use crate::blocks::{col_switch, get_clause, read_column, write_col_normal};
+use crate::column::Column;
+use crate::errors::Result;
+use proc_macro2::{Ident, TokenStream};
+use quote::quote;
+
+/// A field marked with #[welds(flatten)]
+#[derive(Clone)]
+pub(crate) struct Embed {
+    pub(crate) field: Ident,
+    pub(crate) ty: syn::Type,
+    pub(crate) prefix: String,
+}
+
+impl Embed {
+    /// The `Embedded` impl of the field's type
+    pub(crate) fn embedded(&self, wp: &syn::Path) -> TokenStream {
+        let ty = &self.ty;
+        quote! { <#ty as #wp::model_traits::Embedded> }
+    }
+
+    /// The field's `Prefix`, kept in a static so its column names are only built once
+    pub(crate) fn prefix(&self, wp: &syn::Path) -> TokenStream {
+        let prefix = self.prefix.as_str();
+        quote! {{
+            static PREFIX: #wp::model_traits::embedded::Prefix =
+                #wp::model_traits::embedded::Prefix::new(#prefix);
+            &PREFIX
+        }}
+    }
+}
+
+/// Writes the impls for `#[derive(WeldsEmbed)]`
+///
+/// A struct of columns that can be embedded into models with `#[welds(flatten)]`
+pub(crate) fn write(ast: &syn::DeriveInput) -> Result<TokenStream> {
+    if !matches!(&ast.data, syn::Data::Struct(_)) {
+        return Err("WeldsEmbed can only be derived for structs".to_owned());
+    }
+    let name = &ast.ident;
+    let schema = Ident::new(&format!("{}Schema", name), name.span());
+    let wp = get_welds_path(ast);
+    let columns = get_columns(ast);
+    let selected: Vec<&Column> = columns.iter().filter(|c| c.selectable).collect();
+    let prefix_type = quote! { #wp::model_traits::embedded::Prefix };
+    let dbnames: Vec<&str> = selected.iter().map(|c| c.dbname.as_str()).collect();
+    let names = quote! { let names = prefix.names(&[ #(#dbnames),* ]); };
+    // the prefixed name of a column, by its place in `names`
+    let name_at = |i: usize| quote! { names[#i].as_str() };
+
+    let schema_fields = selected.iter().map(|c| {
+        let field = &c.field;
+        let clause = get_clause(c);
+        let ty = &c.field_type;
+        quote! { pub #field: #wp::query::clause::#clause<#ty> }
+    });
+    let schema_values = selected.iter().enumerate().map(|(i, c)| {
+        let field = &c.field;
+        let clause = get_clause(c);
+        let fieldname = field.to_string();
+        let colname = name_at(i);
+        quote! { #field: #wp::query::clause::#clause::new(#colname, #fieldname) }
+    });
+
+    let column_defs = selected.iter().enumerate().map(|(i, c)| {
+        let ty = &c.field_type;
+        let rust_type = quote! { #ty }.to_string();
+        let nullable = c.is_option;
+        let colname = name_at(i);
+        quote! { #wp::model_traits::Column::new(#colname, #rust_type, #nullable) }
+    });
+
+    let reads = selected.iter().enumerate().map(|(i, c)| {
+        let field = &c.field;
+        let value = read_column(&wp, c, &name_at(i));
+        quote! { #field: #value, }
+    });
+    let defaults = match columns.iter().any(|c| !c.selectable) {
+        true => quote! { ..Default::default() },
+        false => quote! {},
+    };
+    let updates = selected.iter().enumerate().map(|(i, c)| {
+        let field = &c.field;
+        let colname = name_at(i);
+        let value = read_column(&wp, c, &quote! { colname });
+        quote! {
+            let colname = #colname;
+            if row.has(colname) { self.#field = #value; }
+        }
+    });
+    let binds = selected.iter().map(|c| write_col_normal(&wp, c));
+    let default_checks = selected.iter().map(|c| col_switch(c));
+    let snapshots = selected.iter().enumerate().map(|(i, c)| {
+        let field = &c.field;
+        let colname = name_at(i);
+        quote! {
+            (#colname, (&&#wp::model_traits::changes::FieldProbe(&self.#field)).snapshot_value())
+        }
+    });
+
+    Ok(quote! {
+        #[derive(Copy, Clone)]
+        pub struct #schema {
+            #(#schema_fields),*
+        }
+
+        impl #wp::model_traits::Embedded for #name {
+            type Schema = #schema;
+
+            fn schema(prefix: &'static #prefix_type) -> Self::Schema {
+                #names
+                #schema {
+                    #(#schema_values),*
+                }
+            }
+
+            fn columns(prefix: &'static #prefix_type) -> Vec<#wp::model_traits::Column> {
+                #names
+                vec![ #(#column_defs),* ]
+            }
+
+            fn from_row(row: &#wp::Row, prefix: &'static #prefix_type) -> #wp::errors::Result<Self> {
+                #names
+                Ok(#name {
+                    #(#reads)*
+                    #defaults
+                })
+            }
+
+            fn update_from_row(&mut self, row: &#wp::Row, prefix: &'static #prefix_type) -> #wp::errors::Result<()> {
+                #names
+                #(#updates)*
+                Ok(())
+            }
+
+            fn bind<'s, 'p>(
+                &'s self,
+                prefix: &str,
+                column: &str,
+                args: &mut #wp::query::clause::ParamArgs<'p>,
+            ) -> #wp::errors::Result<bool>
+            where
+                's: 'p,
+            {
+                let column = match column.strip_prefix(prefix) {
+                    Some(column) => column,
+                    None => return Ok(false),
+                };
+                match column {
+                    #(#binds)*
+                    _ => return Ok(false),
+                }
+                Ok(true)
+            }
+
+            fn col_is_default(&self, prefix: &str, column: &str) -> Option<bool> {
+                let v = match column.strip_prefix(prefix)? {
+                    #(#default_checks)*
+                    _ => return None,
+                };
+                Some(v)
+            }
+
+            fn snapshot_values(
+                &self,
+                prefix: &'static #prefix_type,
+            ) -> Vec<(&'static str, Option<Box<dyn #wp::model_traits::changes::ColumnValue>>)> {
+                #names
+                #[allow(unused_imports)]
+                use #wp::model_traits::changes::{TrackedField, UntrackedField};
+                vec![ #(#snapshots),* ]
+            }
+        }
+    })
+}
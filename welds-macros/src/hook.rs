@@ -10,6 +10,10 @@ pub(crate) struct Hook {
     pub(crate) kind: HookKind,
     pub(crate) callback: syn::Path,
     pub(crate) is_async: bool,
+    /// BeforeUpdate only, the callback is also given the ChangeSet
+    pub(crate) with_changes: bool,
+    /// After hooks only, the callback waits for the client's transaction to commit
+    pub(crate) on_commit: bool,
 }
 
 #[derive(Copy, Clone, PartialEq, Eq, Debug)]
@@ -20,6 +24,12 @@ pub(crate) enum HookKind {
     AfterCreate,
     AfterUpdate,
     AfterDelete,
+    BeforeBulkInsert,
+    AfterBulkInsert,
+    BeforeBulkUpdate,
+    AfterBulkUpdate,
+    BeforeBulkDelete,
+    AfterBulkDelete,
 }
 
 impl Hook {
@@ -27,48 +37,60 @@ impl Hook {
         let badformat = || {
             Err("Expected Hook to be one of the following format(s):\n\
             [ welds(BeforeCreate(fn_to_call_before_create)) ]\n\
-            [ welds(BeforeCreate(fn_to_call_before_create, async = true)) ]"
+            [ welds(BeforeCreate(fn_to_call_before_create, async = true)) ]\n\
+            [ welds(BeforeUpdate(fn_to_call_before_update, changes = true)) ]\n\
+            [ welds(AfterCreate(fn_to_call_after_commit, commit = true)) ]\n\
+            [ welds(BeforeBulkUpdate(fn_to_call_with_the_query)) ]"
                 .to_owned())
         };
 
         let list = &list.tokens.clone().into_iter().collect::<Vec<_>>();
-        if list.len() > 5 {
+        if list.is_empty() {
             return badformat();
         }
 
         let mut is_async = false;
+        let mut with_changes = false;
+        let mut on_commit = false;
+        let is_after = matches!(
+            kind,
+            HookKind::AfterCreate | HookKind::AfterUpdate | HookKind::AfterDelete
+        );
 
-        if list.len() == 5 {
-            match &list[3] {
-                TokenTree::Punct(punct) => {
-                    if punct.as_char() != '=' {
-                        return badformat();
-                    }
-                }
+        // after the callback, read each `, key = bool` option
+        let options = &list[1..];
+        if options.len() % 4 != 0 {
+            return badformat();
+        }
+        for option in options.chunks(4) {
+            match &option[0] {
+                TokenTree::Punct(punct) if punct.as_char() == ',' => {}
                 _ => return badformat(),
             }
-            match &list[2] {
-                TokenTree::Ident(ident) => {
-                    if *ident != "async" {
-                        return badformat();
-                    }
-                }
+            match &option[2] {
+                TokenTree::Punct(punct) if punct.as_char() == '=' => {}
                 _ => return badformat(),
             }
-
-            match &list[4] {
-                TokenTree::Ident(ident) => {
-                    if *ident == "true" {
-                        is_async = true;
-                    } else if *ident == "false" {
-                        is_async = false;
-                    } else {
-                        return badformat();
-                    }
+            let value = match &option[3] {
+                TokenTree::Ident(ident) if *ident == "true" => true,
+                TokenTree::Ident(ident) if *ident == "false" => false,
+                _ => return badformat(),
+            };
+            match &option[1] {
+                TokenTree::Ident(ident) if *ident == "async" => is_async = value,
+                TokenTree::Ident(ident)
+                    if *ident == "changes" && kind == HookKind::BeforeUpdate =>
+                {
+                    with_changes = value
                 }
+                TokenTree::Ident(ident) if *ident == "commit" && is_after => on_commit = value,
                 _ => return badformat(),
             }
         }
+        // callbacks ran on commit can't be awaited
+        if on_commit && is_async {
+            return badformat();
+        }
 
         // Convert the TokenTree to TokenStream
         let token_stream = {
@@ -88,6 +110,8 @@ impl Hook {
             kind,
             callback: callback.clone(),
             is_async,
+            with_changes,
+            on_commit,
         })
     }
 }
@@ -1,8 +1,10 @@
 use crate::attributes;
 use crate::column::Column;
+use crate::embed::Embed;
 use crate::errors::Result;
 use crate::hook::Hook;
 use crate::relation::Relation;
+use crate::validation::Validation;
 use syn::Ident;
 
 pub(crate) struct Info {
@@ -11,12 +13,22 @@ pub(crate) struct Info {
     pub colstruct: Ident,
     pub columns: Vec<Column>,
     pub pks: Vec<Column>,
+    pub version: Option<Column>,
+    pub soft_delete: Option<Column>,
+    pub created_at: Option<Column>,
+    pub updated_at: Option<Column>,
+    pub db_defaults: Vec<Column>,
+    pub db_managed: Vec<Column>,
+    pub embeds: Vec<Embed>,
     pub relations: Vec<Relation>,
     pub hooks: Vec<Hook>,
+    pub validations: Vec<Validation>,
     pub relations_struct: Ident,
     pub tablename: String,
     pub schemaname: Option<String>,
     pub readonly: bool,
+    /// #[welds(audited)], changes are written to a history table
+    pub audited: bool,
     pub welds_path: syn::Path,
 }
 
@@ -35,21 +47,39 @@ impl Info {
         let schemaname = attributes::get_schemaname(ast);
         let columns = attributes::get_columns(ast);
         let pks = attributes::get_pks(ast);
+        let version = attributes::get_version(ast, &columns)?;
+        let soft_delete = attributes::get_soft_delete(ast, &columns)?;
+        let created_at = attributes::get_created_at(ast, &columns)?;
+        let updated_at = attributes::get_updated_at(ast, &columns)?;
+        let db_defaults = attributes::get_db_defaults(ast, &columns);
+        let db_managed = attributes::get_db_managed(ast, &columns);
+        let embeds = attributes::get_embeds(ast)?;
+        let validations = attributes::get_validations(ast, &columns)?;
         let readonly = attributes::get_readonly(ast);
+        let audited = attributes::get_audited(ast);
         let welds_path = attributes::get_welds_path(ast);
 
         Ok(Self {
             columns,
             pks,
+            version,
+            soft_delete,
+            created_at,
+            updated_at,
+            db_defaults,
+            db_managed,
+            embeds,
             defstruct,
             relations,
             hooks,
+            validations,
             schemastruct,
             colstruct,
             relations_struct,
             tablename,
             schemaname,
             readonly,
+            audited,
             welds_path,
         })
     }
@@ -71,12 +101,21 @@ mod tests {
                 colstruct: Ident::new("MockColumns", Span::call_site()),
                 columns: Vec::default(),
                 pks: Vec::default(),
+                version: None,
+                soft_delete: None,
+                created_at: None,
+                updated_at: None,
+                db_defaults: Vec::default(),
+                db_managed: Vec::default(),
+                embeds: Vec::default(),
                 relations: Vec::default(),
                 hooks: Vec::default(),
+                validations: Vec::default(),
                 relations_struct: Ident::new("MockRelationships", Span::call_site()),
                 tablename: "datables".to_string(),
                 schemaname: Some("daschema".to_string()),
                 readonly: false,
+                audited: false,
                 welds_path: Ident::new("welds", Span::call_site()).into(),
             }
         }
@@ -99,11 +138,121 @@ mod tests {
                 dbname: name,
                 field_type,
                 is_option: null,
+                codec: None,
             };
             self.columns.push(col);
             self
         }
 
+        pub(crate) fn add_version(
+            mut self,
+            name: impl Into<String>,
+            ty: impl Into<String>,
+        ) -> Info {
+            self = self.add_column(name, ty, false);
+            self.version = self.columns.last().cloned();
+            self
+        }
+
+        pub(crate) fn add_soft_delete(
+            mut self,
+            name: impl Into<String>,
+            ty: impl Into<String>,
+            null: bool,
+        ) -> Info {
+            self = self.add_column(name, ty, null);
+            self.soft_delete = self.columns.last().cloned();
+            self
+        }
+
+        pub(crate) fn add_created_at(
+            mut self,
+            name: impl Into<String>,
+            ty: impl Into<String>,
+            null: bool,
+        ) -> Info {
+            self = self.add_column(name, ty, null);
+            self.created_at = self.columns.last().cloned();
+            self
+        }
+
+        pub(crate) fn add_updated_at(
+            mut self,
+            name: impl Into<String>,
+            ty: impl Into<String>,
+            null: bool,
+        ) -> Info {
+            self = self.add_column(name, ty, null);
+            self.updated_at = self.columns.last().cloned();
+            self
+        }
+
+        pub(crate) fn add_db_default(
+            mut self,
+            name: impl Into<String>,
+            ty: impl Into<String>,
+            null: bool,
+        ) -> Info {
+            self = self.add_column(name, ty, null);
+            let col = self.columns.last_mut().unwrap();
+            col.updateable = false;
+            let col = col.clone();
+            self.db_defaults.push(col.clone());
+            self.db_managed.push(col);
+            self
+        }
+
+        pub(crate) fn add_generated(
+            mut self,
+            name: impl Into<String>,
+            ty: impl Into<String>,
+            null: bool,
+        ) -> Info {
+            self = self.add_column(name, ty, null);
+            let col = self.columns.last_mut().unwrap();
+            col.updateable = false;
+            col.insertable = false;
+            let col = col.clone();
+            self.db_managed.push(col);
+            self
+        }
+
+        pub(crate) fn add_codec_column(
+            mut self,
+            name: impl Into<String>,
+            ty: impl Into<String>,
+            null: bool,
+            codec: &str,
+        ) -> Info {
+            self = self.add_column(name, ty, null);
+            let col = self.columns.last_mut().unwrap();
+            col.codec = Some(syn::parse_str(codec).unwrap());
+            self
+        }
+
+        pub(crate) fn add_validation(
+            mut self,
+            name: impl Into<String>,
+            ty: impl Into<String>,
+            null: bool,
+            rules: Vec<crate::validation::Rule>,
+        ) -> Info {
+            self = self.add_column(name, ty, null);
+            let column = self.columns.last().cloned().unwrap();
+            self.validations
+                .push(crate::validation::Validation { column, rules });
+            self
+        }
+
+        pub(crate) fn add_embed(mut self, name: &str, ty: &str, prefix: &str) -> Info {
+            self.embeds.push(crate::embed::Embed {
+                field: Ident::new(name, Span::call_site()),
+                ty: syn::parse_str(ty).unwrap(),
+                prefix: prefix.to_owned(),
+            });
+            self
+        }
+
         pub(crate) fn add_pk(mut self, name: impl Into<String>, ty: impl Into<String>) -> Info {
             let name: String = name.into();
             let field: Ident = Ident::new(&name, Span::call_site());
@@ -118,6 +267,7 @@ mod tests {
                 dbname: name,
                 field_type,
                 is_option: false,
+                codec: None,
             };
             self.columns.push(col.clone());
             self.pks.push(col);
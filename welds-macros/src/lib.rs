@@ -4,11 +4,14 @@ use quote::quote;
 pub(crate) mod attributes;
 pub(crate) mod blocks;
 pub(crate) mod column;
+pub(crate) mod db_enum;
+pub(crate) mod embed;
 pub(crate) mod errors;
 pub(crate) mod hook;
 pub(crate) mod info;
 pub(crate) mod relation;
 pub(crate) mod utils;
+pub(crate) mod validation;
 
 use info::Info;
 
@@ -20,6 +23,34 @@ pub fn model_gen(input: TokenStream) -> TokenStream {
     }
 }
 
+/// Maps a Rust enum to a text or integer column
+///
+/// Enums are stored as the name of their variant, or its `#[welds(rename = "..")]`.
+/// Enums with a `#[repr(i16)]`, `#[repr(i32)]` or `#[repr(i64)]` are stored as their discriminant.
+///
+/// Nullable columns are `Option<Enum>` fields on the model.
+#[proc_macro_derive(WeldsEnum, attributes(welds, welds_path))]
+pub fn enum_gen(input: TokenStream) -> TokenStream {
+    let ast: syn::DeriveInput = syn::parse(input).unwrap();
+    match db_enum::write(&ast) {
+        Ok(q) => q.into(),
+        Err(err) => quote! { std::compile_error!(#err); }.into(),
+    }
+}
+
+/// A group of columns that can be embedded into models with `#[welds(flatten)]`
+///
+/// The fields of the struct become columns of each model it is embedded into.
+/// `#[welds(flatten(prefix = "billing_"))]` adds a prefix to the names of the columns.
+#[proc_macro_derive(WeldsEmbed, attributes(welds, welds_path))]
+pub fn embed_gen(input: TokenStream) -> TokenStream {
+    let ast: syn::DeriveInput = syn::parse(input).unwrap();
+    match embed::write(&ast) {
+        Ok(q) => q.into(),
+        Err(err) => quote! { std::compile_error!(#err); }.into(),
+    }
+}
+
 fn model_gen_inner(input: TokenStream) -> errors::Result<TokenStream> {
     // Gather the Info needed to build all the code snipits
     let ast: syn::DeriveInput = syn::parse(input).unwrap();
@@ -42,6 +73,12 @@ fn model_gen_inner(input: TokenStream) -> errors::Result<TokenStream> {
     let p14 = blocks::write_primary_key_value(&info);
     let p15 = blocks::foreign_key_partial_eq(&info);
     let p16 = blocks::jointable(&info);
+    let p17 = blocks::track_changes(&info);
+    let p18 = blocks::has_version(&info);
+    let p19 = blocks::soft_delete(&info);
+    let p20 = blocks::auto_timestamps(&info);
+    let p21 = blocks::validate(&info);
+    let p22 = blocks::write_bulk_hooks(&info);
 
     let q = quote! {
         #p1
@@ -60,6 +97,12 @@ fn model_gen_inner(input: TokenStream) -> errors::Result<TokenStream> {
         #p14
         #p15
         #p16
+        #p17
+        #p18
+        #p19
+        #p20
+        #p21
+        #p22
     };
 
     // // Want to see what the macros generate?
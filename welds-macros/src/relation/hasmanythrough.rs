@@ -0,0 +1,51 @@
+use super::Relation;
+use super::{read_as_ident, read_as_path, read_as_string};
+use crate::errors::Result;
+use syn::MetaList;
+use syn::parse::Parser;
+use syn::punctuated::Punctuated;
+use syn::token::Comma;
+use syn::{Expr, Ident};
+
+impl Relation {
+    pub(crate) fn new_has_many_through(list: &MetaList) -> Result<Vec<Self>> {
+        let badformat = || FORMAT_ERR_HAS_MANY_THROUGH.to_owned();
+
+        let list = Punctuated::<Expr, Comma>::parse_terminated
+            .parse2(list.tokens.clone())
+            .map_err(|_| badformat())?;
+        let list: &Vec<_> = &list.iter().collect();
+
+        if list.len() != 5 {
+            return Err(badformat());
+        }
+
+        let field = read_as_ident(list, 0).ok_or_else(badformat)?;
+        let model = read_as_path(list, 1).ok_or_else(badformat)?;
+        let mid = read_as_path(list, 2).ok_or_else(badformat)?;
+        let through_key = read_as_string(list, 3).ok_or_else(badformat)?;
+        let foreign_key = read_as_string(list, 4).ok_or_else(badformat)?;
+
+        let kind = Ident::new("HasManyThrough", field.span());
+
+        Ok(vec![Self {
+            kind,
+            field,
+            foreign_struct: model,
+            foreign_key_db: foreign_key,
+            self_key_db: None,
+            is_jointable: false,
+            through: Some(mid),
+            through_key_db: Some(through_key),
+            type_column_db: None,
+            type_name: None,
+        }])
+    }
+}
+
+const FORMAT_ERR_HAS_MANY_THROUGH: &str = "Invalid Format For HasManyThrough:
+HasManyThrough should be in for format of
+[ welds(HasManyThrough(field, struct, through_struct, through_foreign_key_str, foreign_key_str) )]
+Example:
+[ welds(HasManyThrough(line_items, LineItem, Order, \"customer_id\", \"order_id\") )]
+";
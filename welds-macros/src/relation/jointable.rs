@@ -46,6 +46,10 @@ impl Relation {
                 foreign_key_db: model_a_key,
                 self_key_db: None,
                 is_jointable: true,
+                through: None,
+                type_column_db: None,
+                type_name: None,
+                through_key_db: None,
             },
             Self {
                 kind: kind.clone(),
@@ -54,6 +58,10 @@ impl Relation {
                 foreign_key_db: model_b_key,
                 self_key_db: None,
                 is_jointable: true,
+                through: None,
+                type_column_db: None,
+                type_name: None,
+                through_key_db: None,
             },
         ];
 
@@ -33,6 +33,10 @@ impl Relation {
             foreign_key_db: foreign_key.clone(),
             self_key_db: Some(self_key),
             is_jointable: false,
+            through: None,
+            type_column_db: None,
+            type_name: None,
+            through_key_db: None,
         }])
     }
 }
@@ -0,0 +1,50 @@
+use super::Relation;
+use super::{read_as_ident, read_as_path};
+use crate::errors::Result;
+use syn::MetaList;
+use syn::parse::Parser;
+use syn::punctuated::Punctuated;
+use syn::token::Comma;
+use syn::{Expr, Ident};
+
+impl Relation {
+    pub(crate) fn new_many_to_many(list: &MetaList) -> Result<Vec<Self>> {
+        let badformat = || FORMAT_ERR_MANY_TO_MANY.to_owned();
+
+        let list = Punctuated::<Expr, Comma>::parse_terminated
+            .parse2(list.tokens.clone())
+            .map_err(|_| badformat())?;
+        let list: &Vec<_> = &list.iter().collect();
+
+        if list.len() != 3 {
+            return Err(badformat());
+        }
+
+        let field = read_as_ident(list, 0).ok_or_else(badformat)?;
+        let model = read_as_path(list, 1).ok_or_else(badformat)?;
+        let link = read_as_path(list, 2).ok_or_else(badformat)?;
+
+        let kind = Ident::new("ManyToMany", field.span());
+
+        // the keys are read from the JoinTable of the link model
+        Ok(vec![Self {
+            kind,
+            field,
+            foreign_struct: model,
+            foreign_key_db: String::default(),
+            self_key_db: None,
+            is_jointable: false,
+            through: Some(link),
+            type_column_db: None,
+            type_name: None,
+            through_key_db: None,
+        }])
+    }
+}
+
+const FORMAT_ERR_MANY_TO_MANY: &str = "Invalid Format For ManyToMany:
+ManyToMany should be in for format of
+[ welds(ManyToMany(field, struct, jointable_struct) )]
+Example:
+[ welds(ManyToMany(cats, Cat, DogCat) )]
+";
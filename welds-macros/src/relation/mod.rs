@@ -1,8 +1,11 @@
 use syn::{Expr, Ident};
 
 mod basic;
+mod hasmanythrough;
 mod jointable;
 mod manual;
+mod manytomany;
+mod polymorphic;
 
 #[derive(Debug)]
 pub(crate) struct Relation {
@@ -20,6 +23,15 @@ pub(crate) struct Relation {
 
     // A flag to know this Relation is part of a many-to-many jointable
     pub(crate) is_jointable: bool,
+    // Used for ManyToMany, the jointable model linking the two models
+    // Used for HasManyThrough, the model between the two models
+    pub(crate) through: Option<syn::Path>,
+    // Used for HasManyThrough, the FK on the model between pointing at this model
+    pub(crate) through_key_db: Option<String>,
+    // Used for polymorphic relationships, the column holding the type of the parent model
+    pub(crate) type_column_db: Option<String>,
+    // Used for polymorphic relationships, the type given in place of the parent's table
+    pub(crate) type_name: Option<String>,
 }
 
 fn read_as_path(list: &Vec<&Expr>, index: usize) -> Option<syn::Path> {
@@ -0,0 +1,72 @@
+use super::Relation;
+use super::{read_as_ident, read_as_path, read_as_string};
+use crate::errors::Result;
+use syn::MetaList;
+use syn::parse::Parser;
+use syn::punctuated::Punctuated;
+use syn::token::Comma;
+use syn::{Expr, Ident};
+
+impl Relation {
+    pub(crate) fn new_polymorphic(list: &MetaList) -> Result<Vec<Self>> {
+        let kind = list.path.get_ident().unwrap().to_string();
+        let kind_str: &str = kind.as_str();
+
+        let badformat = || match kind_str {
+            "HasManyPolymorphic" => FORMAT_ERR_HAS_MANY_POLYMORPHIC.to_owned(),
+            "BelongsToPolymorphic" => FORMAT_ERR_BELONGS_TO_POLYMORPHIC.to_owned(),
+            _ => panic!("Unknown relationship type ({})", kind_str),
+        };
+
+        let list = Punctuated::<Expr, Comma>::parse_terminated
+            .parse2(list.tokens.clone())
+            .map_err(|_| badformat())?;
+        let list: &Vec<_> = &list.iter().collect();
+
+        if list.len() != 3 && list.len() != 4 {
+            return Err(badformat());
+        }
+
+        let field = read_as_ident(list, 0).ok_or_else(badformat)?;
+        let model = read_as_path(list, 1).ok_or_else(badformat)?;
+        let name = read_as_string(list, 2).ok_or_else(badformat)?;
+        let type_name = match list.len() {
+            4 => Some(read_as_string(list, 3).ok_or_else(badformat)?),
+            _ => None,
+        };
+
+        let kind = Ident::new(kind.as_str(), field.span());
+
+        // the columns are named after the relationship, (commentable_id, commentable_type)
+        Ok(vec![Self {
+            kind,
+            field,
+            foreign_struct: model,
+            foreign_key_db: format!("{}_id", name),
+            self_key_db: None,
+            is_jointable: false,
+            through: None,
+            type_column_db: Some(format!("{}_type", name)),
+            type_name,
+            through_key_db: None,
+        }])
+    }
+}
+
+const FORMAT_ERR_HAS_MANY_POLYMORPHIC: &str = "Invalid Format For HasManyPolymorphic:
+HasManyPolymorphic should be in for format of
+[ welds(HasManyPolymorphic(field, struct, name_str) )]
+or with the type stored for this model
+[ welds(HasManyPolymorphic(field, struct, name_str, type_str) )]
+Example:
+[ welds(HasManyPolymorphic(comments, Comment, \"commentable\") )]
+";
+
+const FORMAT_ERR_BELONGS_TO_POLYMORPHIC: &str = "Invalid Format For BelongsToPolymorphic:
+BelongsToPolymorphic should be in for format of
+[ welds(BelongsToPolymorphic(field, struct, name_str) )]
+or with the type stored for the other model
+[ welds(BelongsToPolymorphic(field, struct, name_str, type_str) )]
+Example:
+[ welds(BelongsToPolymorphic(post, Post, \"commentable\") )]
+";
@@ -48,6 +48,16 @@ fn get_clause_typepath(ty: &TypePath) -> Option<&'static str> {
     Some(clause)
 }
 
+/// true if the type is a `Json<T>` column
+pub(crate) fn is_json(ty: &Type) -> bool {
+    let tp = match ty {
+        syn::Type::Path(tp) => tp,
+        syn::Type::Group(g) => return is_json(&g.elem),
+        _ => return false,
+    };
+    tp.path.segments.last().is_some_and(|s| s.ident == "Json")
+}
+
 pub(crate) fn as_typepath(ty: &syn::Type) -> Option<&syn::TypePath> {
     match ty {
         syn::Type::Path(tp) => Some(tp),
@@ -0,0 +1,109 @@
+use crate::column::Column;
+use crate::errors::Result;
+use syn::punctuated::Punctuated;
+use syn::token::Comma;
+use syn::{Expr, Meta};
+
+/// The rules from #[welds(validate(...))] on a field
+#[derive(Clone)]
+pub(crate) struct Validation {
+    pub(crate) column: Column,
+    pub(crate) rules: Vec<Rule>,
+}
+
+#[derive(Clone)]
+pub(crate) enum Rule {
+    Length {
+        min: Option<Expr>,
+        max: Option<Expr>,
+    },
+    Range {
+        min: Option<Expr>,
+        max: Option<Expr>,
+    },
+    Regex(String),
+    NotEmpty,
+    Custom(syn::Path),
+}
+
+const BADFORMAT: &str = "Expected validate to be a list of the following rule(s):\n\
+    [ welds(validate(not_empty)) ]\n\
+    [ welds(validate(length(min = 1, max = 50))) ]\n\
+    [ welds(validate(range(min = 0, max = 100))) ]\n\
+    [ welds(validate(regex = \"^[a-z]+$\")) ]\n\
+    [ welds(validate(custom = \"path::to_fn\")) ]";
+
+impl Rule {
+    pub(crate) fn new(meta: &Meta) -> Result<Self> {
+        let badformat = || Err(BADFORMAT.to_owned());
+        match meta {
+            Meta::Path(path) if path.is_ident("not_empty") => Ok(Rule::NotEmpty),
+            Meta::List(list) if list.path.is_ident("length") => {
+                let (min, max) = read_min_max(list)?;
+                Ok(Rule::Length { min, max })
+            }
+            Meta::List(list) if list.path.is_ident("range") => {
+                let (min, max) = read_min_max(list)?;
+                Ok(Rule::Range { min, max })
+            }
+            Meta::NameValue(nv) if nv.path.is_ident("regex") => match lit_str(&nv.value) {
+                Some(pattern) => Ok(Rule::Regex(check_regex(pattern.value())?)),
+                None => badformat(),
+            },
+            Meta::NameValue(nv) if nv.path.is_ident("custom") => match lit_str(&nv.value) {
+                Some(path) => Ok(Rule::Custom(
+                    path.parse().map_err(|_| BADFORMAT.to_owned())?,
+                )),
+                None => badformat(),
+            },
+            _ => badformat(),
+        }
+    }
+}
+
+/// patterns that don't compile are a macro error, not a panic on the first save
+fn check_regex(pattern: String) -> Result<String> {
+    match regex_syntax::Parser::new().parse(&pattern) {
+        Ok(_) => Ok(pattern),
+        Err(err) => Err(format!(
+            "Invalid pattern in welds(validate(regex = \"{}\")):\n{}",
+            pattern, err
+        )),
+    }
+}
+
+/// reads the `min = ..` and `max = ..` of `length(..)` or `range(..)`
+fn read_min_max(list: &syn::MetaList) -> Result<(Option<Expr>, Option<Expr>)> {
+    let metas = list
+        .parse_args_with(Punctuated::<Meta, Comma>::parse_terminated)
+        .map_err(|_| BADFORMAT.to_owned())?;
+    let mut min = None;
+    let mut max = None;
+    for meta in metas {
+        let nv = match meta {
+            Meta::NameValue(nv) => nv,
+            _ => return Err(BADFORMAT.to_owned()),
+        };
+        if nv.path.is_ident("min") {
+            min = Some(nv.value);
+        } else if nv.path.is_ident("max") {
+            max = Some(nv.value);
+        } else {
+            return Err(BADFORMAT.to_owned());
+        }
+    }
+    if min.is_none() && max.is_none() {
+        return Err(BADFORMAT.to_owned());
+    }
+    Ok((min, max))
+}
+
+fn lit_str(expr: &Expr) -> Option<&syn::LitStr> {
+    match expr {
+        Expr::Lit(expr_lit) => match &expr_lit.lit {
+            syn::Lit::Str(s) => Some(s),
+            _ => None,
+        },
+        _ => None,
+    }
+}
@@ -1,4 +1,7 @@
+use welds::model_traits::ChangeSet;
+use welds::model_traits::hooks::BulkAffected;
 use welds::prelude::*;
+use welds::query::builder::UpdateBuilder;
 
 /// Define a struct the maps to the products table in the databases
 #[derive(Debug, WeldsModel)]
@@ -7,10 +10,13 @@ use welds::prelude::*;
 #[welds(BeforeCreate(before_create))]
 #[welds(AfterCreate(after_create, async = true))]
 #[welds(AfterCreate(after_create_second))]
-#[welds(BeforeUpdate(before_update))]
+#[welds(BeforeUpdate(before_update, changes = true))]
 #[welds(AfterUpdate(after_update))]
 #[welds(BeforeDelete(before_delete))]
 #[welds(AfterDelete(after_delete))]
+// Bulk operations have their own hooks, they are given the query
+#[welds(BeforeBulkUpdate(before_bulk_update))]
+#[welds(AfterBulkUpdate(after_bulk_update))]
 
 pub struct Product {
     #[welds(primary_key)]
@@ -43,8 +49,10 @@ fn after_create_second(product: &Product) {
     println!("After Create2: {:?}", product);
 }
 
-fn before_update(product: &mut Product) -> welds::errors::Result<()> {
+// `changes = true` also hands the hook the columns that are about to be updated
+fn before_update(product: &mut Product, changes: &ChangeSet) -> welds::errors::Result<()> {
     println!("Before Update: {:?}", product);
+    println!("Changed Columns: {:?}", changes.changed_columns());
     Ok(())
 }
 
@@ -61,6 +69,19 @@ fn after_delete(product: &Product) {
     println!("After Delete: {:?}", product);
 }
 
+fn before_bulk_update(query: &UpdateBuilder<Product>) -> welds::errors::Result<()> {
+    println!(
+        "Before Bulk Update: {}",
+        query.to_sql(welds::Syntax::Sqlite)
+    );
+    Ok(())
+}
+
+// `run` gives the number of rows, `run_returning` gives the rows themselves
+fn after_bulk_update(_query: &UpdateBuilder<Product>, affected: &BulkAffected<Product>) {
+    println!("After Bulk Update: {} rows", affected.count());
+}
+
 #[async_std::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
     pretty_env_logger::init();
@@ -78,6 +99,8 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     product.name = "test".to_string();
     product.save(&client).await?;
 
+    Product::all().set(|x| x.active, false).run(&client).await?;
+
     product.delete(&client).await?;
 
     eprintln!("Done");
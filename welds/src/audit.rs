@@ -0,0 +1,155 @@
+//! History tables for `#[welds(audited)]` models.
+//!
+//! Every insert, update and delete of an audited model made through a `DbState`
+//! writes a row into `<table>_history`, in the same transaction as the change.
+//! A transaction is started for the change unless the client is already a transaction.
+//! ```rust,ignore
+//! #[derive(WeldsModel, serde::Serialize)]
+//! #[welds(table = "orders")]
+//! #[welds(audited)]
+//! struct Order {
+//!     #[welds(primary_key)]
+//!     id: i32,
+//!     total: f64,
+//! }
+//!
+//! // record who made the change
+//! let client = ActorClient::new(pool, "user:42");
+//! let trans = client.begin().await?;
+//! order.save(&trans).await?;
+//! trans.commit().await?;
+//! ```
+//! The history row holds:
+//!  - `operation`: INSERT, UPDATE or DELETE
+//!  - `changed_at`: the database's `CURRENT_TIMESTAMP`
+//!  - `actor`: the client's actor, see `welds::connections::actor::ActorClient`
+//!  - `old_values`/`new_values`: the columns of the row as JSON. null for inserts/deletes
+//!
+//! Use `welds::migrations::create_history_table` in a migration to create the table.
+//!
+//! Audited models require the `json` feature and each field to be `serde::Serialize`.
+//!
+//! WARNING: Bulk inserts, updates and deletes do NOT write history rows.
+
+use crate::Client;
+use crate::errors::Result;
+use crate::model_traits::{TableIdent, TrackChanges};
+use crate::writers::NextParam;
+use crate::writers::TableWriter;
+use welds_connections::Transaction;
+
+/// The change being recorded
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Operation {
+    Insert,
+    Update,
+    Delete,
+}
+
+impl Operation {
+    /// The value written to the `operation` column
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Operation::Insert => "INSERT",
+            Operation::Update => "UPDATE",
+            Operation::Delete => "DELETE",
+        }
+    }
+}
+
+/// A column of an audited model that could not be written as JSON
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuditValueError {
+    pub column: String,
+    pub message: String,
+}
+
+impl std::fmt::Display for AuditValueError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "unable to write column {} as JSON: {}",
+            self.column, self.message
+        )
+    }
+}
+
+impl std::error::Error for AuditValueError {}
+
+/// The column values of an audited model, written to the history table as JSON
+///
+/// Built by the Welds Macros for `#[welds(audited)]` models
+#[cfg(feature = "json")]
+#[derive(Default)]
+pub struct AuditValues {
+    values: serde_json::Map<String, serde_json::Value>,
+}
+
+#[cfg(feature = "json")]
+impl AuditValues {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds the value of a column. Fails if the value can't be written as JSON
+    pub fn add<V: serde::Serialize + ?Sized>(
+        &mut self,
+        column: &str,
+        value: &V,
+    ) -> std::result::Result<(), AuditValueError> {
+        let value = serde_json::to_value(value).map_err(|err| AuditValueError {
+            column: column.to_owned(),
+            message: err.to_string(),
+        })?;
+        self.values.insert(column.to_owned(), value);
+        Ok(())
+    }
+
+    /// The values as a JSON object
+    pub fn into_json(self) -> String {
+        serde_json::Value::Object(self.values).to_string()
+    }
+}
+
+/// The history table of a table, `<table>_history` in the same schema
+pub fn history_table(table: &TableIdent) -> TableIdent {
+    TableIdent::new(format!("{}_history", table.name()), table.schema())
+}
+
+/// Starts the transaction a change to an audited T and its history row are written in.
+///
+/// `None` if T is not audited or the client is already a transaction
+#[maybe_async::maybe_async]
+pub(crate) async fn begin<'t, T: TrackChanges>(
+    client: &'t dyn Client,
+) -> Result<Option<Transaction<'t>>> {
+    if !T::AUDITED {
+        return Ok(None);
+    }
+    Ok(client.begin_if_needed().await?)
+}
+
+/// Writes a row into the history table
+#[maybe_async::maybe_async]
+pub(crate) async fn record(
+    client: &dyn Client,
+    table: &TableIdent,
+    operation: Operation,
+    old_values: Option<String>,
+    new_values: Option<String>,
+) -> Result<()> {
+    let syntax = client.syntax();
+    let history = TableWriter::new(syntax).write(&history_table(table));
+    let next_params = NextParam::new(syntax);
+    let params: Vec<String> = (0..4).map(|_| next_params.next()).collect();
+    let sql = format!(
+        "INSERT INTO {history} (operation, changed_at, actor, old_values, new_values) VALUES ({}, CURRENT_TIMESTAMP, {}, {}, {})",
+        params[0], params[1], params[2], params[3]
+    );
+    let operation = operation.as_str().to_owned();
+    let actor: Option<String> = client.actor().map(|a| a.to_owned());
+    client
+        .execute(&sql, &[&operation, &actor, &old_values, &new_values])
+        .await?;
+    Ok(())
+}
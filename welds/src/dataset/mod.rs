@@ -1,10 +1,16 @@
-use crate::model_traits::{HasSchema, TableColumns, TableInfo, UniqueIdentifier};
+use crate::model_traits::{HasSchema, TableColumns, TableInfo};
 use crate::query::include::related_query::{RelatedSetAccesser, SetDowncast};
 use crate::relations::RelationshipCompare;
 use crate::relations::{HasRelations, Relationship};
 use crate::state::DbState;
+use std::marker::PhantomData;
 use std::ops::Deref;
 
+#[cfg(feature = "serde")]
+mod serialize;
+#[cfg(feature = "serde")]
+pub use serialize::SerializeIncluded;
+
 /// A Collection object that hold a set of data that has been
 /// selected out of the database and its related objects
 ///
@@ -41,46 +47,54 @@ use std::ops::Deref;
 /// }
 /// ```
 ///
-pub struct DataSet<T> {
+/// `I` lists the models that were included, see `IncludeBuilder`
+pub struct DataSet<T, I = ()> {
     // not sure if we want to use state or not
     primary: Vec<DbState<T>>,
     related: Vec<Box<dyn RelatedSetAccesser + Send>>,
+    included: PhantomData<fn() -> I>,
 }
 
-impl<T> DataSet<T> {
+impl<T, I> DataSet<T, I> {
     pub(crate) fn new(
         primary: Vec<DbState<T>>,
         related: Vec<Box<dyn RelatedSetAccesser + Send>>,
     ) -> Self {
-        Self { primary, related }
+        Self {
+            primary,
+            related,
+            included: PhantomData,
+        }
     }
 
     pub fn iter(&self) -> DataSetIter<'_, T> {
         DataSetIter {
             index: 0,
-            inner: self,
+            primary: &self.primary,
+            related: &self.related,
         }
     }
 }
 
 pub struct DataSetIter<'t, T> {
     index: usize,
-    inner: &'t DataSet<T>,
+    primary: &'t [DbState<T>],
+    related: &'t [Box<dyn RelatedSetAccesser + Send>],
 }
 
 impl<'t, T> Iterator for DataSetIter<'t, T> {
     type Item = DataAccessGuard<'t, T>;
     fn next(&mut self) -> Option<Self::Item> {
-        let obj: &DbState<T> = self.inner.primary.get(self.index)?;
+        let obj: &DbState<T> = self.primary.get(self.index)?;
         self.index += 1;
         Some(DataAccessGuard {
             inner: obj,
-            sets: self.inner,
+            related: self.related,
         })
     }
 }
 
-impl<T> DataSet<T> {
+impl<T, I> DataSet<T, I> {
     /// Returns the count of the primary selected object
     pub fn len(&self) -> usize {
         self.primary.len()
@@ -96,20 +110,112 @@ impl<T> DataSet<T> {
         let obj = self.primary.get(index)?;
         Some(DataAccessGuard {
             inner: obj,
-            sets: self,
+            related: &self.related,
+        })
+    }
+}
+
+impl<T, I> DataSet<T, I>
+where
+    T: 'static + HasSchema + HasRelations,
+    <T as HasSchema>::Schema: TableInfo + TableColumns,
+{
+    /// Consumes the dataset, pairing each selected object with its related objects.
+    /// Related objects are cloned, they can be related to more than one object.
+    /// The related list is empty if the relationship was NOT included in the query.
+    /// ```rust,ignore
+    /// let dataset = Customer::all().include(|c| c.orders).run(db).await?;
+    /// let customers: Vec<(Customer, Vec<Order>)> = dataset.into_nested(|c| c.orders);
+    /// ```
+    pub fn into_nested<R, Ship>(
+        self,
+        relationship: impl Fn(<T as HasRelations>::Relation) -> Ship,
+    ) -> Vec<(T, Vec<R>)>
+    where
+        Ship: 'static + Relationship<T, R> + RelationshipCompare<T, R>,
+        R: 'static + Send + Sync + Clone + HasSchema,
+        <R as HasSchema>::Schema: TableInfo + TableColumns,
+    {
+        let ship = relationship(Default::default());
+        let related_set = self
+            .related
+            .iter()
+            .filter_map(|rset| rset.downcast_ref::<T, R, Ship>())
+            .find(|related_set| related_set.ship == ship);
+
+        self.primary
+            .into_iter()
+            .map(|state| {
+                let t = state.into_inner();
+                let related = match related_set {
+                    Some(related_set) => related_set
+                        .data
+                        .iter()
+                        .filter(|d| related_set.is_related(&t, d))
+                        .cloned()
+                        .collect(),
+                    None => Vec::default(),
+                };
+                (t, related)
+            })
+            .collect()
+    }
+}
+
+/// The related objects of rows that were already selected,
+/// loaded with `welds::query::include::load_related`
+///
+/// Accessed the same as a `DataSet`
+pub struct LoadedSet<'t, T> {
+    primary: &'t [DbState<T>],
+    related: Vec<Box<dyn RelatedSetAccesser + Send>>,
+}
+
+impl<'t, T> LoadedSet<'t, T> {
+    pub(crate) fn new(
+        primary: &'t [DbState<T>],
+        related: Vec<Box<dyn RelatedSetAccesser + Send>>,
+    ) -> Self {
+        Self { primary, related }
+    }
+
+    /// Returns the count of the rows the objects were loaded for
+    pub fn len(&self) -> usize {
+        self.primary.len()
+    }
+
+    /// Returns true if there are no rows
+    pub fn is_empty(&self) -> bool {
+        self.primary.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = DataAccessGuard<'_, T>> {
+        self.primary.iter().map(|obj| DataAccessGuard {
+            inner: obj.as_ref(),
+            related: &self.related,
+        })
+    }
+
+    /// access a <T> at a given index.
+    pub fn get(&self, index: usize) -> Option<DataAccessGuard<'_, T>> {
+        let obj = self.primary.get(index)?;
+        Some(DataAccessGuard {
+            inner: obj,
+            related: &self.related,
         })
     }
 }
 
 pub struct DataAccessGuard<'t, T> {
-    inner: &'t DbState<T>,
-    sets: &'t DataSet<T>,
+    inner: &'t T,
+    // the included sets this object can read from, nested includes for included objects
+    related: &'t [Box<dyn RelatedSetAccesser + Send>],
 }
 
 impl<'t, T> DataAccessGuard<'t, T> {
     #[allow(clippy::should_implement_trait)]
     pub fn as_ref(&self) -> &'t T {
-        self.inner.as_ref()
+        self.inner
     }
 }
 
@@ -138,20 +244,20 @@ where
         Ship: 'static + Relationship<T, R>,
         R: HasSchema,
         R: 'static + Send + Sync + HasSchema,
-        <R as HasSchema>::Schema: TableInfo + TableColumns + UniqueIdentifier,
-        <T as HasSchema>::Schema: TableInfo + TableColumns + UniqueIdentifier,
+        <R as HasSchema>::Schema: TableInfo + TableColumns,
+        <T as HasSchema>::Schema: TableInfo + TableColumns,
         Ship: RelationshipCompare<T, R>,
     {
-        let t: &T = self.inner.as_ref();
+        let t: &T = self.inner;
         // find the set of data that would fit
-        for rset in &self.sets.related {
+        for rset in self.related {
             if let Some(related_set) = rset.downcast_ref::<T, R, Ship>() {
                 // check that we are working with the same relationship
                 let ship = relationship(Default::default());
                 if related_set.ship == ship {
                     let mut set = Vec::default();
                     for d in &related_set.data {
-                        if ship.is_related(t, d) {
+                        if related_set.is_related(t, d) {
                             set.push(d);
                         }
                     }
@@ -177,20 +283,20 @@ where
         R: HasSchema + ToOwned<Owned = R>,
         R: HasSchema,
         R: 'static + Send + Sync + HasSchema,
-        <R as HasSchema>::Schema: TableInfo + TableColumns + UniqueIdentifier,
-        <T as HasSchema>::Schema: TableInfo + TableColumns + UniqueIdentifier,
+        <R as HasSchema>::Schema: TableInfo + TableColumns,
+        <T as HasSchema>::Schema: TableInfo + TableColumns,
         Ship: RelationshipCompare<T, R>,
     {
-        let t: &T = self.inner.as_ref();
+        let t: &T = self.inner;
         // find the set of data that would fit
-        for rset in &self.sets.related {
+        for rset in self.related {
             if let Some(related_set) = rset.downcast_ref::<T, R, Ship>() {
                 // check that we are working with the same relationship
                 let ship = relationship(Default::default());
                 if related_set.ship == ship {
                     let mut set = Vec::default();
                     for d in &related_set.data {
-                        if ship.is_related(t, d) {
+                        if related_set.is_related(t, d) {
                             set.push(d.to_owned());
                         }
                     }
@@ -200,4 +306,39 @@ where
         }
         Vec::default()
     }
+
+    /// Gets other objects related to this object, with access to their nested includes.
+    /// See `IncludeBuilder::then_include`
+    /// Returns an empty list if the relationship was NOT included in the query.
+    pub fn get_nested<R, Ship>(
+        &self,
+        relationship: impl Fn(<T as HasRelations>::Relation) -> Ship,
+    ) -> Vec<DataAccessGuard<'t, R>>
+    where
+        T: 'static + HasRelations,
+        Ship: 'static + Relationship<T, R>,
+        R: 'static + Send + Sync + HasSchema,
+        <R as HasSchema>::Schema: TableInfo + TableColumns,
+        <T as HasSchema>::Schema: TableInfo + TableColumns,
+        Ship: RelationshipCompare<T, R>,
+    {
+        let t: &T = self.inner;
+        for rset in self.related {
+            if let Some(related_set) = rset.downcast_ref::<T, R, Ship>() {
+                let ship = relationship(Default::default());
+                if related_set.ship == ship {
+                    return related_set
+                        .data
+                        .iter()
+                        .filter(|d| related_set.is_related(t, d))
+                        .map(|d| DataAccessGuard {
+                            inner: d,
+                            related: &related_set.nested,
+                        })
+                        .collect();
+                }
+            }
+        }
+        Vec::default()
+    }
 }
@@ -0,0 +1,86 @@
+use super::DataSet;
+use crate::query::include::Included;
+use crate::query::include::related_query::RelatedSetAccesser;
+use crate::relations::HasRelations;
+use crate::relations::describe::DescribeFn;
+use serde::ser::{Error, Serialize, Serializer};
+use serde_json::Value;
+use std::any::Any;
+
+/// Serializes into a list of the selected rows.
+/// Each included relationship is added to its rows as a list,
+/// named by its field on `HasRelations::Relation`
+/// ```rust,ignore
+/// let dataset = Customer::all().include(|c| c.orders).run(db).await?;
+/// let json = serde_json::to_string(&dataset)?;
+/// // [{"id":1,"name":"Bob","orders":[{"id":4,"customer_id":1}]}]
+/// ```
+/// The included models must also be `serde::Serialize`.
+/// Relationships that were not included are not checked
+impl<T, I> Serialize for DataSet<T, I>
+where
+    T: 'static + Serialize + HasRelations,
+    I: SerializeIncluded,
+{
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut rows = Vec::with_capacity(self.primary.len());
+        for row in &self.primary {
+            let row: &T = row.as_ref();
+            let value = serde_json::to_value(row).map_err(S::Error::custom)?;
+            let value = with_related(value, row, &self.related, T::describe_relation)
+                .map_err(S::Error::custom)?;
+            rows.push(value);
+        }
+        rows.serialize(serializer)
+    }
+}
+
+/// Checks at compile time that the models included in a `DataSet` are `serde::Serialize`,
+/// along with the models included with them by `then_include`
+pub trait SerializeIncluded {}
+
+impl SerializeIncluded for () {}
+
+impl<I, R, N> SerializeIncluded for (I, Included<R, N>)
+where
+    I: SerializeIncluded,
+    R: Serialize,
+    N: SerializeIncluded,
+{
+}
+
+// adds the rows related to `row` from each set onto its JSON object
+fn with_related(
+    mut value: Value,
+    row: &dyn Any,
+    sets: &[Box<dyn RelatedSetAccesser + Send>],
+    describe: DescribeFn,
+) -> Result<Value, String> {
+    for set in sets {
+        let info = describe(set.ship()).ok_or("Unknown relationship in DataSet")?;
+        // checked by `SerializeIncluded`, the included models are Serialize
+        let to_value = info.json.to_value.ok_or_else(|| {
+            format!(
+                "The included model of `{}` doesn't implement serde::Serialize",
+                info.name
+            )
+        })?;
+        let mut related = Vec::default();
+        for other in set.related_to(row, info.compare) {
+            let other_value = to_value(other)
+                .ok_or_else(|| format!("Failed to serialize the `{}` relationship", info.name))?;
+            let other_value = match info.describe {
+                Some(describe) => with_related(other_value, other, set.nested(), describe)?,
+                None => other_value,
+            };
+            related.push(other_value);
+        }
+        match &mut value {
+            Value::Object(map) => {
+                map.insert(info.name.to_owned(), Value::Array(related));
+            }
+            _ => return Err("Models with includes must serialize into JSON objects".to_owned()),
+        }
+    }
+    Ok(value)
+}
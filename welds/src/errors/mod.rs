@@ -1,4 +1,5 @@
-use crate::model_traits::TableIdent;
+use crate::audit::AuditValueError;
+use crate::model_traits::{TableIdent, ValidationErrors};
 use thiserror::Error;
 pub use welds_connections::Error as ConnError;
 
@@ -26,6 +27,14 @@ pub enum WeldsError {
     DuplicateMigration,
     #[error("An underlying Hook canceled the action")]
     ActionCanceled,
+    #[error("The row was changed or removed by someone else since it was loaded")]
+    StaleObject,
+    #[error("Not a valid table name: {0}")]
+    InvalidTableName(String),
+    #[error("The model is not valid: {0}")]
+    Validation(ValidationErrors),
+    #[error("The history of an audited model could not be written: {0}")]
+    Audit(#[from] AuditValueError),
     #[error(
         "Multiple tables exist with this table. Use `table_search` to search return all results"
     )]
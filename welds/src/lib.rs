@@ -154,9 +154,11 @@
 
 pub mod errors;
 pub use errors::WeldsError;
+pub mod audit;
 pub mod dataset;
 pub mod exts;
 pub mod model_traits;
+pub mod observers;
 pub mod query;
 pub mod relations;
 pub mod state;
@@ -182,3 +184,13 @@ pub use welds_connections::{Client, Row, Syntax, TransactStart};
 
 /// Re-export the Macro used to make models
 pub use welds_macros::WeldsModel;
+
+/// Re-export the Macro used to store enums in a column
+pub use welds_macros::WeldsEnum;
+
+/// Re-export the Macro used to embed a group of columns into models
+pub use welds_macros::WeldsEmbed;
+
+/// A serde value stored in a JSON column
+#[cfg(feature = "json")]
+pub use welds_connections::json::Json;
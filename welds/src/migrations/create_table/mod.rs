@@ -37,13 +37,37 @@ pub fn create_table(name: impl Into<String>) -> TableBuilder {
     TableBuilder {
         ident,
         pk: IdBuilder::default(),
+        composite_pk: Vec::default(),
         columns: Vec::default(),
     }
 }
 
+/// Builds a migration that creates the history table of a `#[welds(audited)]` model.
+/// `<table>_history`, see `welds::audit`
+/// ```
+/// use welds::errors::Result;
+/// use welds::migrations::{create_history_table, MigrationStep, TableState};
+///
+/// fn create_orders_history(_state: &TableState) -> Result<MigrationStep> {
+///     let m = create_history_table("orders");
+///     Ok(MigrationStep::new("m20250301120000_create_orders_history", m))
+/// }
+/// ```
+pub fn create_history_table(table: impl Into<String>) -> TableBuilder {
+    let table = crate::audit::history_table(&TableIdent::parse(&table.into()));
+    create_table(table.to_string())
+        .id(|c| c("id", Type::IntBig))
+        .column(|c| c("operation", Type::StringSized(10)))
+        .column(|c| c("changed_at", Type::Datetime))
+        .column(|c| c("actor", Type::String).is_null())
+        .column(|c| c("old_values", Type::Text).is_null())
+        .column(|c| c("new_values", Type::Text).is_null())
+}
+
 pub struct TableBuilder {
     pub(crate) ident: TableIdent,
     pub(crate) pk: IdBuilder,
+    pub(crate) composite_pk: Vec<String>,
     pub(crate) columns: Vec<ColumnBuilder>,
 }
 
@@ -64,6 +88,23 @@ impl TableBuilder {
         self
     }
 
+    /// Use several columns as the primary key of the table, instead of an id column.
+    ///
+    /// The columns are added with `column` like any other column.
+    /// ```
+    /// use welds::migrations::{create_table, types::Type};
+    ///
+    /// let m = create_table("order_lines")
+    ///     .primary_key(["order_id", "line_no"])
+    ///     .column(|c| c("order_id", Type::Int))
+    ///     .column(|c| c("line_no", Type::Int))
+    ///     .column(|c| c("sku", Type::String));
+    /// ```
+    pub fn primary_key<S: Into<String>>(mut self, columns: impl IntoIterator<Item = S>) -> Self {
+        self.composite_pk = columns.into_iter().map(|c| c.into()).collect();
+        self
+    }
+
     /// Add a column to the table
     pub fn column(mut self, lam: fn(ColumnLambda) -> ColumnBuilder) -> Self {
         let builder = |name: &str, ty: Type| -> ColumnBuilder {
@@ -78,3 +78,33 @@ fn should_drop_basic_table() {
     let sql2 = MigrationWriter::down_sql(&m, Syntax::Postgres).join("; ");
     assert_eq!(sql2, expected.trim());
 }
+
+#[test]
+fn should_create_table_with_composite_key() {
+    let m = create_table("order_lines")
+        .primary_key(["order_id", "line_no"])
+        .column(|c| c("order_id", Type::Int))
+        .column(|c| c("line_no", Type::Int))
+        .column(|c| c("sku", Type::String));
+
+    //mysql
+    let sql = MigrationWriter::up_sql(&m, Syntax::Mysql).join("; ");
+    let expected = r#"
+    CREATE TABLE order_lines ( order_id INT NOT NULL, line_no INT NOT NULL, sku VARCHAR(255) NOT NULL, PRIMARY KEY (order_id, line_no) )"#;
+    assert_eq!(sql, expected.trim());
+
+    //postgres
+    let sql = MigrationWriter::up_sql(&m, Syntax::Postgres).join("; ");
+    let expected = r#"
+    CREATE TABLE order_lines ( "order_id" INT NOT NULL, "line_no" INT NOT NULL, "sku" TEXT NOT NULL, PRIMARY KEY ("order_id", "line_no") )"#;
+    assert_eq!(sql, expected.trim());
+}
+
+#[test]
+fn should_create_a_history_table_next_to_the_table() {
+    let m = create_history_table("s1.orders");
+    let sql = MigrationWriter::up_sql(&m, Syntax::Sqlite).join("; ");
+    let expected = r#"
+    CREATE TABLE s1.orders_history ( "id" INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL, "operation" TEXT NOT NULL, "changed_at" DATETIME NOT NULL, "actor" TEXT NULL, "old_values" TEXT NULL, "new_values" TEXT NULL )"#;
+    assert_eq!(sql, expected.trim());
+}
@@ -15,6 +15,7 @@ pub mod writers;
 
 mod create_table;
 pub mod types;
+pub use create_table::create_history_table;
 pub use create_table::create_table;
 mod tablemod;
 pub use tablemod::change_table;
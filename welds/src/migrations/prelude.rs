@@ -4,6 +4,7 @@ pub use crate::migrations::MigrationStep;
 pub use crate::migrations::MigrationWriter;
 pub use crate::migrations::Steps;
 pub use crate::migrations::TableState;
+pub use crate::migrations::create_history_table;
 pub use crate::migrations::create_table;
 pub use crate::migrations::down;
 pub use crate::migrations::down_last;
@@ -48,6 +48,21 @@ fn down_should_recreate_the_table() {
     assert_eq!(sql, expected.trim());
 }
 
+#[test]
+fn down_should_recreate_a_table_with_a_composite_key() {
+    let table = Table::mock(
+        MockTableDef::new(Syntax::Postgres, "s2.order_lines")
+            .with_pk("order_id", "INT")
+            .with_pk("line_no", "INT")
+            .with_column("sku", "TEXT"),
+    );
+    let m = table.drop();
+    let expected = r#"
+    CREATE TABLE s2.order_lines ( "order_id" INT NOT NULL, "line_no" INT NOT NULL, "sku" TEXT NOT NULL, PRIMARY KEY ("order_id", "line_no") )"#;
+    let sql = MigrationWriter::down_sql(&m, Syntax::Postgres).join("; ");
+    assert_eq!(sql, expected.trim());
+}
+
 #[test]
 fn should_be_able_to_rename_column() {
     let table = mock_table(Syntax::Postgres);
@@ -170,6 +170,15 @@ mod tests {
         assert_eq!(&db_type(Syntax::Mssql), "SMALLINT");
     }
 
+    #[test]
+    fn type_should_be_a_json_column() {
+        let db_type = |s| Type::Json.db_type(s);
+        assert_eq!(&db_type(Syntax::Sqlite), "TEXT");
+        assert_eq!(&db_type(Syntax::Mysql), "JSON");
+        assert_eq!(&db_type(Syntax::Postgres), "JSONB");
+        assert_eq!(&db_type(Syntax::Mssql), "NVARCHAR(MAX)");
+    }
+
     #[test]
     fn no_db_type_should_panic() {
         let syntaxes = [
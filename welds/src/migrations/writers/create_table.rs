@@ -15,8 +15,15 @@ pub fn from_def(syntax: Syntax, def: &TableDef) -> Vec<String> {
     assert_eq!(syntax, def.syntax());
 
     let mut columns: Vec<String> = Vec::default();
+    let pks: Vec<&str> = def
+        .columns()
+        .iter()
+        .filter(|x| x.primary_key)
+        .map(|x| x.name.as_str())
+        .collect();
+    let composite = pks.len() > 1;
 
-    for c in def.columns().iter().filter(|x| x.primary_key) {
+    for c in def.columns().iter().filter(|x| x.primary_key && !composite) {
         let pk_type = pk_override(syntax, &c.ty).unwrap_or(&c.ty);
         let col = IdBuilder {
             name: c.name.to_string(),
@@ -25,7 +32,11 @@ pub fn from_def(syntax: Syntax, def: &TableDef) -> Vec<String> {
         columns.push(build_id_column(syntax, &col))
     }
 
-    for c in def.columns().iter().filter(|&x| !x.primary_key) {
+    for c in def
+        .columns()
+        .iter()
+        .filter(|&x| composite || !x.primary_key)
+    {
         let col = ColumnBuilder {
             name: c.name.to_string(),
             ty: Type::parse_db_type(syntax, c.ty()),
@@ -35,6 +46,9 @@ pub fn from_def(syntax: Syntax, def: &TableDef) -> Vec<String> {
         };
         columns.push(build_column(syntax, &col))
     }
+    if composite {
+        columns.push(build_composite_key(syntax, &pks));
+    }
 
     let tablename = TableWriter::new(syntax).write(def.ident());
 
@@ -47,7 +61,16 @@ pub fn from_def(syntax: Syntax, def: &TableDef) -> Vec<String> {
 }
 
 pub fn from_builder(syntax: Syntax, tb: &TableBuilder) -> Vec<String> {
-    let mut columns: Vec<String> = build_columns(syntax, &tb.pk, &tb.columns);
+    let mut columns: Vec<String> = match tb.composite_pk.is_empty() {
+        true => build_columns(syntax, &tb.pk, &tb.columns),
+        false => {
+            let pks: Vec<&str> = tb.composite_pk.iter().map(|c| c.as_str()).collect();
+            let mut parts: Vec<String> =
+                tb.columns.iter().map(|c| build_column(syntax, c)).collect();
+            parts.push(build_composite_key(syntax, &pks));
+            parts
+        }
+    };
 
     // some FK indexs need to be made inline with the Table.
     let mut inline_indexes: Vec<String> = tb
@@ -91,6 +114,13 @@ fn build_id_column(syntax: Syntax, col: &IdBuilder) -> String {
     format!("{name} {ty} {tail} NOT NULL")
 }
 
+/// A primary key made up of several columns, written as a table constraint
+fn build_composite_key(syntax: Syntax, names: &[&str]) -> String {
+    let writer = ColumnWriter::new(syntax);
+    let names: Vec<String> = names.iter().map(|n| writer.excape(n)).collect();
+    format!("PRIMARY KEY ({})", names.join(", "))
+}
+
 fn build_column(syntax: Syntax, col: &ColumnBuilder) -> String {
     let name = ColumnWriter::new(syntax).excape(col.name.as_str());
     let ty: String = col.ty.db_type(syntax);
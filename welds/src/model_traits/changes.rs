@@ -0,0 +1,292 @@
+use crate::audit::AuditValueError;
+use std::any::Any;
+use std::hash::{Hash, Hasher};
+
+/// Captures the values of a model's columns so changes can be detected on save
+///
+/// This is AUTOMATICALLY implemented by the Welds Macros
+pub trait TrackChanges {
+    /// true for `#[welds(audited)]` models, their changes are written to a history table
+    const AUDITED: bool = false;
+
+    /// Returns a copy of the current value of each column that can be updated
+    fn snapshot(&self) -> Snapshot;
+
+    /// Returns a copy of the current value of each primary key column
+    fn primary_key_snapshot(&self) -> Snapshot {
+        Snapshot::default()
+    }
+
+    /// The column values written to the history table as JSON, `None` unless `#[welds(audited)]`
+    ///
+    /// Fails if a column can't be written as JSON
+    fn audit_values(&self) -> Result<Option<String>, AuditValueError> {
+        Ok(None)
+    }
+}
+
+/// A column value stored in a snapshot. Any `Clone + PartialEq` type can be tracked
+pub trait ColumnValue: Send + Sync {
+    fn as_any(&self) -> &dyn Any;
+    /// true if the other value is the same type and equal to this value
+    fn same_as(&self, other: &dyn ColumnValue) -> bool;
+}
+
+impl<V> ColumnValue for V
+where
+    V: 'static + Clone + PartialEq + Send + Sync,
+{
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+    fn same_as(&self, other: &dyn ColumnValue) -> bool {
+        other.as_any().downcast_ref::<V>() == Some(self)
+    }
+}
+
+/// The value of a column that rows can be grouped by. Any `Clone + Hash + Eq` type can be a key
+pub struct ColumnKey(Box<dyn KeyValue>);
+
+impl ColumnKey {
+    pub fn new<V>(value: V) -> Self
+    where
+        V: 'static + Hash + Eq + Send + Sync,
+    {
+        Self(Box::new(value))
+    }
+
+    /// true if both keys hold the same type of value
+    pub(crate) fn same_type(&self, other: &ColumnKey) -> bool {
+        self.0.as_any().type_id() == other.0.as_any().type_id()
+    }
+}
+
+trait KeyValue: Send + Sync {
+    fn as_any(&self) -> &dyn Any;
+    fn same_as(&self, other: &dyn KeyValue) -> bool;
+    fn hash_value(&self, state: &mut dyn Hasher);
+}
+
+impl<V> KeyValue for V
+where
+    V: 'static + Hash + Eq + Send + Sync,
+{
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+    fn same_as(&self, other: &dyn KeyValue) -> bool {
+        other.as_any().downcast_ref::<V>() == Some(self)
+    }
+    fn hash_value(&self, mut state: &mut dyn Hasher) {
+        self.hash(&mut state)
+    }
+}
+
+impl PartialEq for ColumnKey {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.same_as(other.0.as_ref())
+    }
+}
+
+impl Eq for ColumnKey {}
+
+impl Hash for ColumnKey {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.hash_value(state)
+    }
+}
+
+/// The values of a model's columns at a point in time.
+///
+/// A column without a value could not be copied (the field isn't `Clone + PartialEq`).
+/// These columns are always considered changed.
+pub struct Snapshot {
+    values: Vec<(&'static str, Option<Box<dyn ColumnValue>>)>,
+    // The audit_values of `#[welds(audited)]` models
+    pub(crate) audit_values: Result<Option<String>, AuditValueError>,
+}
+
+impl Default for Snapshot {
+    fn default() -> Self {
+        Self::new(Vec::new())
+    }
+}
+
+impl Snapshot {
+    pub fn new(values: Vec<(&'static str, Option<Box<dyn ColumnValue>>)>) -> Self {
+        Self {
+            values,
+            audit_values: Ok(None),
+        }
+    }
+
+    /// The names of the columns in the snapshot
+    pub(crate) fn columns(&self) -> Vec<&'static str> {
+        self.values.iter().map(|(name, _)| *name).collect()
+    }
+
+    pub(crate) fn get(&self, column: &str) -> Option<&dyn ColumnValue> {
+        self.values
+            .iter()
+            .find(|(name, _)| *name == column)
+            .and_then(|(_, value)| value.as_deref())
+    }
+}
+
+/// Snapshots a model along with its audit values. Used by the macros as the `HasSchema::tracker`
+#[doc(hidden)]
+pub fn track<T: TrackChanges>(obj: &T) -> Snapshot {
+    let mut snapshot = obj.snapshot();
+    snapshot.audit_values = obj.audit_values();
+    snapshot
+}
+
+/// The difference between a model as it was loaded from the database and as it is now
+///
+/// Passed to `BeforeUpdate` hooks that ask for it
+/// ```rust,ignore
+/// #[welds(BeforeUpdate(log_changes, changes = true))]
+/// ```
+pub struct ChangeSet<'s> {
+    old: Baseline<'s>,
+    new: Snapshot,
+}
+
+/// What the current values are compared against
+enum Baseline<'s> {
+    /// The values when the model was loaded
+    Snapshot(&'s Snapshot),
+    /// The model is known to be unchanged
+    Unchanged,
+    /// Nothing to compare against, everything is considered changed
+    Unknown,
+}
+
+impl<'s> ChangeSet<'s> {
+    /// Compares the current state of a model against a snapshot taken when it was loaded.
+    /// Without a snapshot all columns are considered changed
+    pub fn new<T: TrackChanges>(obj: &T, old: Option<&'s Snapshot>) -> Self {
+        let old = match old {
+            Some(snapshot) => Baseline::Snapshot(snapshot),
+            None => Baseline::Unknown,
+        };
+        Self {
+            old,
+            new: obj.snapshot(),
+        }
+    }
+
+    /// A ChangeSet for a model that hasn't been touched since it was loaded
+    pub(crate) fn unchanged<T: TrackChanges>(obj: &T) -> Self {
+        Self {
+            old: Baseline::Unchanged,
+            new: obj.snapshot(),
+        }
+    }
+
+    /// The names of the columns whose values have changed
+    pub fn changed_columns(&self) -> Vec<&'static str> {
+        self.new
+            .values
+            .iter()
+            .map(|(name, _)| *name)
+            .filter(|name| self.is_changed(name))
+            .collect()
+    }
+
+    /// Returns true if the value of this column has changed
+    pub fn is_changed(&self, column: &str) -> bool {
+        let old = match self.old {
+            Baseline::Snapshot(old) => old,
+            Baseline::Unchanged => return false,
+            Baseline::Unknown => return true,
+        };
+        match (old.get(column), self.new.get(column)) {
+            (Some(old), Some(new)) => !new.same_as(old),
+            _ => true,
+        }
+    }
+
+    /// Returns true if no columns have changed
+    pub fn is_empty(&self) -> bool {
+        self.changed_columns().is_empty()
+    }
+
+    /// The value of a column when the model was loaded from the database
+    pub fn old_value<V: 'static>(&self, column: &str) -> Option<&V> {
+        let old = match self.old {
+            Baseline::Snapshot(old) => old.get(column),
+            Baseline::Unchanged => self.new.get(column),
+            Baseline::Unknown => None,
+        };
+        old?.as_any().downcast_ref::<V>()
+    }
+
+    /// The current value of a column
+    pub fn new_value<V: 'static>(&self, column: &str) -> Option<&V> {
+        self.new.get(column)?.as_any().downcast_ref::<V>()
+    }
+}
+
+// ******************************************************************************************
+// Used by the macros to copy a field into a snapshot.
+// Fields that are `Clone + PartialEq` are copied, everything else falls back to `None`
+// ******************************************************************************************
+
+#[doc(hidden)]
+pub struct FieldProbe<'a, V>(pub &'a V);
+
+#[doc(hidden)]
+pub trait TrackedField {
+    fn snapshot_value(&self) -> Option<Box<dyn ColumnValue>>;
+}
+
+impl<V> TrackedField for &FieldProbe<'_, V>
+where
+    V: 'static + Clone + PartialEq + Send + Sync,
+{
+    fn snapshot_value(&self) -> Option<Box<dyn ColumnValue>> {
+        Some(Box::new(self.0.clone()))
+    }
+}
+
+#[doc(hidden)]
+pub trait UntrackedField {
+    fn snapshot_value(&self) -> Option<Box<dyn ColumnValue>>;
+}
+
+impl<V> UntrackedField for FieldProbe<'_, V> {
+    fn snapshot_value(&self) -> Option<Box<dyn ColumnValue>> {
+        None
+    }
+}
+
+// ******************************************************************************************
+// Used by the macros to read a column as a key.
+// Fields that are `Clone + Hash + Eq` are copied, everything else falls back to `None`
+// ******************************************************************************************
+
+#[doc(hidden)]
+pub trait KeyedField {
+    fn column_key(&self) -> Option<ColumnKey>;
+}
+
+impl<V> KeyedField for &FieldProbe<'_, V>
+where
+    V: 'static + Clone + Hash + Eq + Send + Sync,
+{
+    fn column_key(&self) -> Option<ColumnKey> {
+        Some(ColumnKey::new(self.0.clone()))
+    }
+}
+
+#[doc(hidden)]
+pub trait UnkeyedField {
+    fn column_key(&self) -> Option<ColumnKey>;
+}
+
+impl<V> UnkeyedField for FieldProbe<'_, V> {
+    fn column_key(&self) -> Option<ColumnKey> {
+        None
+    }
+}
@@ -0,0 +1,94 @@
+use super::Column;
+use super::changes::ColumnValue;
+use crate::Row;
+use crate::errors::Result;
+use crate::query::clause::ParamArgs;
+use std::sync::OnceLock;
+
+/// A group of columns shared by several tables, embedded into a model with `#[welds(flatten)]`
+///
+/// This is AUTOMATICALLY implemented by `#[derive(WeldsEmbed)]`
+/// ```rust,ignore
+/// #[derive(WeldsEmbed)]
+/// struct Address {
+///     street: String,
+///     city: String,
+/// }
+///
+/// #[derive(WeldsModel)]
+/// #[welds(table = "customers")]
+/// struct Customer {
+///     #[welds(primary_key)]
+///     id: i32,
+///     #[welds(flatten)]
+///     address: Address,
+///     #[welds(flatten(prefix = "billing_"))]
+///     billing: Address,
+/// }
+///
+/// let query = Customer::where_col(|c| c.billing.city.equal("Paris"));
+/// ```
+/// The columns of a group are added to the columns of the model,
+/// with the prefix in front of their names. (`billing_street`, `billing_city`)
+pub trait Embedded: Sized {
+    /// The columns of the group used to build queries
+    type Schema: Copy;
+
+    /// The schema of the group with the prefix added to each column
+    fn schema(prefix: &'static Prefix) -> Self::Schema;
+
+    /// The columns of the group with the prefix added to each name
+    fn columns(prefix: &'static Prefix) -> Vec<Column>;
+
+    /// Reads the group out of a row
+    fn from_row(row: &Row, prefix: &'static Prefix) -> Result<Self>;
+
+    /// Updates the group from the columns that are in a row
+    fn update_from_row(&mut self, row: &Row, prefix: &'static Prefix) -> Result<()>;
+
+    /// Binds the value of a column. Returns false if the column isn't part of this group
+    fn bind<'s, 'p>(&'s self, prefix: &str, column: &str, args: &mut ParamArgs<'p>) -> Result<bool>
+    where
+        's: 'p;
+
+    /// true if the column holds its default value. None if the column isn't part of this group
+    fn col_is_default(&self, prefix: &str, column: &str) -> Option<bool>;
+
+    /// The current value of each column, used to track changes
+    fn snapshot_values(
+        &self,
+        prefix: &'static Prefix,
+    ) -> Vec<(&'static str, Option<Box<dyn ColumnValue>>)>;
+}
+
+/// The prefix of a field embedded with `#[welds(flatten)]`
+///
+/// The macros keep one in a static, so the prefixed names of the columns
+/// are only built the first time they are used
+pub struct Prefix {
+    prefix: &'static str,
+    names: OnceLock<Box<[String]>>,
+}
+
+impl Prefix {
+    pub const fn new(prefix: &'static str) -> Self {
+        Self {
+            prefix,
+            names: OnceLock::new(),
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        self.prefix
+    }
+
+    /// The names of the columns of the group with the prefix in front of each
+    pub fn names(&'static self, columns: &[&str]) -> &'static [String] {
+        self.names.get_or_init(|| {
+            columns
+                .iter()
+                .map(|name| format!("{}{}", self.prefix, name))
+                .collect()
+        })
+    }
+}
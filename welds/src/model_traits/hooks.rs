@@ -1,4 +1,8 @@
+use crate::Client;
 use crate::errors::Result;
+use crate::model_traits::ChangeSet;
+use crate::query::builder::{QueryBuilder, UpdateBuilder};
+use crate::state::DbState;
 
 /// A collection of trait that allow for intercepting/monitoring call to the database
 ///
@@ -9,6 +13,8 @@ use crate::errors::Result;
 /// Welds you want a Hook in the welds macros
 ///
 /// WARNING: These are NOT effected by bulk operations !!!
+/// Bulk inserts, updates and deletes have their own hooks.
+/// `BeforeBulkInsert`, `BeforeBulkUpdate`, `BeforeBulkDelete`, ...
 pub trait BeforeCreate {
     /// a last minute opportunity to check/edit a model before it is saved to the database
     /// Err results will cancel the action.
@@ -32,6 +38,24 @@ pub trait BeforeUpdate {
     fn before(&mut self) -> impl std::future::Future<Output = Result<()>> + Send;
     #[maybe_async::sync_impl]
     fn before(&mut self) -> Result<()>;
+
+    /// Called ahead of `before` with the columns that have changed since the model was loaded.
+    /// Err results will cancel the action.
+    ///
+    /// `#[welds(BeforeUpdate(log_changes, changes = true))]`
+    #[cfg(not(feature = "__sync"))]
+    fn before_with_changes(
+        &mut self,
+        changes: &ChangeSet<'_>,
+    ) -> impl std::future::Future<Output = Result<()>> + Send {
+        let _ = changes;
+        async { Ok(()) }
+    }
+    #[maybe_async::sync_impl]
+    fn before_with_changes(&mut self, changes: &ChangeSet<'_>) -> Result<()> {
+        let _ = changes;
+        Ok(())
+    }
 }
 
 pub trait BeforeDelete {
@@ -54,6 +78,14 @@ pub trait AfterCreate {
     fn after(&self) -> impl std::future::Future<Output = Result<()>> + Send;
     #[maybe_async::sync_impl]
     fn after(&self) -> Result<()>;
+
+    /// Queues the `commit = true` callbacks to run once the client's transaction commits.
+    /// Outside a transaction they run right away
+    ///
+    /// `#[welds(AfterCreate(send_email, commit = true))]`
+    fn after_commit(&self, client: &dyn Client) {
+        let _ = client;
+    }
 }
 
 pub trait AfterUpdate {
@@ -64,6 +96,14 @@ pub trait AfterUpdate {
     fn after(&self) -> impl std::future::Future<Output = Result<()>> + Send;
     #[maybe_async::sync_impl]
     fn after(&self) -> Result<()>;
+
+    /// Queues the `commit = true` callbacks to run once the client's transaction commits.
+    /// Outside a transaction they run right away
+    ///
+    /// `#[welds(AfterUpdate(send_email, commit = true))]`
+    fn after_commit(&self, client: &dyn Client) {
+        let _ = client;
+    }
 }
 
 pub trait AfterDelete {
@@ -74,4 +114,99 @@ pub trait AfterDelete {
     fn after(&self) -> impl std::future::Future<Output = Result<()>> + Send;
     #[maybe_async::sync_impl]
     fn after(&self) -> Result<()>;
+
+    /// Queues the `commit = true` callbacks to run once the client's transaction commits.
+    /// Outside a transaction they run right away
+    ///
+    /// `#[welds(AfterDelete(send_email, commit = true))]`
+    fn after_commit(&self, client: &dyn Client) {
+        let _ = client;
+    }
+}
+
+/// The rows touched by a bulk update or delete
+pub enum BulkAffected<'a, T> {
+    /// The number of rows, from `run` or `delete`
+    Count(u64),
+    /// The rows themselves, from `run_returning` or `delete_returning`
+    Rows(&'a [DbState<T>]),
+}
+
+impl<T> BulkAffected<'_, T> {
+    /// The number of rows that were touched
+    pub fn count(&self) -> u64 {
+        match self {
+            BulkAffected::Count(count) => *count,
+            BulkAffected::Rows(rows) => rows.len() as u64,
+        }
+    }
+
+    /// The rows that were touched, if the query returned them
+    pub fn rows(&self) -> Option<&[DbState<T>]> {
+        match self {
+            BulkAffected::Count(_) => None,
+            BulkAffected::Rows(rows) => Some(rows),
+        }
+    }
+}
+
+pub trait BeforeBulkInsert {
+    /// Called for each row of a `bulk_insert` before anything is written.
+    /// Err results will cancel the whole insert.
+    #[cfg(not(feature = "__sync"))]
+    fn before(&self) -> impl std::future::Future<Output = Result<()>> + Send;
+    #[maybe_async::sync_impl]
+    fn before(&self) -> Result<()>;
+}
+
+pub trait AfterBulkInsert {
+    /// Called for each row of a `bulk_insert` once all the rows are written
+    #[cfg(not(feature = "__sync"))]
+    fn after(&self) -> impl std::future::Future<Output = Result<()>> + Send;
+    #[maybe_async::sync_impl]
+    fn after(&self) -> Result<()>;
+}
+
+pub trait BeforeBulkUpdate: Sized {
+    /// Called with the query before a bulk update is executed.
+    /// Err results will cancel the update.
+    #[cfg(not(feature = "__sync"))]
+    fn before(query: &UpdateBuilder<Self>) -> impl std::future::Future<Output = Result<()>> + Send;
+    #[maybe_async::sync_impl]
+    fn before(query: &UpdateBuilder<Self>) -> Result<()>;
+}
+
+pub trait AfterBulkUpdate: Sized {
+    /// Called with the query after a bulk update is executed.
+    ///
+    /// `run` gives the number of rows updated, `run_returning` gives the updated rows
+    #[cfg(not(feature = "__sync"))]
+    fn after(
+        query: &UpdateBuilder<Self>,
+        affected: &BulkAffected<'_, Self>,
+    ) -> impl std::future::Future<Output = Result<()>> + Send;
+    #[maybe_async::sync_impl]
+    fn after(query: &UpdateBuilder<Self>, affected: &BulkAffected<'_, Self>) -> Result<()>;
+}
+
+pub trait BeforeBulkDelete: Sized {
+    /// Called with the query before a bulk delete is executed.
+    /// Err results will cancel the delete.
+    #[cfg(not(feature = "__sync"))]
+    fn before(query: &QueryBuilder<Self>) -> impl std::future::Future<Output = Result<()>> + Send;
+    #[maybe_async::sync_impl]
+    fn before(query: &QueryBuilder<Self>) -> Result<()>;
+}
+
+pub trait AfterBulkDelete: Sized {
+    /// Called with the query after a bulk delete is executed.
+    ///
+    /// `delete` gives the number of rows deleted, `delete_returning` gives the deleted rows
+    #[cfg(not(feature = "__sync"))]
+    fn after(
+        query: &QueryBuilder<Self>,
+        affected: &BulkAffected<'_, Self>,
+    ) -> impl std::future::Future<Output = Result<()>> + Send;
+    #[maybe_async::sync_impl]
+    fn after(query: &QueryBuilder<Self>, affected: &BulkAffected<'_, Self>) -> Result<()>;
 }
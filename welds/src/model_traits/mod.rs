@@ -1,7 +1,13 @@
 /// ***********************************************************************************
 /// These are all the trait and struct used to connect a rust Struct to a database driver
 /// ***********************************************************************************
+pub mod changes;
+pub mod embedded;
 pub mod hooks;
+pub mod soft_delete;
+pub mod timestamp;
+pub mod validate;
+pub mod version;
 
 #[cfg(test)]
 mod tests;
@@ -11,6 +17,33 @@ mod tests;
 pub trait TableInfo {
     /// the unique name (schema + tablename) that identities this database object
     fn identifier() -> &'static [&'static str];
+
+    /// The column rows are flagged as deleted in. `#[welds(soft_delete)]`
+    fn soft_delete_column() -> Option<SoftDeleteColumn> {
+        None
+    }
+
+    /// The column set to now when a row is inserted. `#[welds(created_at)]`
+    fn created_at_column() -> Option<TimestampColumn> {
+        None
+    }
+
+    /// The column set to now when a row is inserted or updated. `#[welds(updated_at)]`
+    fn updated_at_column() -> Option<TimestampColumn> {
+        None
+    }
+
+    /// Columns left out of an INSERT while the model holds their default value,
+    /// so the database can fill them in. `#[welds(db_default)]`
+    fn db_default_columns() -> &'static [&'static str] {
+        &[]
+    }
+
+    /// Columns written by the database. `#[welds(db_default)]` and `#[welds(generated)]`
+    /// They are read back into the model after it is saved
+    fn db_managed_columns() -> &'static [&'static str] {
+        &[]
+    }
 }
 
 /// The db column name to use for a field
@@ -74,6 +107,12 @@ pub trait WriteToArgs {
     fn bind<'s, 'c, 'a, 'p>(&'s self, column: &'c str, args: &'a mut ParamArgs<'p>) -> Result<()>
     where
         's: 'p;
+
+    /// The value of a column as a key, `None` if the column can't be hashed.
+    /// Used to skip binding the same value twice
+    fn column_key(&self, _column: &str) -> Option<ColumnKey> {
+        None
+    }
 }
 
 pub trait ColumnDefaultCheck {
@@ -88,6 +127,13 @@ pub trait UpdateFromRow {
 /// Used to link a models schema to the model
 pub trait HasSchema: Sync + Send {
     type Schema: Default + TableInfo;
+
+    /// Snapshots a model loaded from the database when it is first edited,
+    /// so saves only update the columns that changed. Set by the macros.
+    /// Without it saves update every column
+    fn tracker() -> Option<fn(&Self) -> Snapshot> {
+        None
+    }
 }
 
 /// Returns the Value of the PK of a model
@@ -104,5 +150,12 @@ pub trait ForeignKeyPartialEq<Rhs> {
     fn eq(&self, foreign_key_column: &str, other: &Rhs) -> bool;
 }
 
+pub use changes::{ChangeSet, ColumnKey, Snapshot, TrackChanges};
+pub use embedded::Embedded;
+pub use soft_delete::{SoftDelete, SoftDeleteColumn, SoftDeleteValue};
+pub use timestamp::{AutoTimestamps, Timestamp, TimestampColumn};
+pub use validate::{Validate, ValidationErrors};
+pub use version::{HasVersion, NextVersion};
+
 mod tableident;
 pub use tableident::TableIdent;
@@ -0,0 +1,142 @@
+use super::Timestamp;
+use crate::query::clause::{AssignmentManual, ClauseColManual};
+use std::any::Any;
+use welds_connections::Param;
+
+/// Soft deletes. Flags a row as deleted instead of removing it
+///
+/// This is AUTOMATICALLY implemented by the Welds Macros.
+/// Mark a `bool` or nullable timestamp field with `#[welds(soft_delete)]` to turn it on
+/// ```rust,ignore
+/// #[derive(WeldsModel)]
+/// #[welds(table = "customers")]
+/// struct Customer {
+///     #[welds(primary_key)]
+///     id: i32,
+///     #[welds(soft_delete)]
+///     deleted_at: Option<chrono::DateTime<chrono::Utc>>,
+/// }
+/// ```
+/// Deleting a soft deleted model updates the column instead,
+/// and queries skip the deleted rows unless asked for them with `with_deleted()`
+pub trait SoftDelete {
+    /// Sets the field to the value of a deleted row, handing back the value it had before
+    fn mark_deleted(&mut self) -> Option<Box<dyn Any + Send>>;
+    /// Sets the field to the value of a row that isn't deleted, handing back the value it had before
+    fn mark_restored(&mut self) -> Option<Box<dyn Any + Send>>;
+    /// Puts back a value that was handed out by `mark_deleted` or `mark_restored`
+    fn unmark(&mut self, old: Box<dyn Any + Send>);
+}
+
+/// The types that can be used as a soft delete column
+///
+/// `bool` is `true` when deleted.
+/// A nullable timestamp holds the time it was deleted, `NULL` when it isn't
+pub trait SoftDeleteValue: Sized {
+    /// true if a row that isn't deleted holds a `NULL`
+    const NULLABLE: bool;
+    /// The value of a deleted row
+    fn deleted() -> Self;
+    /// The value of a row that isn't deleted
+    fn restored() -> Self;
+    /// The value written to the database when rows are deleted in bulk
+    fn deleted_param() -> Box<dyn Param + Send + Sync>;
+}
+
+impl SoftDeleteValue for bool {
+    const NULLABLE: bool = false;
+    fn deleted() -> Self {
+        true
+    }
+    fn restored() -> Self {
+        false
+    }
+    fn deleted_param() -> Box<dyn Param + Send + Sync> {
+        Box::new(true)
+    }
+}
+
+impl<V> SoftDeleteValue for Option<V>
+where
+    V: 'static + Timestamp + Param + Send + Sync,
+{
+    const NULLABLE: bool = true;
+    fn deleted() -> Self {
+        Some(V::now())
+    }
+    fn restored() -> Self {
+        None
+    }
+    fn deleted_param() -> Box<dyn Param + Send + Sync> {
+        Box::new(V::now())
+    }
+}
+
+/// The `#[welds(soft_delete)]` column of a table
+#[derive(Clone, Copy)]
+pub struct SoftDeleteColumn {
+    name: &'static str,
+    nullable: bool,
+    deleted_param: fn() -> Box<dyn Param + Send + Sync>,
+}
+
+impl SoftDeleteColumn {
+    pub fn new<V: SoftDeleteValue>(name: &'static str) -> Self {
+        Self {
+            name,
+            nullable: V::NULLABLE,
+            deleted_param: V::deleted_param,
+        }
+    }
+
+    /// The name of the column in the database
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// WHERE clause matching the rows that aren't deleted
+    pub(crate) fn not_deleted_clause(&self) -> ClauseColManual {
+        match self.nullable {
+            true => self.clause("IS NULL", Vec::default()),
+            false => self.clause("= ?", vec![Box::new(false)]),
+        }
+    }
+
+    /// WHERE clause matching the rows that are deleted
+    pub(crate) fn deleted_clause(&self) -> ClauseColManual {
+        match self.nullable {
+            true => self.clause("IS NOT NULL", Vec::default()),
+            false => self.clause("= ?", vec![Box::new(true)]),
+        }
+    }
+
+    /// SET flagging rows as deleted
+    pub(crate) fn delete_assignment(&self) -> AssignmentManual {
+        AssignmentManual {
+            col: self.name,
+            sql: "?".to_string(),
+            params: vec![(self.deleted_param)()],
+        }
+    }
+
+    /// SET flagging rows as no longer deleted
+    pub(crate) fn restore_assignment(&self) -> AssignmentManual {
+        let (sql, params): (_, Vec<Box<dyn Param + Send + Sync>>) = match self.nullable {
+            true => ("NULL", Vec::default()),
+            false => ("?", vec![Box::new(false)]),
+        };
+        AssignmentManual {
+            col: self.name,
+            sql: sql.to_string(),
+            params,
+        }
+    }
+
+    fn clause(&self, sql: &str, params: Vec<Box<dyn Param + Send + Sync>>) -> ClauseColManual {
+        ClauseColManual {
+            col: Some(self.name),
+            sql: sql.to_string(),
+            params,
+        }
+    }
+}
@@ -1,5 +1,4 @@
 use super::HasSchema;
-use super::TableColumns;
 use super::TableInfo;
 use crate::Syntax;
 
@@ -25,7 +24,6 @@ impl TableIdent {
     pub fn from_model<T>() -> TableIdent
     where
         T: HasSchema,
-        <T as HasSchema>::Schema: TableInfo + TableColumns,
     {
         let fullname = <T as HasSchema>::Schema::identifier().join(".");
         Self::parse(&fullname)
@@ -52,6 +50,14 @@ impl TableIdent {
         self.schema.as_deref()
     }
 
+    /// Returns the TableIdent placed in `schema` if it doesn't have a schema of its own
+    pub(crate) fn or_schema(mut self, schema: Option<&str>) -> Self {
+        if self.schema.is_none() {
+            self.schema = schema.map(|s| s.to_owned());
+        }
+        self
+    }
+
     /// Parse a string into a TableIdent
     pub fn parse(raw: &str) -> Self {
         let parts: Vec<&str> = raw.split('.').collect();
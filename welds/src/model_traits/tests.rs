@@ -143,3 +143,343 @@ fn should_be_able_to_equal_fks_optional() {
         assert!(super::ForeignKeyPartialEq::eq(&order2, "product_id2", &234));
     });
 }
+
+// Gives the models in the tests a "timestamp" without turning on chrono or time
+impl super::Timestamp for String {
+    fn now() -> Self {
+        "now".to_owned()
+    }
+}
+
+#[derive(Debug, Default, WeldsModel)]
+#[welds(table = "accounts")]
+#[welds_path(crate)] // needed only within the welds crate.
+struct AccountV {
+    #[welds(primary_key)]
+    pub id: i32,
+    #[welds(validate(not_empty, length(max = 5)))]
+    pub name: String,
+    #[welds(validate(regex = "^[^@]+@[^@]+$"))]
+    pub email: String,
+    #[welds(validate(range(min = 0, max = 150)))]
+    pub age: Option<i32>,
+    #[welds(validate(custom = "single_word"))]
+    pub nickname: String,
+}
+
+fn single_word(value: &str) -> Result<(), String> {
+    match value.contains(' ') {
+        true => Err("must be a single word".to_owned()),
+        false => Ok(()),
+    }
+}
+
+#[test]
+fn should_list_the_broken_rules_by_field() {
+    use crate::model_traits::Validate;
+    let account = AccountV {
+        id: 1,
+        name: String::default(),
+        email: "nope".to_owned(),
+        age: Some(200),
+        nickname: "two words".to_owned(),
+    };
+    let errors = account.validate().unwrap_err();
+    assert_eq!(errors.field("name"), &["must not be empty".to_owned()]);
+    assert_eq!(
+        errors.field("email"),
+        &["must match ^[^@]+@[^@]+$".to_owned()]
+    );
+    assert_eq!(
+        errors.field("age"),
+        &["must be between 0 and 150".to_owned()]
+    );
+    assert_eq!(
+        errors.field("nickname"),
+        &["must be a single word".to_owned()]
+    );
+
+    let account = AccountV {
+        id: 1,
+        name: "bob".to_owned(),
+        email: "bob@example.com".to_owned(),
+        age: None,
+        nickname: "bobby".to_owned(),
+    };
+    assert!(account.validate().is_ok());
+}
+
+#[test]
+fn should_not_save_models_that_break_a_rule() {
+    use crate::state::DbState;
+    use welds_connections::Syntax;
+    futures::executor::block_on(async move {
+        let client = welds_connections::noop::build(Syntax::Postgres);
+        let mut account = DbState::new_uncreated(AccountV::default());
+        let err = account.save(&client).await.unwrap_err();
+        assert!(matches!(err, crate::WeldsError::Validation(_)), "{err}");
+        assert!(client.last_sql().is_none());
+
+        let account = AccountV {
+            id: 1,
+            name: "bob".to_owned(),
+            email: "bob@example.com".to_owned(),
+            ..Default::default()
+        };
+        let mut account = DbState::db_loaded_tracked(account);
+        account.name = "robert".to_owned();
+        let err = account.save(&client).await.unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "The model is not valid: name: length must be at most 5"
+        );
+        assert!(client.last_sql().is_none());
+
+        account.name = "rob".to_owned();
+        account.save(&client).await.unwrap();
+        let sql = client.last_sql().unwrap();
+        assert_eq!(sql, r#"UPDATE accounts SET "name"=$1 where "id"=$2"#);
+    });
+}
+
+#[derive(Debug, Default, WeldsModel)]
+#[welds(table = "ledgers")]
+#[welds_path(crate)] // needed only within the welds crate.
+#[welds(BeforeBulkUpdate(refuse_unscoped_update, async = true))]
+#[welds(BeforeBulkDelete(refuse_unscoped_delete))]
+#[welds(AfterBulkDelete(record_delete))]
+struct Ledger {
+    #[welds(primary_key)]
+    pub id: i32,
+    pub name: String,
+}
+
+static LEDGER_DELETES: std::sync::Mutex<Vec<u64>> = std::sync::Mutex::new(Vec::new());
+
+async fn refuse_unscoped_update(
+    query: &crate::query::builder::UpdateBuilder<Ledger>,
+) -> crate::errors::Result<()> {
+    let sql = query.to_sql(welds_connections::Syntax::Postgres);
+    match sql.to_lowercase().contains("where") {
+        true => Ok(()),
+        false => Err(crate::WeldsError::ActionCanceled),
+    }
+}
+
+fn refuse_unscoped_delete(
+    query: &crate::query::builder::QueryBuilder<Ledger>,
+) -> crate::errors::Result<()> {
+    let sql = query.delete_sql(welds_connections::Syntax::Postgres);
+    match sql.to_lowercase().contains("where") {
+        true => Ok(()),
+        false => Err(crate::WeldsError::ActionCanceled),
+    }
+}
+
+fn record_delete(
+    _query: &crate::query::builder::QueryBuilder<Ledger>,
+    affected: &super::hooks::BulkAffected<Ledger>,
+) {
+    LEDGER_DELETES.lock().unwrap().push(affected.count());
+}
+
+#[test]
+fn should_call_the_bulk_hooks_with_the_query() {
+    use welds_connections::Syntax;
+    futures::executor::block_on(async move {
+        let client = welds_connections::noop::build(Syntax::Postgres);
+
+        let err = Ledger::all().set(|x| x.name, "x").run(&client).await;
+        assert!(matches!(err, Err(crate::WeldsError::ActionCanceled)));
+        assert!(client.last_sql().is_none());
+
+        let err = Ledger::all().delete(&client).await;
+        assert!(matches!(err, Err(crate::WeldsError::ActionCanceled)));
+        assert!(client.last_sql().is_none());
+        assert!(LEDGER_DELETES.lock().unwrap().is_empty());
+
+        let q = Ledger::where_col(|x| x.id.equal(1));
+        q.clone().set(|x| x.name, "x").run(&client).await.unwrap();
+        let sql = client.last_sql().unwrap();
+        assert_eq!(
+            sql,
+            r#"UPDATE ledgers SET "name"=$1 WHERE ( ledgers."id" = $2 )"#
+        );
+
+        q.delete(&client).await.unwrap();
+        assert_eq!(*LEDGER_DELETES.lock().unwrap(), vec![0]);
+    });
+}
+
+#[derive(Debug, Default, WeldsModel)]
+#[welds(table = "observed")]
+#[welds_path(crate)] // needed only within the welds crate.
+struct Observed {
+    #[welds(primary_key)]
+    pub id: i32,
+    pub name: String,
+    pub note: String,
+}
+
+#[test]
+fn should_tell_observers_about_saves_and_deletes() {
+    use crate::observers::{self, EventKind, ModelEvent};
+    use crate::state::DbState;
+    use std::sync::{Arc, Mutex};
+    use welds_connections::Syntax;
+
+    type Seen = (EventKind, i32, Vec<&'static str>);
+    let seen: Arc<Mutex<Vec<Seen>>> = Default::default();
+    let seen_by_observer = seen.clone();
+    let id = observers::register(move |event: &ModelEvent| {
+        // other tests save models too
+        if event.table().name() != "observed" {
+            return;
+        }
+        let pk = *event.id::<i32>().unwrap();
+        let changes = event.changed_columns().to_vec();
+        seen_by_observer
+            .lock()
+            .unwrap()
+            .push((event.kind(), pk, changes));
+    });
+
+    futures::executor::block_on(async move {
+        let client = welds_connections::noop::build(Syntax::Postgres);
+        let mut obj = DbState::db_loaded_tracked(Observed {
+            id: 7,
+            ..Default::default()
+        });
+        obj.note = "hello".to_owned();
+        obj.save(&client).await.unwrap();
+        // nothing changed, nothing to tell
+        obj.save(&client).await.unwrap();
+        obj.delete(&client).await.unwrap();
+    });
+    assert!(observers::unregister(id));
+
+    let seen = seen.lock().unwrap();
+    assert_eq!(
+        *seen,
+        vec![
+            (EventKind::Updated, 7, vec!["note"]),
+            (EventKind::Deleted, 7, vec![]),
+        ]
+    );
+}
+
+#[derive(Debug, Default, WeldsModel, serde::Serialize)]
+#[welds(table = "invoices")]
+#[welds(audited)]
+#[welds_path(crate)] // needed only within the welds crate.
+struct Invoice {
+    #[welds(primary_key)]
+    pub id: i32,
+    pub total: i32,
+}
+
+#[test]
+fn should_write_the_columns_of_audited_models_as_json() {
+    use crate::model_traits::TrackChanges;
+    let invoice = Invoice { id: 3, total: 10 };
+    let values = invoice.audit_values().unwrap().unwrap();
+    assert_eq!(values, r#"{"id":3,"total":10}"#);
+    assert!(Observed::default().audit_values().unwrap().is_none());
+}
+
+#[test]
+fn should_write_the_history_of_audited_models() {
+    use crate::state::DbState;
+    use welds_connections::Syntax;
+    use welds_connections::actor::ActorClient;
+    futures::executor::block_on(async move {
+        let noop = welds_connections::noop::build(Syntax::Postgres);
+        let client = ActorClient::new(noop.clone(), "user:1");
+        let mut invoice = DbState::db_loaded_tracked(Invoice { id: 3, total: 10 });
+        invoice.total = 20;
+        invoice.save(&client).await.unwrap();
+        let sql = noop.last_sql().unwrap();
+        assert_eq!(
+            sql,
+            "INSERT INTO invoices_history (operation, changed_at, actor, old_values, new_values) VALUES ($1, CURRENT_TIMESTAMP, $2, $3, $4)"
+        );
+        assert_eq!(noop.args_count(), Some(4));
+    });
+}
+
+#[derive(Debug, Default, Clone, PartialEq, serde::Deserialize)]
+struct Unwritable;
+
+impl serde::Serialize for Unwritable {
+    fn serialize<S: serde::Serializer>(&self, _: S) -> std::result::Result<S::Ok, S::Error> {
+        Err(serde::ser::Error::custom("can't be written"))
+    }
+}
+
+#[derive(Debug, Default, WeldsModel, serde::Serialize)]
+#[welds(table = "journals")]
+#[welds(audited)]
+#[welds_path(crate)] // needed only within the welds crate.
+struct Journal {
+    #[welds(primary_key)]
+    pub id: i32,
+    pub notes: crate::Json<Unwritable>,
+}
+
+#[test]
+fn should_fail_to_delete_an_audited_model_whose_history_can_not_be_written() {
+    use crate::errors::WeldsError;
+    use crate::state::DbState;
+    use welds_connections::Syntax;
+    futures::executor::block_on(async move {
+        let client = welds_connections::noop::build(Syntax::Postgres);
+        let mut journal = DbState::db_loaded_tracked(Journal::default());
+        let err = journal.force_delete(&client).await.unwrap_err();
+        match err {
+            WeldsError::Audit(err) => assert_eq!(err.column, "notes"),
+            err => panic!("expected an audit error, got {err:?}"),
+        }
+        let sql = client.last_sql().unwrap();
+        assert!(!sql.contains("journals_history"));
+    });
+}
+
+#[derive(Debug, Default, Clone, WeldsModel)]
+#[welds(table = "receipts")]
+#[welds_path(crate)] // needed only within the welds crate.
+#[welds(AfterUpdate(send_receipt, commit = true))]
+struct Receipt {
+    #[welds(primary_key)]
+    pub id: i32,
+    pub email: String,
+}
+
+static RECEIPTS_SENT: std::sync::Mutex<Vec<i32>> = std::sync::Mutex::new(Vec::new());
+
+fn send_receipt(receipt: &Receipt) {
+    RECEIPTS_SENT.lock().unwrap().push(receipt.id);
+}
+
+#[test]
+fn should_run_commit_hooks_right_away_outside_of_a_transaction() {
+    use crate::state::DbState;
+    use welds_connections::Syntax;
+    futures::executor::block_on(async move {
+        let client = welds_connections::noop::build(Syntax::Postgres);
+        let mut receipt = DbState::db_loaded_tracked(Receipt {
+            id: 4,
+            email: "a@b.c".to_owned(),
+        });
+        receipt.email = "d@e.f".to_owned();
+        receipt.save(&client).await.unwrap();
+        assert_eq!(*RECEIPTS_SENT.lock().unwrap(), vec![4]);
+    });
+}
+
+#[cfg(feature = "chrono")]
+#[test]
+fn should_move_a_timestamp_version_to_now() {
+    use crate::model_traits::NextVersion;
+    let old = chrono::NaiveDateTime::default();
+    assert!(old.next_version() > old);
+}
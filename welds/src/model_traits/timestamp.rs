@@ -0,0 +1,109 @@
+use super::{ColumnDefaultCheck, HasSchema, TableInfo};
+use crate::errors::Result;
+use welds_connections::Param;
+
+/// A type that can hold the current time.
+///
+/// Used by the columns welds fills in with the current time,
+/// `#[welds(created_at)]`, `#[welds(updated_at)]`, a `#[welds(version)]` timestamp
+/// or a `#[welds(soft_delete)]` deleted_at.
+///
+/// Implemented for the `chrono` and `time` types when those features are on.
+/// Implement it yourself for other types
+pub trait Timestamp: Sized {
+    fn now() -> Self;
+}
+
+// Timestamps are truncated to microseconds, the most precision most databases will store.
+// Otherwise the value in the model would never match the value in the database.
+#[cfg(feature = "chrono")]
+impl Timestamp for chrono::DateTime<chrono::Utc> {
+    fn now() -> Self {
+        use chrono::DurationRound;
+        let now = chrono::Utc::now();
+        now.duration_trunc(chrono::TimeDelta::microseconds(1))
+            .unwrap_or(now)
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl Timestamp for chrono::NaiveDateTime {
+    fn now() -> Self {
+        chrono::DateTime::<chrono::Utc>::now().naive_utc()
+    }
+}
+
+#[cfg(feature = "time")]
+impl Timestamp for time::OffsetDateTime {
+    fn now() -> Self {
+        let now = time::OffsetDateTime::now_utc();
+        now.replace_microsecond(now.microsecond()).unwrap_or(now)
+    }
+}
+
+#[cfg(feature = "time")]
+impl Timestamp for time::PrimitiveDateTime {
+    fn now() -> Self {
+        let now = <time::OffsetDateTime as Timestamp>::now();
+        time::PrimitiveDateTime::new(now.date(), now.time())
+    }
+}
+
+/// Sets the `#[welds(created_at)]` and `#[welds(updated_at)]` fields of a model
+///
+/// This is AUTOMATICALLY implemented by the Welds Macros
+pub trait AutoTimestamps {
+    /// Sets the `#[welds(created_at)]` field to now
+    fn touch_created_at(&mut self);
+    /// Sets the `#[welds(updated_at)]` field to now
+    fn touch_updated_at(&mut self);
+}
+
+/// A `#[welds(created_at)]` or `#[welds(updated_at)]` column of a table
+#[derive(Clone, Copy)]
+pub struct TimestampColumn {
+    name: &'static str,
+    now_param: fn() -> Box<dyn Param + Send + Sync>,
+}
+
+impl TimestampColumn {
+    pub fn new<V>(name: &'static str) -> Self
+    where
+        V: 'static + Timestamp + Param + Send + Sync,
+    {
+        Self {
+            name,
+            now_param: || Box::new(V::now()),
+        }
+    }
+
+    /// The name of the column in the database
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// The current time, ready to be written to the database.
+    /// Used when there isn't a model to set
+    pub(crate) fn now_param(&self) -> Box<dyn Param + Send + Sync> {
+        (self.now_param)()
+    }
+}
+
+/// Fills in the timestamps of a model about to be inserted.
+/// Timestamps that have already been given a value are left alone
+pub(crate) fn touch_for_insert<T>(obj: &mut T) -> Result<()>
+where
+    T: HasSchema + ColumnDefaultCheck + AutoTimestamps,
+{
+    if let Some(col) = <T as HasSchema>::Schema::created_at_column()
+        && obj.col_is_default(col.name())?
+    {
+        obj.touch_created_at();
+    }
+    if let Some(col) = <T as HasSchema>::Schema::updated_at_column()
+        && obj.col_is_default(col.name())?
+    {
+        obj.touch_updated_at();
+    }
+    Ok(())
+}
@@ -0,0 +1,194 @@
+use std::collections::BTreeMap;
+
+/// Checks the values of a model before it is saved
+///
+/// This is AUTOMATICALLY implemented by the Welds Macros.
+/// Add rules to the fields of a model with `#[welds(validate(...))]`
+/// ```rust,ignore
+/// #[derive(WeldsModel)]
+/// #[welds(table = "users")]
+/// struct User {
+///     #[welds(primary_key)]
+///     id: i32,
+///     #[welds(validate(not_empty, length(max = 50)))]
+///     name: String,
+///     #[welds(validate(regex = "^[^@]+@[^@]+$"))]
+///     email: String,
+///     #[welds(validate(range(min = 0, max = 150)))]
+///     age: Option<i32>,
+///     #[welds(validate(custom = "check_nickname"))]
+///     nickname: String,
+/// }
+///
+/// // custom rules are given the value of the field
+/// fn check_nickname(value: &str) -> Result<(), String> {
+///     match value.contains(' ') {
+///         true => Err("must be a single word".to_owned()),
+///         false => Ok(()),
+///     }
+/// }
+/// ```
+/// Rules on `Option` fields check the value when there is one.
+/// The rules are checked after the `BeforeCreate`/`BeforeUpdate` hooks.
+/// Saves of models that break a rule fail with `WeldsError::Validation`
+///
+/// `regex` requires the `regex` feature
+pub trait Validate {
+    /// Checks every rule, returning the messages of the rules that failed by field
+    fn validate(&self) -> Result<(), ValidationErrors>;
+}
+
+/// The rules a model broke, a list of messages for each field
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ValidationErrors {
+    fields: BTreeMap<&'static str, Vec<String>>,
+}
+
+impl ValidationErrors {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a message to a field
+    pub fn add(&mut self, field: &'static str, message: impl Into<String>) {
+        self.fields.entry(field).or_default().push(message.into());
+    }
+
+    /// true if no rules were broken
+    pub fn is_empty(&self) -> bool {
+        self.fields.is_empty()
+    }
+
+    /// The messages for a field
+    pub fn field(&self, field: &str) -> &[String] {
+        self.fields.get(field).map(|m| m.as_slice()).unwrap_or(&[])
+    }
+
+    /// All the messages by field
+    pub fn fields(&self) -> &BTreeMap<&'static str, Vec<String>> {
+        &self.fields
+    }
+
+    /// Ok if no rules were broken
+    pub fn into_result(self) -> Result<(), ValidationErrors> {
+        match self.is_empty() {
+            true => Ok(()),
+            false => Err(self),
+        }
+    }
+}
+
+impl std::fmt::Display for ValidationErrors {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let parts: Vec<String> = self
+            .fields
+            .iter()
+            .map(|(field, messages)| format!("{}: {}", field, messages.join(", ")))
+            .collect();
+        f.write_str(&parts.join("; "))
+    }
+}
+
+impl std::error::Error for ValidationErrors {}
+
+/// The rules used by `#[welds(validate(...))]`
+///
+/// Each rule returns the message to show when the value breaks it
+pub mod rules {
+    use std::fmt::Display;
+
+    /// The types that can be checked with `length` and `not_empty`
+    pub trait HasLength {
+        fn length(&self) -> usize;
+    }
+
+    impl HasLength for String {
+        fn length(&self) -> usize {
+            self.chars().count()
+        }
+    }
+
+    impl HasLength for str {
+        fn length(&self) -> usize {
+            self.chars().count()
+        }
+    }
+
+    impl<T> HasLength for Vec<T> {
+        fn length(&self) -> usize {
+            self.len()
+        }
+    }
+
+    impl<T> HasLength for [T] {
+        fn length(&self) -> usize {
+            self.len()
+        }
+    }
+
+    /// `length(min = 1, max = 50)`
+    pub fn length<V>(value: &V, min: Option<usize>, max: Option<usize>) -> Result<(), String>
+    where
+        V: HasLength + ?Sized,
+    {
+        let len = value.length();
+        match (min, max) {
+            (Some(min), Some(max)) if len < min || len > max => {
+                Err(format!("length must be between {} and {}", min, max))
+            }
+            (Some(min), None) if len < min => Err(format!("length must be at least {}", min)),
+            (None, Some(max)) if len > max => Err(format!("length must be at most {}", max)),
+            _ => Ok(()),
+        }
+    }
+
+    /// `not_empty`
+    pub fn not_empty<V>(value: &V) -> Result<(), String>
+    where
+        V: HasLength + ?Sized,
+    {
+        match value.length() {
+            0 => Err("must not be empty".to_owned()),
+            _ => Ok(()),
+        }
+    }
+
+    /// `range(min = 0, max = 100)`
+    pub fn range<V>(value: &V, min: Option<V>, max: Option<V>) -> Result<(), String>
+    where
+        V: PartialOrd + Display,
+    {
+        match (min, max) {
+            (Some(min), Some(max)) if *value < min || *value > max => {
+                Err(format!("must be between {} and {}", min, max))
+            }
+            (Some(min), _) if *value < min => Err(format!("must be at least {}", min)),
+            (_, Some(max)) if *value > max => Err(format!("must be at most {}", max)),
+            _ => Ok(()),
+        }
+    }
+
+    #[cfg(feature = "regex")]
+    pub use regex::Regex;
+
+    /// `regex = "pattern"`
+    ///
+    /// The pattern is checked by welds-macros, and compiled once into the field's `OnceLock`
+    #[cfg(feature = "regex")]
+    pub fn regex<V>(
+        value: &V,
+        compiled: &std::sync::OnceLock<Regex>,
+        pattern: &'static str,
+    ) -> Result<(), String>
+    where
+        V: AsRef<str> + ?Sized,
+    {
+        let re = compiled.get_or_init(|| {
+            Regex::new(pattern).expect("regex patterns are checked by welds-macros")
+        });
+        match re.is_match(value.as_ref()) {
+            true => Ok(()),
+            false => Err(format!("must match {}", pattern)),
+        }
+    }
+}
@@ -0,0 +1,100 @@
+#[cfg(any(feature = "chrono", feature = "time"))]
+use super::Timestamp;
+use std::any::Any;
+use welds_connections::Param;
+
+/// Optimistic locking. Tells welds which column holds the version of a row
+///
+/// This is AUTOMATICALLY implemented by the Welds Macros.
+/// Mark an integer or timestamp field with `#[welds(version)]` to turn it on.
+/// Timestamps need the `chrono` or `time` feature
+/// ```rust,ignore
+/// #[derive(WeldsModel)]
+/// #[welds(table = "accounts")]
+/// struct Account {
+///     #[welds(primary_key)]
+///     id: i32,
+///     #[welds(version)]
+///     lock_version: i32,
+/// }
+/// ```
+/// Updates and deletes only touch the row if its version hasn't moved since it was loaded.
+/// If it has, the action fails with `WeldsError::StaleObject`
+pub trait HasVersion {
+    /// The db name of the version column, None if the model isn't versioned
+    fn version_column() -> Option<&'static str>;
+
+    /// Moves the version forward, handing back the value it had before
+    fn bump_version(&mut self) -> Option<Box<dyn VersionValue>>;
+
+    /// Puts back a version that was handed out by `bump_version`
+    fn restore_version(&mut self, old: Box<dyn VersionValue>);
+}
+
+/// The types that can be used as a version column
+///
+/// Integers are incremented by one. Timestamps are set to now.
+pub trait NextVersion {
+    fn next_version(&self) -> Self;
+}
+
+macro_rules! next_version_int {
+    ($($t:ty),*) => {
+        $(
+            impl NextVersion for $t {
+                fn next_version(&self) -> Self {
+                    self.wrapping_add(1)
+                }
+            }
+        )*
+    };
+}
+
+next_version_int!(i16, i32, i64, u16, u32, u64);
+
+#[cfg(feature = "chrono")]
+impl NextVersion for chrono::DateTime<chrono::Utc> {
+    fn next_version(&self) -> Self {
+        Timestamp::now()
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl NextVersion for chrono::NaiveDateTime {
+    fn next_version(&self) -> Self {
+        Timestamp::now()
+    }
+}
+
+#[cfg(feature = "time")]
+impl NextVersion for time::OffsetDateTime {
+    fn next_version(&self) -> Self {
+        Timestamp::now()
+    }
+}
+
+#[cfg(feature = "time")]
+impl NextVersion for time::PrimitiveDateTime {
+    fn next_version(&self) -> Self {
+        Timestamp::now()
+    }
+}
+
+/// A version that was taken out of a model. Used to find the row as it was when loaded
+#[doc(hidden)]
+pub trait VersionValue: Send + Sync {
+    fn as_param(&self) -> &(dyn Param + Sync);
+    fn into_any(self: Box<Self>) -> Box<dyn Any>;
+}
+
+impl<V> VersionValue for V
+where
+    V: 'static + Param + Send + Sync,
+{
+    fn as_param(&self) -> &(dyn Param + Sync) {
+        self
+    }
+    fn into_any(self: Box<Self>) -> Box<dyn Any> {
+        self
+    }
+}
@@ -0,0 +1,160 @@
+//! Observers are told about every model saved or deleted through a `DbState`.
+//!
+//! Unlike hooks they are registered at runtime, from any crate, and see every model.
+//! Useful for cross-cutting concerns like clearing caches or updating a search index.
+//! ```rust,ignore
+//! use welds::observers::{self, EventKind, ModelEvent};
+//!
+//! observers::register(|event: &ModelEvent| {
+//!     if event.table().name() == "products" && event.kind() != EventKind::Created {
+//!         if let Some(id) = event.id::<i32>() {
+//!             cache.remove(*id);
+//!         }
+//!     }
+//! });
+//! ```
+//! Observers are called after the change is written, while the save/delete is running.
+//! Keep them quick, send slow work to a channel or task.
+//!
+//! Bulk operations do not notify observers, see the bulk hooks in `model_traits::hooks`
+
+use crate::model_traits::{Snapshot, TableIdent};
+use std::sync::{Arc, RwLock};
+
+/// What happened to the model
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EventKind {
+    Created,
+    Updated,
+    /// The row was deleted, or flagged as deleted for `#[welds(soft_delete)]` models
+    Deleted,
+}
+
+/// A model was written to the database
+pub struct ModelEvent<'a> {
+    kind: EventKind,
+    model: &'static str,
+    table: &'a TableIdent,
+    primary_key: &'a Snapshot,
+    changed_columns: &'a [&'static str],
+}
+
+impl<'a> ModelEvent<'a> {
+    pub(crate) fn new(
+        kind: EventKind,
+        model: &'static str,
+        table: &'a TableIdent,
+        primary_key: &'a Snapshot,
+        changed_columns: &'a [&'static str],
+    ) -> Self {
+        Self {
+            kind,
+            model,
+            table,
+            primary_key,
+            changed_columns,
+        }
+    }
+
+    /// Created, Updated or Deleted
+    pub fn kind(&self) -> EventKind {
+        self.kind
+    }
+
+    /// The rust type name of the model
+    pub fn model(&self) -> &'static str {
+        self.model
+    }
+
+    /// The table the model was written to
+    pub fn table(&self) -> &TableIdent {
+        self.table
+    }
+
+    /// The names of the primary key columns
+    pub fn primary_key_columns(&self) -> Vec<&'static str> {
+        self.primary_key.columns()
+    }
+
+    /// The value of a primary key column, None if it isn't a `V`
+    pub fn primary_key<V: 'static>(&self, column: &str) -> Option<&V> {
+        self.primary_key.get(column)?.as_any().downcast_ref::<V>()
+    }
+
+    /// The value of the first primary key column, None if it isn't a `V`
+    pub fn id<V: 'static>(&self) -> Option<&V> {
+        let column = *self.primary_key.columns().first()?;
+        self.primary_key(column)
+    }
+
+    /// The columns that were written.
+    /// All the columns for Created, none for Deleted
+    pub fn changed_columns(&self) -> &[&'static str] {
+        self.changed_columns
+    }
+}
+
+/// Receives an event for every model saved or deleted through a `DbState`
+///
+/// Implemented for any `Fn(&ModelEvent)`
+pub trait Observer: Send + Sync {
+    fn notify(&self, event: &ModelEvent<'_>);
+}
+
+impl<F> Observer for F
+where
+    F: Fn(&ModelEvent<'_>) + Send + Sync,
+{
+    fn notify(&self, event: &ModelEvent<'_>) {
+        self(event)
+    }
+}
+
+/// Returned from `register`, used to remove the observer
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ObserverId(u64);
+
+struct Registry {
+    next_id: u64,
+    observers: Vec<(ObserverId, Arc<dyn Observer>)>,
+}
+
+static REGISTRY: RwLock<Registry> = RwLock::new(Registry {
+    next_id: 0,
+    observers: Vec::new(),
+});
+
+/// Adds an observer for all the models in the process
+pub fn register(observer: impl Observer + 'static) -> ObserverId {
+    let mut registry = REGISTRY.write().unwrap_or_else(|err| err.into_inner());
+    let id = ObserverId(registry.next_id);
+    registry.next_id += 1;
+    registry.observers.push((id, Arc::new(observer)));
+    id
+}
+
+/// Removes an observer. Returns false if it was already removed
+pub fn unregister(id: ObserverId) -> bool {
+    let mut registry = REGISTRY.write().unwrap_or_else(|err| err.into_inner());
+    let before = registry.observers.len();
+    registry.observers.retain(|(x, _)| *x != id);
+    registry.observers.len() != before
+}
+
+/// true if anyone is listening, used to skip building events no one will see
+pub(crate) fn any() -> bool {
+    let registry = REGISTRY.read().unwrap_or_else(|err| err.into_inner());
+    !registry.observers.is_empty()
+}
+
+/// Sends the event to each observer
+pub(crate) fn notify(event: &ModelEvent<'_>) {
+    // copied out so an observer can register/unregister without deadlocking
+    let observers: Vec<Arc<dyn Observer>> = {
+        let registry = REGISTRY.read().unwrap_or_else(|err| err.into_inner());
+        registry.observers.iter().map(|(_, o)| o.clone()).collect()
+    };
+    for observer in observers {
+        observer.notify(event);
+    }
+}
@@ -1,5 +1,7 @@
 pub use crate::Client;
 pub use crate::TransactStart;
+pub use crate::WeldsEmbed;
+pub use crate::WeldsEnum;
 pub use crate::WeldsModel;
 pub use crate::exts::{VecRowExt, VecStateExt};
 pub use crate::state::DbState;
@@ -1,13 +1,17 @@
 use super::clause::{self, AsOptField};
 use super::select_cols::SelectBuilder;
 pub use super::update::bulk::UpdateBuilder;
-use crate::model_traits::{HasSchema, TableColumns, TableInfo, UniqueIdentifier};
+use crate::Client;
+use crate::errors::Result;
+use crate::model_traits::{HasSchema, TableColumns, TableIdent, TableInfo, WriteToArgs};
 use crate::query::clause::exists::ExistIn;
 use crate::query::clause::{AsFieldName, AssignmentAdder, ClauseAdder, OrderBy};
-use crate::query::include::IncludeBuilder;
+use crate::query::include::{IncludeBuilder, Included};
 use crate::query::optional::Optional;
-use crate::relations::{HasRelations, Relationship};
+use crate::relations::{DiscriminatorSide, HasRelations, Relationship};
+use crate::writers::TableWriter;
 use crate::writers::alias::TableAlias;
+use std::borrow::Cow;
 use std::marker::PhantomData;
 use std::sync::Arc;
 use welds_connections::Param;
@@ -36,6 +40,10 @@ pub struct QueryBuilder<T> {
     pub(crate) orderby: Vec<OrderBy>,
     pub(crate) alias: String,
     pub(crate) alias_asigner: Arc<TableAlias>,
+    // the clause in `wheres` that filters on soft deleted rows
+    pub(crate) soft_delete: Option<Arc<Box<dyn ClauseAdder>>>,
+    // the table to use in place of the model's table. `in_table`
+    pub(crate) table: Option<TableIdent>,
 }
 
 impl<T> Clone for QueryBuilder<T> {
@@ -49,6 +57,8 @@ impl<T> Clone for QueryBuilder<T> {
             exist_ins: self.exist_ins.clone(),
             alias: self.alias.clone(),
             alias_asigner: self.alias_asigner.clone(),
+            soft_delete: self.soft_delete.clone(),
+            table: self.table.clone(),
         }
     }
 }
@@ -69,7 +79,7 @@ where
     pub fn new() -> Self {
         let ta = TableAlias::new();
         let alias = ta.next();
-        Self {
+        let qb = Self {
             _t: Default::default(),
             wheres: Vec::default(),
             limit: None,
@@ -78,7 +88,90 @@ where
             exist_ins: Default::default(),
             alias,
             alias_asigner: Arc::new(ta),
+            soft_delete: None,
+            table: None,
+        };
+        // Soft deleted rows are hidden by default
+        match <T as HasSchema>::Schema::soft_delete_column() {
+            Some(col) => qb.set_soft_delete_filter(Box::new(col.not_deleted_clause())),
+            None => qb,
+        }
+    }
+
+    /// Include rows that have been soft deleted. `#[welds(soft_delete)]`
+    ///
+    /// By default queries skip over soft deleted rows
+    pub fn with_deleted(mut self) -> Self {
+        if let Some(filter) = self.soft_delete.take() {
+            self.wheres.retain(|w| !Arc::ptr_eq(w, &filter));
+        }
+        self
+    }
+
+    /// Only return rows that have been soft deleted. `#[welds(soft_delete)]`
+    pub fn only_deleted(self) -> Self {
+        let qb = self.with_deleted();
+        match <T as HasSchema>::Schema::soft_delete_column() {
+            Some(col) => qb.set_soft_delete_filter(Box::new(col.deleted_clause())),
+            None => qb,
+        }
+    }
+
+    /// Run this query on another table with the same columns as the model.
+    ///
+    /// Used when one model is stored in many tables, partitions or tenant schemas.
+    /// The name is `table` or `schema.table`. Rows loaded from this query are saved back to it.
+    /// ```
+    /// use welds::prelude::*;
+    ///
+    /// #[derive(Debug, Default, WeldsModel)]
+    /// #[welds(table = "events")]
+    /// struct Event {
+    ///     #[welds(primary_key)]
+    ///     pub id: i32,
+    ///     pub kind: String,
+    /// }
+    ///
+    /// async fn example(db: &dyn Client) -> welds::errors::Result<()> {
+    ///     let events = Event::all().in_table("events_2025_01")?.run(db).await?;
+    ///     // [SELECT ... FROM events_2025_01 t1]
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    /// Returns `WeldsError::InvalidTableName` if the name is not a plain table name
+    pub fn in_table(mut self, table: impl AsRef<str>) -> Result<Self> {
+        self.table = Some(TableWriter::validate(table.as_ref())?);
+        Ok(self)
+    }
+
+    /// The table this query runs on
+    pub(crate) fn table_ident(&self) -> TableIdent {
+        match &self.table {
+            Some(table) => table.clone(),
+            None => TableIdent::from_model::<T>(),
+        }
+    }
+
+    /// The query with its tables placed in the client's default schema. `SchemaClient`
+    pub(crate) fn for_client(&self, client: &dyn Client) -> Cow<'_, Self> {
+        let schema = client.default_schema();
+        if schema.is_none() {
+            return Cow::Borrowed(self);
         }
+        let mut qb = self.clone();
+        qb.table = Some(qb.table_ident().or_schema(schema));
+        for exist_in in &mut qb.exist_ins {
+            exist_in.set_default_schema(schema);
+        }
+        Cow::Owned(qb)
+    }
+
+    fn set_soft_delete_filter(mut self, clause: Box<dyn ClauseAdder>) -> Self {
+        let clause = Arc::new(clause);
+        self.wheres.push(clause.clone());
+        self.soft_delete = Some(clause);
+        self
     }
 
     /// Filter the results returned by this query.
@@ -218,10 +311,19 @@ where
         <T as HasRelations>::Relation: Default,
     {
         let ship = relationship(Default::default());
+        if let Some(join_table) = ship.join_table() {
+            join_table.filter_linking(&filter, &mut self);
+            return self;
+        }
+        let mut filter = filter;
+        match ship.discriminator() {
+            Some(d) if d.side == DiscriminatorSide::Source => d.filter(&mut self),
+            Some(d) => d.filter(&mut filter),
+            None => {}
+        }
         let out_col = ship.my_key();
-        let inner_tn = <R as HasSchema>::Schema::identifier();
         let inner_col = ship.their_key();
-        let mut exist_in = ExistIn::new(&filter, out_col, inner_tn, inner_col);
+        let mut exist_in = ExistIn::new(&filter, out_col, inner_col);
         exist_in.set_aliases(&self.alias_asigner);
         self.exist_ins.push(exist_in);
         self
@@ -244,11 +346,21 @@ where
         let ship = relationship(Default::default());
         let mut qb: QueryBuilder<R> = QueryBuilder::new();
         qb.set_aliases(&self.alias_asigner);
+        if let Some(join_table) = ship.join_table() {
+            join_table.filter_linked(self, &mut qb);
+            return qb;
+        }
+
+        let mut primary = self.clone();
+        match ship.discriminator() {
+            Some(d) if d.side == DiscriminatorSide::Source => d.filter(&mut primary),
+            Some(d) => d.filter(&mut qb),
+            None => {}
+        }
 
         let out_col = ship.their_key();
-        let inner_tn = <T as HasSchema>::Schema::identifier();
         let inner_col = ship.my_key();
-        let exist_in = ExistIn::new(self, out_col, inner_tn, inner_col);
+        let exist_in = ExistIn::new(&primary, out_col, inner_col);
 
         qb.exist_ins.push(exist_in);
         qb
@@ -531,15 +643,15 @@ where
     pub fn include<R, Ship>(
         self,
         relationship: impl Fn(<T as HasRelations>::Relation) -> Ship,
-    ) -> IncludeBuilder<T>
+    ) -> IncludeBuilder<T, ((), Included<R>)>
     where
-        T: 'static + HasRelations,
+        T: 'static + Sync + HasRelations + WriteToArgs,
         Ship: 'static + Sync + Relationship<T, R>,
         R: HasSchema,
         R: 'static,
         R: Send + Sync + HasSchema,
-        <R as HasSchema>::Schema: TableInfo + TableColumns + UniqueIdentifier,
-        <T as HasSchema>::Schema: TableInfo + TableColumns + UniqueIdentifier,
+        <R as HasSchema>::Schema: TableInfo + TableColumns,
+        <T as HasSchema>::Schema: TableInfo + TableColumns,
         <T as HasRelations>::Relation: Default,
         R: TryFrom<crate::connections::Row>,
         crate::errors::WeldsError: From<<R as TryFrom<crate::connections::Row>>::Error>,
@@ -556,19 +668,41 @@ where
         self,
         relationship: impl Fn(<T as HasRelations>::Relation) -> Ship,
         qb: QueryBuilder<R>,
-    ) -> IncludeBuilder<T>
+    ) -> IncludeBuilder<T, ((), Included<R>)>
     where
-        T: 'static + HasRelations,
+        T: 'static + Sync + HasRelations + WriteToArgs,
         Ship: 'static + Sync + Relationship<T, R>,
         R: HasSchema,
         R: 'static,
         R: Send + Sync + HasSchema,
-        <R as HasSchema>::Schema: TableInfo + TableColumns + UniqueIdentifier,
-        <T as HasSchema>::Schema: TableInfo + TableColumns + UniqueIdentifier,
+        <R as HasSchema>::Schema: TableInfo + TableColumns,
+        <T as HasSchema>::Schema: TableInfo + TableColumns,
         <T as HasRelations>::Relation: Default,
         R: TryFrom<crate::connections::Row>,
         crate::errors::WeldsError: From<<R as TryFrom<crate::connections::Row>>::Error>,
     {
         IncludeBuilder::new(self).include_where(relationship, qb)
     }
+
+    /// Include models related to this model, and models related to those models.
+    /// See `IncludeBuilder::then_include`
+    pub fn then_include<R, Ship, N>(
+        self,
+        relationship: impl Fn(<T as HasRelations>::Relation) -> Ship,
+        nested: impl FnOnce(IncludeBuilder<R>) -> IncludeBuilder<R, N>,
+    ) -> IncludeBuilder<T, ((), Included<R, N>)>
+    where
+        T: 'static + Sync + HasRelations + WriteToArgs,
+        Ship: 'static + Sync + Relationship<T, R>,
+        R: HasSchema,
+        R: 'static,
+        R: Send + Sync + HasSchema,
+        <R as HasSchema>::Schema: TableInfo + TableColumns,
+        <T as HasSchema>::Schema: TableInfo + TableColumns,
+        <T as HasRelations>::Relation: Default,
+        R: TryFrom<crate::connections::Row>,
+        crate::errors::WeldsError: From<<R as TryFrom<crate::connections::Row>>::Error>,
+    {
+        IncludeBuilder::new(self).then_include(relationship, nested)
+    }
 }
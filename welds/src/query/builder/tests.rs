@@ -1,5 +1,6 @@
-use crate::WeldsModel;
+use crate::{WeldsEmbed, WeldsEnum, WeldsModel};
 use welds_connections::Syntax;
+use welds_connections::db_enum::DbEnum;
 
 #[derive(Debug, Default, WeldsModel)]
 #[welds(table = "products")]
@@ -23,6 +24,66 @@ struct OrderC {
     pub price: i32,
 }
 
+#[derive(Debug, Default, Clone, PartialEq, WeldsEnum)]
+#[welds_path(crate)] // needed only within the welds crate.
+enum TicketStatus {
+    #[default]
+    Open,
+    #[welds(rename = "closed")]
+    Closed,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, WeldsEnum)]
+#[welds_path(crate)] // needed only within the welds crate.
+#[repr(i16)]
+enum TicketPriority {
+    #[default]
+    Low = 1,
+    High = 10,
+}
+
+#[derive(Debug, Default, WeldsModel)]
+#[welds(table = "tickets")]
+#[welds_path(crate)] // needed only within the welds crate.
+struct Ticket {
+    #[welds(primary_key)]
+    pub id: i32,
+    pub status: TicketStatus,
+    pub priority: TicketPriority,
+}
+
+#[derive(Debug, Default, WeldsModel)]
+#[welds(table = "ticket_reviews")]
+#[welds_path(crate)] // needed only within the welds crate.
+struct TicketReview {
+    #[welds(primary_key)]
+    pub id: i32,
+    pub status: Option<TicketStatus>,
+    pub priority: Option<TicketPriority>,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, WeldsEmbed)]
+#[welds_path(crate)] // needed only within the welds crate.
+struct Address {
+    pub street: String,
+    #[welds(rename = "town")]
+    pub city: String,
+    pub zip: Option<String>,
+}
+
+#[derive(Debug, Default, WeldsModel)]
+#[welds(table = "shops")]
+#[welds_path(crate)] // needed only within the welds crate.
+struct Shop {
+    #[welds(primary_key)]
+    pub id: i32,
+    pub name: String,
+    #[welds(flatten)]
+    pub address: Address,
+    #[welds(flatten(prefix = "billing_"))]
+    pub billing: Address,
+}
+
 #[test]
 fn should_be_able_to_map_query_from_belongs_to() {
     futures::executor::block_on(async move {
@@ -42,3 +103,289 @@ fn should_be_able_to_map_query_from_has_many() {
         assert_eq!(sql, valid);
     });
 }
+
+#[derive(Debug, Default, WeldsModel)]
+#[welds(table = "customers")]
+#[welds_path(crate)] // needed only within the welds crate.
+#[welds(HasMany(notes, NoteC, "customer_id"))]
+struct CustomerC {
+    #[welds(primary_key)]
+    pub id: i32,
+    #[welds(soft_delete)]
+    pub deleted: bool,
+}
+
+#[derive(Debug, Default, WeldsModel)]
+#[welds(table = "notes")]
+#[welds_path(crate)] // needed only within the welds crate.
+#[welds(BelongsTo(customer, CustomerC, "customer_id"))]
+struct NoteC {
+    #[welds(primary_key)]
+    pub id: i32,
+    pub customer_id: i32,
+}
+
+#[test]
+fn should_skip_soft_deleted_rows_by_default() {
+    let q = CustomerC::all().where_col(|c| c.id.gt(1));
+    let sql = q.to_sql(Syntax::Postgres);
+    let valid = r#"SELECT t1."id", t1."deleted" FROM customers t1 WHERE ( t1."deleted" = $1 AND t1."id" > $2 )"#;
+    assert_eq!(sql, valid);
+}
+
+#[test]
+fn should_be_able_to_include_soft_deleted_rows() {
+    let q = CustomerC::all().with_deleted().where_col(|c| c.id.gt(1));
+    let sql = q.to_sql(Syntax::Postgres);
+    let valid = r#"SELECT t1."id", t1."deleted" FROM customers t1 WHERE ( t1."id" > $1 )"#;
+    assert_eq!(sql, valid);
+}
+
+#[test]
+fn should_be_able_to_select_only_soft_deleted_rows() {
+    let q = CustomerC::all()
+        .only_deleted()
+        .with_deleted()
+        .only_deleted();
+    let sql = q.to_sql(Syntax::Postgres);
+    let valid = r#"SELECT t1."id", t1."deleted" FROM customers t1 WHERE ( t1."deleted" = $1 )"#;
+    assert_eq!(sql, valid);
+}
+
+#[test]
+fn should_skip_soft_deleted_rows_when_mapping_queries() {
+    let q = CustomerC::all().map_query(|c| c.notes);
+    let sql = q.to_sql(Syntax::Postgres);
+    let valid = r#"SELECT t2."id", t2."customer_id" FROM notes t2 WHERE ( EXISTS ( SELECT "id" FROM customers t1 WHERE t1."deleted" = $1 AND t1."id" = t2."customer_id" ) )"#;
+    assert_eq!(sql, valid);
+}
+
+#[test]
+fn should_be_able_to_filter_on_an_enum_column() {
+    let q = Ticket::all()
+        .where_col(|t| t.status.equal(TicketStatus::Closed))
+        .where_col(|t| t.priority.equal(TicketPriority::High));
+    let sql = q.to_sql(Syntax::Postgres);
+    let valid = r#"SELECT t1."id", t1."status", t1."priority" FROM tickets t1 WHERE ( t1."status" = $1 AND t1."priority" = $2 )"#;
+    assert_eq!(sql, valid);
+}
+
+#[test]
+fn should_map_enums_to_their_column_values() {
+    assert_eq!(TicketStatus::Open.to_repr(), "Open");
+    assert_eq!(TicketStatus::Closed.to_repr(), "closed");
+    assert_eq!(TicketPriority::High.to_repr(), 10);
+    assert_eq!(
+        TicketStatus::from_repr("closed".to_owned()).unwrap(),
+        TicketStatus::Closed
+    );
+    assert_eq!(TicketPriority::from_repr(1).unwrap(), TicketPriority::Low);
+    assert!(TicketPriority::from_repr(2).is_err());
+}
+
+#[test]
+fn should_write_nullable_enum_columns() {
+    futures::executor::block_on(async move {
+        let review = TicketReview {
+            id: 0,
+            status: Some(TicketStatus::Closed),
+            priority: None,
+        };
+        let mut review = crate::state::DbState::new_uncreated(review);
+        let client = welds_connections::noop::build(Syntax::Postgres);
+        let _ = review.save(&client).await;
+        let sql = client.last_sql().unwrap();
+        let valid =
+            r#"INSERT INTO ticket_reviews ("status", "priority") VALUES ($1, $2) RETURNING *"#;
+        assert_eq!(sql, valid);
+        assert_eq!(client.args_count().unwrap(), 2);
+    });
+}
+
+#[test]
+fn should_select_and_filter_on_flattened_columns() {
+    let q =
+        Shop::where_col(|s| s.address.city.equal("Paris")).where_col(|s| s.billing.zip.equal(None));
+    let sql = q.to_sql(Syntax::Postgres);
+    let valid = r#"SELECT t1."id", t1."name", t1."street", t1."town", t1."zip", t1."billing_street", t1."billing_town", t1."billing_zip" FROM shops t1 WHERE ( t1."town" = $1 AND t1."billing_zip" IS NULL )"#;
+    assert_eq!(sql, valid);
+}
+
+#[test]
+fn should_write_flattened_columns_on_insert() {
+    futures::executor::block_on(async move {
+        let shop = Shop {
+            name: "corner".to_owned(),
+            ..Default::default()
+        };
+        let mut shop = crate::state::DbState::new_uncreated(shop);
+        let client = welds_connections::noop::build(Syntax::Postgres);
+        let _ = shop.save(&client).await;
+        let sql = client.last_sql().unwrap();
+        let valid = r#"INSERT INTO shops ("name", "street", "town", "zip", "billing_street", "billing_town", "billing_zip") VALUES ($1, $2, $3, $4, $5, $6, $7) RETURNING *"#;
+        assert_eq!(sql, valid);
+        assert_eq!(client.args_count().unwrap(), 7);
+    });
+}
+
+#[test]
+fn should_track_changes_to_flattened_columns() {
+    use crate::model_traits::{ChangeSet, ColumnDefaultCheck, TrackChanges};
+    let mut shop = Shop::default();
+    let before = shop.snapshot();
+    shop.billing.city = "Lyon".to_owned();
+    let changes = ChangeSet::new(&shop, Some(&before));
+    assert_eq!(changes.changed_columns(), vec!["billing_town"]);
+    assert!(shop.col_is_default("town").unwrap());
+    assert!(!shop.col_is_default("billing_town").unwrap());
+    assert!(shop.col_is_default("nope").is_err());
+}
+
+#[test]
+fn should_build_the_prefixed_names_of_a_field_once() {
+    use crate::model_traits::embedded::Prefix;
+    static PREFIX: Prefix = Prefix::new("billing_");
+    let first = PREFIX.names(&["street", "town"]);
+    let second = PREFIX.names(&["street", "town"]);
+    assert_eq!(first, ["billing_street", "billing_town"]);
+    assert!(std::ptr::eq(first, second));
+}
+
+#[test]
+fn should_run_a_query_on_another_table() {
+    let q = OrderC::where_col(|o| o.price.gt(1))
+        .in_table("tenant_a.orders_2025_01")
+        .unwrap();
+    let sql = q.to_sql(Syntax::Postgres);
+    let valid = r#"SELECT t1."id", t1."product_id", t1."price" FROM tenant_a.orders_2025_01 t1 WHERE ( t1."price" > $1 )"#;
+    assert_eq!(sql, valid);
+
+    let sql = q.limit(5).delete_sql(Syntax::Mysql);
+    let valid = r#"DELETE FROM tenant_a.orders_2025_01 WHERE (  tenant_a.orders_2025_01.id IN (SELECT t1.id FROM tenant_a.orders_2025_01 t1 WHERE ( t1.price > ? ) ORDER BY 1 LIMIT 0, 5)  )"#;
+    assert_eq!(sql, valid);
+}
+
+#[test]
+fn should_reject_table_names_that_are_not_plain_names() {
+    for name in [
+        "",
+        "orders; DROP TABLE x",
+        "a.b.c",
+        "orders\"",
+        ".orders",
+        "t`x",
+        "orders t9 CROSS JOIN secrets",
+        "orders\tx",
+    ] {
+        let err = OrderC::all().in_table(name).err();
+        assert!(
+            matches!(err, Some(crate::WeldsError::InvalidTableName(_))),
+            "{name}"
+        );
+    }
+}
+
+#[test]
+fn should_include_from_another_table() {
+    let q = ProductC::all()
+        .in_table("products_eu")
+        .unwrap()
+        .include(|p| p.orders);
+    let sqls = q.to_sql(Syntax::Postgres);
+    let valid = r#"SELECT t2."id", t2."product_id", t2."price" FROM orders t2 WHERE ( EXISTS ( SELECT "pid" FROM products_eu t1 WHERE t1."pid" = t2."product_id" ) )"#;
+    assert_eq!(sqls[1], valid);
+}
+
+#[test]
+fn should_save_and_delete_models_in_another_table() {
+    futures::executor::block_on(async move {
+        let client = welds_connections::noop::build(Syntax::Postgres);
+        let order = crate::state::DbState::new_uncreated(OrderC::default());
+        let mut order = order.in_table("orders_2025_01").unwrap();
+        let _ = order.save(&client).await;
+        let sql = client.last_sql().unwrap();
+        assert!(sql.starts_with("INSERT INTO orders_2025_01 "), "{sql}");
+
+        let order = crate::state::DbState::db_loaded_tracked(OrderC::default());
+        let mut order = order.in_table("orders_2025_01").unwrap();
+        order.price = 3;
+        let _ = order.save(&client).await;
+        let sql = client.last_sql().unwrap();
+        assert_eq!(sql, r#"UPDATE orders_2025_01 SET "price"=$1 where "id"=$2"#);
+
+        let _ = order.delete(&client).await;
+        let sql = client.last_sql().unwrap();
+        assert_eq!(sql, r#"DELETE FROM orders_2025_01 where "id"=$1"#);
+    });
+}
+
+#[test]
+fn should_run_queries_in_the_clients_schema() {
+    use welds_connections::schema::SchemaClient;
+    futures::executor::block_on(async move {
+        let noop = welds_connections::noop::build(Syntax::Postgres);
+        let client = SchemaClient::new(noop.clone(), "tenant_a").unwrap();
+
+        let q = ProductC::all().map_query(|p| p.orders);
+        let _ = q.run(&client).await;
+        let sql = noop.last_sql().unwrap();
+        let valid = r#"SELECT t2."id", t2."product_id", t2."price" FROM tenant_a.orders t2 WHERE ( EXISTS ( SELECT "pid" FROM tenant_a.products t1 WHERE t1."pid" = t2."product_id" ) )"#;
+        assert_eq!(sql, valid);
+
+        // tables given a schema are left alone
+        let q = OrderC::all().in_table("archive.orders").unwrap();
+        let _ = q.count(&client).await;
+        let sql = noop.last_sql().unwrap();
+        assert_eq!(
+            sql,
+            r#"SELECT CAST( COUNT(t1.*) as BIGINT ) FROM archive.orders t1"#
+        );
+
+        let _ = OrderC::all().limit(5).delete(&client).await;
+        let sql = noop.last_sql().unwrap();
+        let valid = r#"DELETE FROM tenant_a.orders WHERE (  tenant_a.orders."id" IN (SELECT t1."id" FROM tenant_a.orders t1 ORDER BY 1 OFFSET 0 LIMIT 5)  )"#;
+        assert_eq!(sql, valid);
+    });
+}
+
+#[test]
+fn should_save_models_in_the_clients_schema() {
+    use welds_connections::schema::SchemaClient;
+    futures::executor::block_on(async move {
+        let noop = welds_connections::noop::build(Syntax::Postgres);
+        let client = SchemaClient::new(noop.clone(), "tenant_a").unwrap();
+
+        let mut order = crate::state::DbState::db_loaded_tracked(OrderC::default());
+        order.price = 3;
+        let _ = order.save(&client).await;
+        let sql = noop.last_sql().unwrap();
+        assert_eq!(
+            sql,
+            r#"UPDATE tenant_a.orders SET "price"=$1 where "id"=$2"#
+        );
+
+        let _ = order.delete(&client).await;
+        let sql = noop.last_sql().unwrap();
+        assert_eq!(sql, r#"DELETE FROM tenant_a.orders where "id"=$1"#);
+    });
+}
+
+#[test]
+fn should_reject_schema_names_that_are_not_plain_names() {
+    use welds_connections::schema::SchemaClient;
+    let noop = welds_connections::noop::build(Syntax::Postgres);
+    for name in [
+        "",
+        "a.b",
+        "tenant\"; DROP TABLE x",
+        "tenant_a t9",
+        " tenant_a",
+        "tenänt_a",
+    ] {
+        let err = SchemaClient::new(noop.clone(), name).err();
+        assert!(
+            matches!(err, Some(welds_connections::Error::InvalidSchemaName(_))),
+            "{name}"
+        );
+    }
+}
@@ -12,6 +12,12 @@ pub trait AssignmentAdder: Send + Sync {
         'lam: 'p;
     /// Returns the SQL snipit for this clause
     fn clause(&self, syntax: Syntax, alias: &str, next_params: &NextParam) -> Option<String>;
+    /// The column being assigned, if known
+    fn column(&self) -> Option<&str> {
+        None
+    }
+    /// Returns the SQL of the value being assigned, binds the same args as `bind`
+    fn value(&self, syntax: Syntax, alias: &str, next_params: &NextParam) -> String;
 }
 
 impl<T> AssignmentAdder for ClauseColValEqual<T>
@@ -49,6 +55,17 @@ where
         let clause: String = parts.join("");
         Some(clause)
     }
+
+    fn column(&self) -> Option<&str> {
+        Some(self.col)
+    }
+
+    fn value(&self, _syntax: Syntax, _alias: &str, next_params: &NextParam) -> String {
+        if self.null_clause {
+            return "NULL".to_owned();
+        }
+        next_params.next()
+    }
 }
 
 impl<T> AssignmentAdder for SetColVal<T>
@@ -69,6 +86,14 @@ where
         let sql = format!("{}={}", colname, next_params.next());
         Some(sql)
     }
+
+    fn column(&self) -> Option<&str> {
+        Some(&self.col_raw)
+    }
+
+    fn value(&self, _syntax: Syntax, _alias: &str, next_params: &NextParam) -> String {
+        next_params.next()
+    }
 }
 
 impl AssignmentAdder for SetColNull {
@@ -86,6 +111,14 @@ impl AssignmentAdder for SetColNull {
         let sql = format!("{}=NULL", colname);
         Some(sql)
     }
+
+    fn column(&self) -> Option<&str> {
+        Some(&self.col_raw)
+    }
+
+    fn value(&self, _syntax: Syntax, _alias: &str, _next_params: &NextParam) -> String {
+        "NULL".to_owned()
+    }
 }
 
 impl AssignmentAdder for AssignmentManual {
@@ -101,12 +134,16 @@ impl AssignmentAdder for AssignmentManual {
 
     /// Returns the SQL snipit for this clause
     fn clause(&self, syntax: Syntax, alias: &str, next_params: &NextParam) -> Option<String> {
-        // build the column name
-        let mut parts = vec![];
-
         let colname = ColumnWriter::new(syntax).excape(self.col);
-        parts.push(colname);
-        parts.push(" = ( ".to_string());
+        let value = self.value(syntax, alias, next_params);
+        Some(format!("{colname} = {value}"))
+    }
+    fn column(&self) -> Option<&str> {
+        Some(self.col)
+    }
+
+    fn value(&self, _syntax: Syntax, alias: &str, next_params: &NextParam) -> String {
+        let mut parts = vec!["( ".to_string()];
 
         // swap out all the '?' with the correct params type for the syntax
         // swap out all the '$' the table prefix/alias used in this table
@@ -119,7 +156,6 @@ impl AssignmentAdder for AssignmentManual {
         }
         parts.push(" )".to_string());
 
-        let clause = parts.join("");
-        Some(clause)
+        parts.join("")
     }
 }
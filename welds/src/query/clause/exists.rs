@@ -1,5 +1,6 @@
 use super::ClauseAdder;
 use crate::Syntax;
+use crate::model_traits::{HasSchema, TableIdent};
 use crate::query::builder::QueryBuilder;
 use crate::query::clause::OrderBy;
 use crate::query::clause::ParamArgs;
@@ -14,7 +15,7 @@ use std::sync::Arc;
 pub struct ExistIn {
     outer_column: String,
     inner_column: String,
-    inner_tablename: &'static [&'static str],
+    inner_tablename: TableIdent,
     pub(crate) inner_tablealias: String,
     wheres: Vec<Arc<Box<dyn ClauseAdder>>>,
     inner_exists_ins: Vec<Self>,
@@ -24,16 +25,14 @@ pub struct ExistIn {
 }
 
 impl ExistIn {
-    pub(crate) fn new<T>(
-        sb: &QueryBuilder<T>,
-        outer_column: String,
-        inner_tablename: &'static [&'static str],
-        inner_column: String,
-    ) -> Self {
+    pub(crate) fn new<T>(sb: &QueryBuilder<T>, outer_column: String, inner_column: String) -> Self
+    where
+        T: Send + HasSchema,
+    {
         ExistIn {
             outer_column,
             inner_column,
-            inner_tablename,
+            inner_tablename: sb.table_ident(),
             inner_tablealias: sb.alias.clone(),
             wheres: sb.wheres.clone(),
             inner_exists_ins: sb.exist_ins.clone(),
@@ -51,6 +50,14 @@ impl ExistIn {
         }
     }
 
+    // place the tables without a schema in the client's default schema
+    pub(crate) fn set_default_schema(&mut self, schema: Option<&str>) {
+        self.inner_tablename = self.inner_tablename.clone().or_schema(schema);
+        for sub in &mut self.inner_exists_ins {
+            sub.set_default_schema(schema);
+        }
+    }
+
     fn inner_fk_equal(&self, syntax: Syntax, tablealias: &str) -> String {
         let col_writer = ColumnWriter::new(syntax);
         format!(
@@ -70,7 +77,7 @@ impl ExistIn {
 
     fn exists_clause(&self, syntax: Syntax, _tablealias: &str, inner_clauses: &str) -> String {
         let tails = self.tails(syntax, &self.inner_tablealias);
-        let inner_tablename = TableWriter::new(syntax).write2(self.inner_tablename);
+        let inner_tablename = TableWriter::new(syntax).write(&self.inner_tablename);
         let col_writer = ColumnWriter::new(syntax);
         format!(
             "EXISTS ( SELECT {} FROM {} {} WHERE {} {})",
@@ -90,7 +97,7 @@ impl ExistIn {
             self.inner_tablealias,
             col_writer.excape(&self.inner_column)
         );
-        let inner_tablename = TableWriter::new(syntax).write2(self.inner_tablename);
+        let inner_tablename = TableWriter::new(syntax).write(&self.inner_tablename);
         let tails = self.tails(syntax, &self.inner_tablealias);
         let mut wheres = "".to_string();
         if !inner_clauses.is_empty() {
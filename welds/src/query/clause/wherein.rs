@@ -2,8 +2,8 @@ use super::ClauseAdder;
 use crate::Syntax;
 use crate::model_traits::HasSchema;
 use crate::model_traits::TableColumns;
+use crate::model_traits::TableIdent;
 use crate::model_traits::TableInfo;
-use crate::model_traits::UniqueIdentifier;
 use crate::query::builder::QueryBuilder;
 use crate::query::clause::ParamArgs;
 use crate::query::helpers::{build_tail, build_where, join_sql_parts};
@@ -13,30 +13,38 @@ use crate::writers::TableWriter;
 
 /// Used to generated a SQL IN clause.
 /// This is used when deleting and updating to be able to apply limit
+///
+/// Models with a composite primary key match their rows with a row-value IN,
+/// MSSQL doesn't support row-values, it uses an EXISTS instead
 pub struct WhereIn<'qb, T> {
     qb: &'qb QueryBuilder<T>,
 }
 
+/// The alias of the sub-query used by the EXISTS of a composite primary key
+const EXISTS_ALIAS: &str = "welds_in";
+
 impl<'qb, T> WhereIn<'qb, T>
 where
     T: HasSchema,
-    <T as HasSchema>::Schema: UniqueIdentifier,
+    <T as HasSchema>::Schema: TableColumns,
 {
     pub(crate) fn new(qb: &'qb QueryBuilder<T>) -> Self {
         WhereIn { qb }
     }
 
-    fn outer_tablecolumn(&self, syntax: Syntax, outer_tablealias: &str) -> String {
-        let column = T::Schema::id_column();
+    fn outer_tablecolumns(&self, syntax: Syntax, outer_tablealias: &str) -> Vec<String> {
         let col_writer = ColumnWriter::new(syntax);
-        format!("{}.{}", outer_tablealias, col_writer.excape(column.name()))
+        T::Schema::primary_keys()
+            .iter()
+            .map(|c| format!("{}.{}", outer_tablealias, col_writer.excape(c.name())))
+            .collect()
     }
 }
 
 impl<T> ClauseAdder for WhereIn<'_, T>
 where
     T: HasSchema + Sync + Send,
-    <T as HasSchema>::Schema: UniqueIdentifier + TableInfo + TableColumns,
+    <T as HasSchema>::Schema: TableInfo + TableColumns,
 {
     fn bind<'lam, 'args, 'p>(&'lam self, args: &'args mut ParamArgs<'p>)
     where
@@ -52,12 +60,17 @@ where
 
     fn clause(&self, syntax: Syntax, alias: &str, next_params: &NextParam) -> Option<String> {
         // writes => ID IN ( SELECT ID FROM ... )
+        // or       (ID1, ID2) IN ( SELECT ID1, ID2 FROM ... )
 
-        let outcol = self.outer_tablecolumn(syntax, alias);
+        let outcols = self.outer_tablecolumns(syntax, alias);
         let inner_alias = &self.qb.alias;
         let mut args = None;
         let inner_sql = join_sql_parts(&[
-            build_head_select::<<T as HasSchema>::Schema>(syntax, inner_alias),
+            build_head_select::<<T as HasSchema>::Schema>(
+                syntax,
+                &self.qb.table_ident(),
+                inner_alias,
+            ),
             build_where(
                 syntax,
                 next_params,
@@ -69,21 +82,45 @@ where
             build_tail(syntax, self.qb),
         ]);
 
-        Some(format!(" {} IN ({}) ", outcol, inner_sql))
+        if outcols.len() == 1 {
+            return Some(format!(" {} IN ({}) ", outcols[0], inner_sql));
+        }
+        if syntax != Syntax::Mssql {
+            return Some(format!(" ({}) IN ({}) ", outcols.join(", "), inner_sql));
+        }
+
+        // writes => EXISTS ( SELECT 1 FROM ( SELECT ID1, ID2 FROM ... ) welds_in WHERE ... )
+        let col_writer = ColumnWriter::new(syntax);
+        let matches: Vec<String> = T::Schema::primary_keys()
+            .iter()
+            .zip(&outcols)
+            .map(|(c, out)| {
+                let inner = format!("{}.{}", EXISTS_ALIAS, col_writer.excape(c.name()));
+                format!("{} = {}", inner, out)
+            })
+            .collect();
+        Some(format!(
+            " EXISTS (SELECT 1 FROM ({}) {} WHERE {}) ",
+            inner_sql,
+            EXISTS_ALIAS,
+            matches.join(" AND ")
+        ))
     }
 }
 
-fn build_head_select<S>(syntax: Syntax, tablealias: &str) -> Option<String>
+fn build_head_select<S>(syntax: Syntax, table: &TableIdent, tablealias: &str) -> Option<String>
 where
-    S: TableInfo + UniqueIdentifier,
+    S: TableColumns,
 {
-    let mut tablename = TableWriter::new(syntax).write2(S::identifier());
+    let mut tablename = TableWriter::new(syntax).write(table);
 
     if tablename != tablealias {
         tablename = format!("{} {}", tablename, tablealias);
     }
     let writer = ColumnWriter::new(syntax);
-    let col_raw = S::id_column();
-    let col = writer.write(tablealias, &col_raw);
-    Some(format!("SELECT {} FROM {}", col, tablename))
+    let cols: Vec<String> = S::primary_keys()
+        .iter()
+        .map(|c| writer.write(tablealias, c))
+        .collect();
+    Some(format!("SELECT {} FROM {}", cols.join(", "), tablename))
 }
@@ -1,15 +1,19 @@
 use super::super::{
     builder::QueryBuilder,
     clause::{ClauseAdder, wherein::WhereIn},
-    helpers::{build_where, join_sql_parts},
+    helpers::{build_where, build_where_ids_in, join_sql_parts},
 };
-use crate::Syntax;
 use crate::errors::Result;
-use crate::model_traits::UniqueIdentifier;
-use crate::model_traits::{HasSchema, TableColumns, TableInfo};
+use crate::model_traits::hooks::{AfterBulkDelete, BeforeBulkDelete, BulkAffected};
+use crate::model_traits::{HasSchema, TableColumns, TableInfo, WriteToArgs};
 use crate::query::clause::ParamArgs;
+use crate::query::update::bulk::UpdateBuilder;
+use crate::state::DbState;
 use crate::writers::NextParam;
 use crate::writers::TableWriter;
+use crate::writers::{ReturningRow, ReturningWriter};
+use crate::{Row, Syntax, WeldsError};
+use std::sync::Arc;
 use welds_connections::Client;
 
 // ******************************************************************************************
@@ -23,15 +27,43 @@ where
     /// The SQL to delete a `DELETE FROM ... `
     ///
     /// return SQL to delete all the resulting rows from the database
+    ///
+    /// Soft deleted models `UPDATE` their `#[welds(soft_delete)]` column instead
     pub fn delete_sql(&self, syntax: Syntax) -> String
     where
-        <T as HasSchema>::Schema: TableInfo + TableColumns + UniqueIdentifier,
+        <T as HasSchema>::Schema: TableInfo + TableColumns,
+    {
+        match self.soft_delete_update() {
+            Some(update) => update.to_sql(syntax),
+            None => self.force_delete_sql(syntax),
+        }
+    }
+
+    /// The SQL to delete a `DELETE FROM ... `, even for soft deleted models
+    pub fn force_delete_sql(&self, syntax: Syntax) -> String
+    where
+        <T as HasSchema>::Schema: TableInfo + TableColumns,
     {
         // we are wrapping this query in a where in clause.
         // This is needed if the user has a limit
         let mut w_in = WhereIn::new(self);
 
-        self.delete_sql_internal(syntax, &mut w_in, &mut None)
+        self.delete_sql_internal(syntax, &mut w_in, &mut None, false)
+    }
+
+    /// The SQL to delete a `DELETE FROM ... ` returning the deleted rows
+    ///
+    /// NOTE: MySql doesn't support returning rows, this is the plain DELETE for MySql
+    pub fn delete_returning_sql(&self, syntax: Syntax) -> String
+    where
+        <T as HasSchema>::Schema: TableInfo + TableColumns,
+    {
+        if let Some(update) = self.soft_delete_update() {
+            return update.to_sql_returning(syntax);
+        }
+        let mut w_in = WhereIn::new(self);
+
+        self.delete_sql_internal(syntax, &mut w_in, &mut None, true)
     }
 
     fn delete_sql_internal<'s, 'w, 'args, 'p>(
@@ -39,53 +71,220 @@ where
         syntax: Syntax,
         w_in: &'w mut WhereIn<T>,
         args: &'args mut Option<ParamArgs<'p>>,
+        returning: bool,
     ) -> String
     where
         'w: 'p,
         's: 'p,
-        <T as HasSchema>::Schema: UniqueIdentifier + TableInfo + TableColumns,
+        <T as HasSchema>::Schema: TableInfo + TableColumns,
     {
         let next_params = NextParam::new(syntax);
 
         // Note: for deletes we can't alias the FROM tablename
-        let parts = <T as HasSchema>::Schema::identifier();
-        let alias = TableWriter::new(syntax).write2(parts);
+        let alias = TableWriter::new(syntax).write(&self.table_ident());
+
+        let columns = <T as HasSchema>::Schema::select_columns();
+        let ret_writer = ReturningWriter::new(syntax);
+        let (output, returning) = match returning {
+            true => (
+                ret_writer.output(ReturningRow::Deleted, &columns),
+                ret_writer.returning(&columns),
+            ),
+            false => (None, None),
+        };
 
         join_sql_parts(&[
-            build_head_delete::<<T as HasSchema>::Schema>(syntax),
+            build_head_delete(&alias),
+            output,
             build_where_delete(syntax, &next_params, &alias, args, self, w_in),
+            returning,
         ])
     }
 
     /// Executes a `DELETE FROM ... `
     ///
     /// deletes all the resulting rows from the database
+    ///
+    /// Soft deleted models `UPDATE` their `#[welds(soft_delete)]` column instead
+    ///
+    /// Calls the model's `BeforeBulkDelete` and `AfterBulkDelete` hooks
     #[maybe_async::maybe_async]
     pub async fn delete(&self, client: &dyn Client) -> Result<u64>
     where
-        <T as HasSchema>::Schema: UniqueIdentifier + TableInfo + TableColumns,
+        <T as HasSchema>::Schema: TableInfo + TableColumns,
+        T: BeforeBulkDelete + AfterBulkDelete,
+    {
+        <T as BeforeBulkDelete>::before(self).await?;
+        let count = match self.soft_delete_update() {
+            Some(update) => update.execute(client).await?,
+            None => self.execute_force_delete(client).await?,
+        };
+        <T as AfterBulkDelete>::after(self, &BulkAffected::Count(count)).await?;
+        Ok(count)
+    }
+
+    /// Executes a `DELETE FROM ... `, even for soft deleted models
+    ///
+    /// deletes all the resulting rows from the database
+    ///
+    /// Calls the model's `BeforeBulkDelete` and `AfterBulkDelete` hooks
+    #[maybe_async::maybe_async]
+    pub async fn force_delete(&self, client: &dyn Client) -> Result<u64>
+    where
+        <T as HasSchema>::Schema: TableInfo + TableColumns,
+        T: BeforeBulkDelete + AfterBulkDelete,
+    {
+        <T as BeforeBulkDelete>::before(self).await?;
+        let count = self.execute_force_delete(client).await?;
+        <T as AfterBulkDelete>::after(self, &BulkAffected::Count(count)).await?;
+        Ok(count)
+    }
+
+    /// Runs the `DELETE FROM ... ` without calling any hooks
+    #[maybe_async::maybe_async]
+    async fn execute_force_delete(&self, client: &dyn Client) -> Result<u64>
+    where
+        <T as HasSchema>::Schema: TableInfo + TableColumns,
     {
         // we are wrapping this query in a where in clause.
         // This is needed if the user has a limit
-        let w_in_q = self;
-        let mut w_in = WhereIn::new(w_in_q);
+        let w_in_q = self.for_client(client);
+        let mut w_in = WhereIn::new(&w_in_q);
 
         let syntax = client.syntax();
         let mut args: Option<ParamArgs> = Some(Vec::default());
-        let sql = self.delete_sql_internal(syntax, &mut w_in, &mut args);
+        let sql = w_in_q.delete_sql_internal(syntax, &mut w_in, &mut args, false);
         let args: ParamArgs = args.unwrap();
         let results = client.execute(&sql, &args).await?;
         Ok(results.rows_affected())
     }
-}
 
-fn build_head_delete<S>(syntax: Syntax) -> Option<String>
-where
-    S: TableInfo + TableColumns,
-{
-    let parts = S::identifier();
-    let identifier = TableWriter::new(syntax).write2(parts);
+    /// Executes a `DELETE FROM ... ` returning the rows that were deleted
+    ///
+    /// Postgres/Sqlite use `RETURNING`, MSSQL uses `OUTPUT DELETED.*`
+    ///
+    /// MySql can't return rows from a DELETE. The rows are selected `FOR UPDATE`
+    /// then deleted by their id, in a transaction unless the client is already a transaction.
+    ///
+    /// The returned rows are no longer in the database, saving them will re-create them.
+    /// Soft deleted models are still in the database, their rows are returned flagged as deleted
+    ///
+    /// Calls the model's `BeforeBulkDelete` and `AfterBulkDelete` hooks, the after hook gets the rows
+    #[maybe_async::maybe_async]
+    pub async fn delete_returning(&self, client: &dyn Client) -> Result<Vec<DbState<T>>>
+    where
+        <T as HasSchema>::Schema: TableInfo + TableColumns,
+        T: TryFrom<Row> + WriteToArgs + BeforeBulkDelete + AfterBulkDelete,
+        WeldsError: From<<T as TryFrom<Row>>::Error>,
+    {
+        <T as BeforeBulkDelete>::before(self).await?;
+        let rows = self.execute_delete_returning(client).await?;
+        <T as AfterBulkDelete>::after(self, &BulkAffected::Rows(&rows)).await?;
+        Ok(rows)
+    }
+
+    /// Runs the `DELETE FROM ... ` returning the rows without calling any hooks
+    #[maybe_async::maybe_async]
+    async fn execute_delete_returning(&self, client: &dyn Client) -> Result<Vec<DbState<T>>>
+    where
+        <T as HasSchema>::Schema: TableInfo + TableColumns,
+        T: TryFrom<Row> + WriteToArgs,
+        WeldsError: From<<T as TryFrom<Row>>::Error>,
+    {
+        if let Some(update) = self.soft_delete_update() {
+            return update.execute_returning(client).await;
+        }
+        let syntax = client.syntax();
+        if !ReturningWriter::new(syntax).supported() {
+            return self.delete_returning_fallback(client).await;
+        }
+
+        let qb = self.for_client(client);
+        let mut w_in = WhereIn::new(&qb);
+        let mut args: Option<ParamArgs> = Some(Vec::default());
+        let sql = qb.delete_sql_internal(syntax, &mut w_in, &mut args, true);
+        let args: ParamArgs = args.unwrap();
+        let rows = client.fetch_rows(&sql, &args).await?;
+
+        let mut objs = Vec::default();
+        for row in rows {
+            let obj: T = T::try_from(row)?;
+            objs.push(DbState::new_uncreated(obj).with_table(self.table.clone()));
+        }
+        Ok(objs)
+    }
+
+    /// Clears the soft delete flag on all the resulting rows. `#[welds(soft_delete)]`
+    ///
+    /// Soft deleted rows are included, there is no need to call `with_deleted()` first.
+    /// Models without a soft delete column are left alone.
+    #[maybe_async::maybe_async]
+    pub async fn restore(&self, client: &dyn Client) -> Result<u64>
+    where
+        <T as HasSchema>::Schema: TableInfo + TableColumns,
+    {
+        let col = match <T as HasSchema>::Schema::soft_delete_column() {
+            Some(col) => col,
+            None => return Ok(0),
+        };
+        let mut update = UpdateBuilder::new(self.clone().with_deleted());
+        update
+            .sets
+            .push(Arc::new(Box::new(col.restore_assignment())));
+        update.execute(client).await
+    }
+
+    /// The UPDATE used in place of a DELETE for soft deleted models
+    fn soft_delete_update(&self) -> Option<UpdateBuilder<T>> {
+        let col = <T as HasSchema>::Schema::soft_delete_column()?;
+        let mut update = UpdateBuilder::new(self.clone());
+        update
+            .sets
+            .push(Arc::new(Box::new(col.delete_assignment())));
+        Some(update)
+    }
+
+    /// select-then-delete for databases without RETURNING
+    #[maybe_async::maybe_async]
+    async fn delete_returning_fallback(&self, client: &dyn Client) -> Result<Vec<DbState<T>>>
+    where
+        <T as HasSchema>::Schema: TableInfo + TableColumns,
+        T: TryFrom<Row> + WriteToArgs,
+        WeldsError: From<<T as TryFrom<Row>>::Error>,
+    {
+        // the rows are locked until the transaction is committed
+        let trans = client.begin_if_needed().await?;
+        let conn: &dyn Client = match &trans {
+            Some(trans) => trans,
+            None => client,
+        };
+
+        let syntax = conn.syntax();
+        let found = self.run_for_update(conn).await?;
+        if found.is_empty() {
+            return Ok(Vec::default());
+        }
+
+        let table = self.table_ident().or_schema(conn.default_schema());
+        let alias = TableWriter::new(syntax).write(&table);
+        let next_params = NextParam::new(syntax);
+        let mut args: ParamArgs = Vec::default();
+        let wheres = build_where_ids_in(syntax, &next_params, &alias, &found, &mut args)?;
+        let sql = join_sql_parts(&[build_head_delete(&alias), wheres]);
+        conn.execute(&sql, &args).await?;
+        if let Some(trans) = trans {
+            trans.commit().await?;
+        }
+
+        let table = self.table.clone();
+        Ok(found
+            .into_iter()
+            .map(|obj| DbState::new_uncreated(obj).with_table(table.clone()))
+            .collect())
+    }
+}
 
+fn build_head_delete(identifier: &str) -> Option<String> {
     Some(format!("DELETE FROM {}", identifier))
 }
 
@@ -101,7 +300,7 @@ where
     'qb: 'p,
     'w: 'p,
     T: HasSchema,
-    <T as HasSchema>::Schema: UniqueIdentifier + TableInfo + TableColumns,
+    <T as HasSchema>::Schema: TableInfo + TableColumns,
 {
     // If we have a limit, we need to wrap the wheres in an IN clause to
     // we can limit the number of row to delete
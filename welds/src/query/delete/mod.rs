@@ -1,7 +1,9 @@
 use crate::errors::Result;
 use crate::errors::WeldsError;
 use crate::model_traits::hooks::{AfterDelete, BeforeDelete};
-use crate::model_traits::{HasSchema, TableColumns, TableInfo, WriteToArgs};
+use crate::model_traits::{
+    HasSchema, HasVersion, TableColumns, TableIdent, TableInfo, WriteToArgs,
+};
 use crate::query::clause::ParamArgs;
 use crate::writers::ColumnWriter;
 use crate::writers::NextParam;
@@ -9,11 +11,17 @@ use crate::writers::TableWriter;
 use welds_connections::Client;
 
 pub mod bulk;
+mod soft;
+pub use soft::{restore_one, soft_delete_one};
 
+/// Deletes the row of a model from the database
+///
+/// Versioned models are only deleted if their version hasn't moved since they were loaded,
+/// otherwise `WeldsError::StaleObject` is returned
 #[maybe_async::maybe_async]
-pub async fn delete_one<T>(obj: &T, client: &dyn Client) -> Result<()>
+pub async fn delete_one<T>(obj: &T, table: &TableIdent, client: &dyn Client) -> Result<()>
 where
-    T: HasSchema + WriteToArgs,
+    T: HasSchema + WriteToArgs + HasVersion,
     <T as HasSchema>::Schema: TableInfo + TableColumns,
     T: AfterDelete + BeforeDelete,
 {
@@ -22,8 +30,7 @@ where
     let col_writer = ColumnWriter::new(syntax);
     let next_params = NextParam::new(syntax);
 
-    let parts = <<T as HasSchema>::Schema>::identifier();
-    let identifier = TableWriter::new(syntax).write2(parts);
+    let identifier = TableWriter::new(syntax).write(table);
 
     let pks = <<T as HasSchema>::Schema as TableColumns>::primary_keys();
     if pks.is_empty() {
@@ -38,13 +45,24 @@ where
         let colname = col_writer.excape(col.name());
         wheres.push(format!("{}={}", colname, p));
     }
+    let version_col = T::version_column();
+    if let Some(col) = version_col {
+        obj.bind(col, &mut args)?;
+        let p = next_params.next();
+        let colname = col_writer.excape(col);
+        wheres.push(format!("{}={}", colname, p));
+    }
 
     let wheres = wheres.join(" AND ");
 
     let sql = format!("DELETE FROM {} where {}", identifier, wheres);
 
-    client.execute(&sql, &args).await?;
+    let result = client.execute(&sql, &args).await?;
+    if version_col.is_some() && result.rows_affected() == 0 {
+        return Err(WeldsError::StaleObject);
+    }
     AfterDelete::after(obj).await.ok();
+    AfterDelete::after_commit(obj, client);
 
     Ok(())
 }
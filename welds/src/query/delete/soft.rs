@@ -0,0 +1,141 @@
+use crate::errors::Result;
+use crate::errors::WeldsError;
+use crate::model_traits::hooks::{AfterDelete, BeforeDelete};
+use crate::model_traits::{
+    HasSchema, HasVersion, SoftDelete, TableColumns, TableIdent, TableInfo, WriteToArgs,
+};
+use crate::query::clause::ParamArgs;
+use crate::writers::ColumnWriter;
+use crate::writers::NextParam;
+use crate::writers::TableWriter;
+use welds_connections::Client;
+
+/// Flags the row of a model as deleted. `#[welds(soft_delete)]`
+///
+/// Models without a soft delete column are removed with `delete_one`
+#[maybe_async::maybe_async]
+pub async fn soft_delete_one<T>(obj: &mut T, table: &TableIdent, client: &dyn Client) -> Result<()>
+where
+    T: HasSchema + WriteToArgs + HasVersion + SoftDelete,
+    <T as HasSchema>::Schema: TableInfo + TableColumns,
+    T: AfterDelete + BeforeDelete,
+{
+    if <T as HasSchema>::Schema::soft_delete_column().is_none() {
+        return super::delete_one(obj, table, client).await;
+    }
+    BeforeDelete::before(obj).await?;
+    let old = obj.mark_deleted();
+    if let Err(err) = write_soft_delete_column(obj, table, client).await {
+        if let Some(old) = old {
+            obj.unmark(old);
+        }
+        return Err(err);
+    }
+    AfterDelete::after(obj).await.ok();
+    AfterDelete::after_commit(obj, client);
+    Ok(())
+}
+
+/// Clears the soft delete flag on the row of a model
+#[maybe_async::maybe_async]
+pub async fn restore_one<T>(obj: &mut T, table: &TableIdent, client: &dyn Client) -> Result<()>
+where
+    T: HasSchema + WriteToArgs + HasVersion + SoftDelete,
+    <T as HasSchema>::Schema: TableInfo + TableColumns,
+{
+    if <T as HasSchema>::Schema::soft_delete_column().is_none() {
+        return Ok(());
+    }
+    let old = obj.mark_restored();
+    if let Err(err) = write_soft_delete_column(obj, table, client).await {
+        if let Some(old) = old {
+            obj.unmark(old);
+        }
+        return Err(err);
+    }
+    Ok(())
+}
+
+/// Writes the model's soft delete column to its row
+///
+/// Versioned models only update the row if its version hasn't moved since it was loaded.
+/// The version is moved forward, or `WeldsError::StaleObject` is returned
+#[maybe_async::maybe_async]
+async fn write_soft_delete_column<T>(
+    obj: &mut T,
+    table: &TableIdent,
+    client: &dyn Client,
+) -> Result<()>
+where
+    T: HasSchema + WriteToArgs + HasVersion,
+    <T as HasSchema>::Schema: TableInfo + TableColumns,
+{
+    let col = match <T as HasSchema>::Schema::soft_delete_column() {
+        Some(col) => col.name(),
+        None => return Ok(()),
+    };
+    let syntax = client.syntax();
+    let col_writer = ColumnWriter::new(syntax);
+    let next_params = NextParam::new(syntax);
+
+    let identifier = TableWriter::new(syntax).write(table);
+
+    let pks = <<T as HasSchema>::Schema as TableColumns>::primary_keys();
+    if pks.is_empty() {
+        return Err(WeldsError::NoPrimaryKey);
+    }
+
+    let version_col = T::version_column();
+    let mut set_columns = vec![col];
+    set_columns.extend(version_col);
+
+    // Move the version forward, the old version is used to find the row
+    let old_version = obj.bump_version();
+
+    let mut args: ParamArgs = Vec::default();
+    let mut sets = Vec::default();
+    for col in set_columns {
+        obj.bind(col, &mut args)?;
+        let p = next_params.next();
+        let colname = col_writer.excape(col);
+        sets.push(format!("{}={}", colname, p));
+    }
+
+    let mut wheres = Vec::default();
+    for col in pks {
+        obj.bind(col.name(), &mut args)?;
+        let p = next_params.next();
+        let colname = col_writer.excape(col.name());
+        wheres.push(format!("{}={}", colname, p));
+    }
+    if let (Some(col), Some(old)) = (version_col, &old_version) {
+        args.push(old.as_param());
+        let p = next_params.next();
+        let colname = col_writer.excape(col);
+        wheres.push(format!("{}={}", colname, p));
+    }
+
+    let sql = format!(
+        "UPDATE {} SET {} where {}",
+        identifier,
+        sets.join(", "),
+        wheres.join(" AND ")
+    );
+
+    let result = client.execute(&sql, &args).await.map(|r| r.rows_affected());
+    if let Some(old) = old_version {
+        match result {
+            Ok(affected) if affected > 0 => {}
+            Ok(_) => {
+                obj.restore_version(old);
+                return Err(WeldsError::StaleObject);
+            }
+            Err(err) => {
+                obj.restore_version(old);
+                return Err(err.into());
+            }
+        }
+    }
+    result?;
+    Ok(())
+}
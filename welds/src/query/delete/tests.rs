@@ -15,6 +15,49 @@ struct Product {
     pub b: i32,
 }
 
+#[derive(Debug, Default, WeldsModel)]
+#[welds(table = "accounts")]
+#[welds_path(crate)] // needed only within the welds crate.
+struct Account {
+    #[welds(primary_key)]
+    pub id: i32,
+    #[welds(version)]
+    pub lock_version: i32,
+}
+
+#[derive(Debug, Default, WeldsModel)]
+#[welds(table = "customers")]
+#[welds_path(crate)] // needed only within the welds crate.
+struct Customer {
+    #[welds(primary_key)]
+    pub id: i32,
+    #[welds(soft_delete)]
+    pub deleted_at: Option<String>,
+}
+
+#[derive(Debug, Default, WeldsModel)]
+#[welds(table = "vendors")]
+#[welds_path(crate)] // needed only within the welds crate.
+struct Vendor {
+    #[welds(primary_key)]
+    pub id: i32,
+    #[welds(soft_delete)]
+    pub deleted_at: Option<String>,
+    #[welds(version)]
+    pub lock_version: i32,
+}
+
+#[derive(Debug, Default, WeldsModel)]
+#[welds(table = "order_lines")]
+#[welds_path(crate)] // needed only within the welds crate.
+struct OrderLine {
+    #[welds(primary_key)]
+    pub order_id: i32,
+    #[welds(primary_key)]
+    pub line_no: i32,
+    pub sku: String,
+}
+
 // Tests
 
 #[test]
@@ -30,6 +73,21 @@ fn should_be_able_to_delete_simple_object() {
     });
 }
 
+#[test]
+fn versioned_delete_should_check_the_version() {
+    futures::executor::block_on(async move {
+        let obj = Account::default();
+        let mut obj = DbState::db_loaded(obj);
+        // The noop client never affects any rows
+        let client = welds_connections::noop::build(Syntax::Mysql);
+        let result = obj.delete(&client).await;
+        let ran_sql = client.last_sql().unwrap();
+        let expected = "DELETE FROM accounts where id=? AND lock_version=?";
+        assert_eq!(expected, &ran_sql);
+        assert!(matches!(result, Err(crate::WeldsError::StaleObject)));
+    });
+}
+
 #[test]
 fn should_be_able_to_write_delete_query() {
     futures::executor::block_on(async move {
@@ -60,3 +118,175 @@ fn should_be_able_to_write_delete_query_with_limit() {
         assert_eq!(args_count, 1);
     });
 }
+
+#[test]
+fn should_be_able_to_write_delete_returning_query() {
+    futures::executor::block_on(async move {
+        use crate::query::builder::QueryBuilder;
+        let q = QueryBuilder::<Product>::new().where_col(|c| c.a.gt(1));
+        let client = welds_connections::noop::build(Syntax::Sqlite);
+        let _ = q.delete_returning(&client).await;
+        let ran_sql = client.last_sql().unwrap();
+        let expected = "DELETE FROM nums WHERE ( nums.\"a\" > ? ) RETURNING \"id\", \"a\", \"b\"";
+        assert_eq!(expected, &ran_sql);
+    });
+}
+
+#[test]
+fn should_be_able_to_write_delete_returning_query_mssql() {
+    use crate::query::builder::QueryBuilder;
+    let q = QueryBuilder::<Product>::new().where_col(|c| c.a.gt(1));
+    let sql = q.delete_returning_sql(Syntax::Mssql);
+    let expected = "DELETE FROM nums OUTPUT DELETED.\"id\", DELETED.\"a\", DELETED.\"b\" WHERE ( nums.\"a\" > @p1 )";
+    assert_eq!(expected, &sql);
+}
+
+#[test]
+fn soft_delete_should_flag_the_row() {
+    futures::executor::block_on(async move {
+        let obj = Customer::default();
+        let mut obj = DbState::db_loaded(obj);
+        let client = welds_connections::noop::build(Syntax::Postgres);
+        obj.delete(&client).await.unwrap();
+        let ran_sql = client.last_sql().unwrap();
+        let expected = "UPDATE customers SET \"deleted_at\"=$1 where \"id\"=$2";
+        assert_eq!(expected, &ran_sql);
+        assert_eq!(obj.deleted_at.as_deref(), Some("now"));
+    });
+}
+
+#[test]
+fn restore_should_clear_the_flag() {
+    futures::executor::block_on(async move {
+        let obj = Customer {
+            id: 1,
+            deleted_at: Some("yesterday".to_owned()),
+        };
+        let mut obj = DbState::db_loaded(obj);
+        let client = welds_connections::noop::build(Syntax::Postgres);
+        obj.restore(&client).await.unwrap();
+        let ran_sql = client.last_sql().unwrap();
+        let expected = "UPDATE customers SET \"deleted_at\"=$1 where \"id\"=$2";
+        assert_eq!(expected, &ran_sql);
+        assert_eq!(obj.deleted_at, None);
+    });
+}
+
+#[test]
+fn versioned_soft_delete_should_check_and_bump_the_version() {
+    futures::executor::block_on(async move {
+        let mut obj = DbState::db_loaded(Vendor::default());
+        // The noop client never affects any rows
+        let client = welds_connections::noop::build(Syntax::Postgres);
+        let result = obj.delete(&client).await;
+        let ran_sql = client.last_sql().unwrap();
+        let expected = "UPDATE vendors SET \"deleted_at\"=$1, \"lock_version\"=$2 where \"id\"=$3 AND \"lock_version\"=$4";
+        assert_eq!(expected, &ran_sql);
+        assert!(matches!(result, Err(crate::WeldsError::StaleObject)));
+        // the failed delete is undone on the model
+        assert_eq!(obj.deleted_at, None);
+        assert_eq!(obj.lock_version, 0);
+    });
+}
+
+#[test]
+fn versioned_restore_should_undo_the_flag_when_stale() {
+    futures::executor::block_on(async move {
+        let obj = Vendor {
+            id: 1,
+            deleted_at: Some("yesterday".to_owned()),
+            lock_version: 3,
+        };
+        let mut obj = DbState::db_loaded(obj);
+        let client = welds_connections::noop::build(Syntax::Postgres);
+        let result = obj.restore(&client).await;
+        assert!(matches!(result, Err(crate::WeldsError::StaleObject)));
+        assert_eq!(obj.deleted_at.as_deref(), Some("yesterday"));
+        assert_eq!(obj.lock_version, 3);
+    });
+}
+
+#[test]
+fn force_delete_should_remove_a_soft_deleted_row() {
+    futures::executor::block_on(async move {
+        let obj = Customer::default();
+        let mut obj = DbState::db_loaded(obj);
+        let client = welds_connections::noop::build(Syntax::Postgres);
+        obj.force_delete(&client).await.unwrap();
+        let ran_sql = client.last_sql().unwrap();
+        let expected = "DELETE FROM customers where \"id\"=$1";
+        assert_eq!(expected, &ran_sql);
+    });
+}
+
+#[test]
+fn bulk_soft_delete_should_write_an_update() {
+    use crate::query::builder::QueryBuilder;
+    let q = QueryBuilder::<Customer>::new().where_col(|c| c.id.gt(1));
+    let sql = q.delete_sql(Syntax::Postgres);
+    let expected = "UPDATE customers SET \"deleted_at\" = ( $1 ) WHERE ( customers.\"deleted_at\" IS NULL AND customers.\"id\" > $2 )";
+    assert_eq!(expected, &sql);
+    let sql = q.force_delete_sql(Syntax::Postgres);
+    let expected = "DELETE FROM customers WHERE ( customers.\"deleted_at\" IS NULL AND customers.\"id\" > $1 )";
+    assert_eq!(expected, &sql);
+}
+
+#[test]
+fn bulk_restore_should_include_deleted_rows() {
+    futures::executor::block_on(async move {
+        use crate::query::builder::QueryBuilder;
+        let q = QueryBuilder::<Customer>::new().where_col(|c| c.id.gt(1));
+        let client = welds_connections::noop::build(Syntax::Postgres);
+        q.restore(&client).await.unwrap();
+        let ran_sql = client.last_sql().unwrap();
+        let expected =
+            "UPDATE customers SET \"deleted_at\" = ( NULL ) WHERE ( customers.\"id\" > $1 )";
+        assert_eq!(expected, &ran_sql);
+    });
+}
+
+#[test]
+fn should_delete_by_a_composite_key() {
+    futures::executor::block_on(async move {
+        let mut obj = DbState::db_loaded(OrderLine::default());
+        let client = welds_connections::noop::build(Syntax::Mysql);
+        let _ = obj.delete(&client).await;
+        let ran_sql = client.last_sql().unwrap();
+        let expected = "DELETE FROM order_lines where order_id=? AND line_no=?";
+        assert_eq!(expected, &ran_sql);
+    });
+}
+
+#[test]
+fn should_find_by_a_composite_key() {
+    futures::executor::block_on(async move {
+        let client = welds_connections::noop::build(Syntax::Postgres);
+        let _ = OrderLine::find_by_id(&client, (4, 2)).await;
+        let ran_sql = client.last_sql().unwrap();
+        let expected = "SELECT t1.\"order_id\", t1.\"line_no\", t1.\"sku\" FROM order_lines t1 WHERE ( t1.\"order_id\" = $1 AND t1.\"line_no\" = $2 ) ORDER BY 1 OFFSET 0 LIMIT 1";
+        assert_eq!(expected, &ran_sql);
+        assert_eq!(client.args_count().unwrap(), 2);
+    });
+}
+
+#[test]
+fn delete_with_limit_should_match_a_composite_key_with_a_row_value() {
+    use crate::query::builder::QueryBuilder;
+    let q = QueryBuilder::<OrderLine>::new()
+        .where_col(|c| c.sku.equal("old"))
+        .limit(10);
+    let sql = q.delete_sql(Syntax::Postgres);
+    let expected = "DELETE FROM order_lines WHERE (  (order_lines.\"order_id\", order_lines.\"line_no\") IN (SELECT t1.\"order_id\", t1.\"line_no\" FROM order_lines t1 WHERE ( t1.\"sku\" = $1 ) ORDER BY 1 OFFSET 0 LIMIT 10)  )";
+    assert_eq!(expected, &sql);
+}
+
+#[test]
+fn delete_with_limit_should_match_a_composite_key_with_exists_in_mssql() {
+    use crate::query::builder::QueryBuilder;
+    let q = QueryBuilder::<OrderLine>::new()
+        .where_col(|c| c.sku.equal("old"))
+        .limit(10);
+    let sql = q.delete_sql(Syntax::Mssql);
+    let expected = "DELETE FROM order_lines WHERE (  EXISTS (SELECT 1 FROM (SELECT t1.\"order_id\", t1.\"line_no\" FROM order_lines t1 WHERE ( t1.\"sku\" = @p1 ) ORDER BY 1 OFFSET 0 ROWS FETCH FIRST 10 ROWS ONLY) welds_in WHERE welds_in.\"order_id\" = order_lines.\"order_id\" AND welds_in.\"line_no\" = order_lines.\"line_no\")  )";
+    assert_eq!(expected, &sql);
+}
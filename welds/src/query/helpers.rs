@@ -1,10 +1,14 @@
 use super::builder::QueryBuilder;
 use super::clause::ParamArgs;
 use crate::Syntax;
-use crate::model_traits::HasSchema;
+use crate::errors::Result;
+use crate::model_traits::WriteToArgs;
+use crate::model_traits::{Column, HasSchema, TableColumns, TableIdent, TableInfo};
 use crate::query::clause::ClauseAdder;
 use crate::query::clause::exists::ExistIn;
+use crate::writers::ColumnWriter;
 use crate::writers::NextParam;
+use crate::writers::TableWriter;
 use std::sync::Arc;
 
 pub(crate) fn join_sql_parts(parts: &[Option<String>]) -> String {
@@ -80,3 +84,103 @@ where
         &select.alias,
     )
 }
+
+/// Writes a `WHERE ( id IN (...) )` that matches the rows of the given objects.
+/// Used when rows are selected first, then acted on (no RETURNING support)
+///
+/// Composite primary keys are matched with `WHERE ( (a=? AND b=?) OR ... )`
+pub(crate) fn build_where_ids_in<'o, 'p, T>(
+    syntax: Syntax,
+    next_params: &NextParam,
+    alias: &str,
+    objs: &'o [T],
+    args: &mut ParamArgs<'p>,
+) -> Result<Option<String>>
+where
+    'o: 'p,
+    T: HasSchema + WriteToArgs,
+    <T as HasSchema>::Schema: TableColumns,
+{
+    if objs.is_empty() {
+        return Ok(None);
+    }
+    let writer = ColumnWriter::new(syntax);
+    let pks = <T as HasSchema>::Schema::primary_keys();
+    if let [id_col] = pks.as_slice() {
+        let mut params = Vec::default();
+        for obj in objs {
+            obj.bind(id_col.name(), args)?;
+            params.push(next_params.next());
+        }
+        let col = writer.write(alias, id_col);
+        return Ok(Some(format!(
+            "WHERE ( {} IN ({}) )",
+            col,
+            params.join(", ")
+        )));
+    }
+
+    let mut rows = Vec::default();
+    for obj in objs {
+        let mut matches = Vec::default();
+        for col in &pks {
+            obj.bind(col.name(), args)?;
+            matches.push(format!(
+                "{}={}",
+                writer.write(alias, col),
+                next_params.next()
+            ));
+        }
+        rows.push(format!("({})", matches.join(" AND ")));
+    }
+    Ok(Some(format!("WHERE ( {} )", rows.join(" OR "))))
+}
+
+/// Writes a SELECT of the given columns from the row of a single model, found by its primary keys
+pub(crate) fn build_select_by_pks<'o, 'p, T>(
+    syntax: Syntax,
+    obj: &'o T,
+    table: &TableIdent,
+    columns: &[Column],
+    args: &mut ParamArgs<'p>,
+) -> Result<String>
+where
+    'o: 'p,
+    T: HasSchema + WriteToArgs,
+    <T as HasSchema>::Schema: TableInfo + TableColumns,
+{
+    let col_writer = ColumnWriter::new(syntax);
+    let next_params = NextParam::new(syntax);
+    let identifier = TableWriter::new(syntax).write(table);
+
+    let cols: Vec<String> = columns
+        .iter()
+        .map(|c| col_writer.excape(c.name()))
+        .collect();
+
+    let mut wheres = Vec::default();
+    for col in <<T as HasSchema>::Schema as TableColumns>::primary_keys() {
+        obj.bind(col.name(), args)?;
+        let colname = col_writer.excape(col.name());
+        wheres.push(format!("{}={}", colname, next_params.next()));
+    }
+
+    Ok(format!(
+        "SELECT {} FROM {} where {}",
+        cols.join(", "),
+        identifier,
+        wheres.join(" AND ")
+    ))
+}
+
+/// The columns of a model the database writes to, `#[welds(db_default)]` and `#[welds(generated)]`
+pub(crate) fn db_managed_columns<S>() -> Vec<Column>
+where
+    S: TableInfo + TableColumns,
+{
+    let managed = S::db_managed_columns();
+    S::select_columns()
+        .into_iter()
+        .filter(|c| managed.contains(&c.name()))
+        .collect()
+}
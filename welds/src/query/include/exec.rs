@@ -11,7 +11,7 @@ use crate::query::include::IncludeBuilder;
 // This file contains all the stuff added onto the IncludeBuilder to allow it to run Execute the Query
 // ******************************************************************************************
 
-impl<T> IncludeBuilder<T>
+impl<T, I> IncludeBuilder<T, I>
 where
     T: Send + HasSchema,
 {
@@ -25,14 +25,14 @@ where
     {
         let mut sqls: Vec<_> = vec![self.qb.to_sql(syntax)];
         for related_query in &self.related {
-            sqls.push(related_query.to_sql(&self.qb, syntax));
+            sqls.extend(related_query.to_sql(&self.qb, syntax));
         }
         sqls
     }
 
     /// Executes the query in the database returning the results
     #[maybe_async::maybe_async]
-    pub async fn run<'q, 'c>(&'q self, client: &'c dyn Client) -> Result<DataSet<T>>
+    pub async fn run<'q, 'c>(&'q self, client: &'c dyn Client) -> Result<DataSet<T, I>>
     where
         'q: 'c,
         <T as HasSchema>::Schema: TableInfo + TableColumns,
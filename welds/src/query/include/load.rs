@@ -0,0 +1,170 @@
+use super::related_query::RelatedSet;
+use crate::Client;
+use crate::Syntax;
+use crate::connections::Row;
+use crate::dataset::LoadedSet;
+use crate::errors::{Result, WeldsError};
+use crate::model_traits::{HasSchema, TableColumns, TableInfo, WriteToArgs};
+use crate::query::builder::QueryBuilder;
+use crate::query::clause::ParamArgs;
+use crate::query::helpers::{build_where_clauses, join_sql_parts};
+use crate::query::select::SelectWriter;
+use crate::query::tail;
+use crate::relations::{DiscriminatorSide, HasRelations, Relationship};
+use crate::state::DbState;
+use crate::writers::{ColumnWriter, NextParam};
+use std::collections::HashSet;
+
+/// Loads the models related to rows that have already been selected.
+///
+/// Selects the related models with `IN (...)` lists of the rows' keys,
+/// split into as many queries as needed to fit in the database's parameter limit.
+/// The original query is NOT ran again.
+/// ```rust,ignore
+/// let customers = Customer::all().run(db).await?;
+/// let active: Vec<_> = customers.into_iter().filter(|c| c.active).collect();
+///
+/// let loaded = load_related(db, &active, |c| c.orders).await?;
+/// for customer in loaded.iter() {
+///     let orders = customer.get(|c| c.orders);
+/// }
+/// ```
+#[maybe_async::maybe_async]
+pub async fn load_related<'t, T, R, Ship>(
+    client: &dyn Client,
+    rows: &'t [DbState<T>],
+    relationship: impl Fn(<T as HasRelations>::Relation) -> Ship,
+) -> Result<LoadedSet<'t, T>>
+where
+    T: 'static + Send + Sync + HasSchema + HasRelations + WriteToArgs,
+    <T as HasSchema>::Schema: TableInfo + TableColumns,
+    Ship: 'static + Relationship<T, R>,
+    R: 'static + Send + Sync + HasSchema,
+    <R as HasSchema>::Schema: TableInfo + TableColumns,
+    R: TryFrom<Row>,
+    WeldsError: From<<R as TryFrom<Row>>::Error>,
+{
+    let ship = relationship(Default::default());
+    let models: Vec<&T> = rows.iter().map(|x| x.as_ref()).collect();
+
+    // many-to-many rows are found through the join table,
+    // the links are selected along with them to match the rows up
+    let (data, links) = match ship.join_table() {
+        Some(join_table) => {
+            let (data, links) = join_table
+                .load(&models, &QueryBuilder::new(), client)
+                .await?;
+            (data, Some(links))
+        }
+        None => {
+            // rows of the wrong type are skipped when matched up, see `RelationshipCompare`
+            let mut query = QueryBuilder::new();
+            let related_side = ship
+                .discriminator()
+                .filter(|d| d.side == DiscriminatorSide::Related);
+            if let Some(d) = related_side {
+                d.filter(&mut query);
+            }
+            let their_key = ship.their_key();
+            let my_key = ship.my_key();
+            let data = select_in(client, &query, &their_key, &models, &my_key).await?;
+            (data, None)
+        }
+    };
+
+    let related = RelatedSet::<T, R, Ship>::new(data, ship, links, Vec::default());
+    Ok(LoadedSet::new(rows, vec![Box::new(related)]))
+}
+
+/// Selects the rows of `query` with `column` in the `value_column` of each of `values`.
+/// Each distinct value is bound once. The filters and order of `query` are kept
+#[maybe_async::maybe_async]
+pub(crate) async fn select_in<R, V>(
+    client: &dyn Client,
+    query: &QueryBuilder<R>,
+    column: &str,
+    values: &[&V],
+    value_column: &str,
+) -> Result<Vec<R>>
+where
+    R: Send + HasSchema,
+    <R as HasSchema>::Schema: TableInfo + TableColumns,
+    R: TryFrom<Row>,
+    WeldsError: From<<R as TryFrom<Row>>::Error>,
+    V: WriteToArgs,
+{
+    let syntax = client.syntax();
+    let query = query.for_client(client);
+
+    // rows sharing a key (many rows belonging to the same parent) are only bound once
+    let mut seen = HashSet::new();
+    let values: Vec<&V> = values
+        .iter()
+        .copied()
+        .filter(|v| match v.column_key(value_column) {
+            Some(key) => seen.insert(key),
+            None => true,
+        })
+        .collect();
+
+    // leave room for the params of the query's filters
+    let mut filter_args: Option<ParamArgs> = Some(Vec::default());
+    keyed_sql(syntax, &query, column, 0, &mut filter_args);
+    let filter_params = filter_args.map(|a| a.len()).unwrap_or_default();
+    let max_params = NextParam::new(syntax).max_params() as usize;
+    let chunk_size = max_params.saturating_sub(filter_params).max(1);
+
+    let mut found = Vec::default();
+    for chunk in values.chunks(chunk_size) {
+        let mut keys: ParamArgs = Vec::with_capacity(chunk.len());
+        for value in chunk {
+            value.bind(value_column, &mut keys)?;
+        }
+        let mut args = Some(keys);
+        let sql = keyed_sql(syntax, &query, column, chunk.len(), &mut args);
+        let args = args.unwrap_or_default();
+
+        let rows = client.fetch_rows(&sql, &args).await?;
+        for row in rows {
+            found.push(R::try_from(row)?);
+        }
+    }
+    Ok(found)
+}
+
+/// The SELECT of `select_in`, with `keys` params in its `IN (...)` list.
+/// The query's own params are added to `args` after the keys
+pub(crate) fn keyed_sql<'q, 'p, R>(
+    syntax: Syntax,
+    query: &'q QueryBuilder<R>,
+    column: &str,
+    keys: usize,
+    args: &mut Option<ParamArgs<'p>>,
+) -> String
+where
+    'q: 'p,
+    R: Send + HasSchema,
+    <R as HasSchema>::Schema: TableInfo + TableColumns,
+{
+    let alias = &query.alias;
+    let table = query.table_ident();
+    let columns = <R as HasSchema>::Schema::select_columns();
+    let writer = SelectWriter::new_with_alias(syntax, &table, alias);
+    let head = writer.sql(&columns, &[], &[], &None, &None, &[], &mut None);
+
+    let next_param = NextParam::new(syntax);
+    let params: Vec<String> = (0..keys).map(|_| next_param.next()).collect();
+    let column = ColumnWriter::new(syntax).excape(column);
+    let mut clauses = vec![format!("{alias}.{column} IN ({})", params.join(", "))];
+    clauses.extend(build_where_clauses(
+        syntax,
+        &next_param,
+        alias,
+        &query.wheres,
+        args,
+        &query.exist_ins,
+    ));
+    let wheres = format!("WHERE ( {} )", clauses.join(" AND "));
+    let tail = tail::write(syntax, &query.limit, &query.offset, &query.orderby, alias);
+    join_sql_parts(&[Some(head), Some(wheres), tail])
+}
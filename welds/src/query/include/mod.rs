@@ -1,22 +1,33 @@
 use crate::connections::Row;
-use crate::errors::WeldsError;
-use crate::model_traits::{HasSchema, TableColumns, TableInfo, UniqueIdentifier};
+use crate::errors::{Result, WeldsError};
+use crate::model_traits::{HasSchema, TableColumns, TableInfo, WriteToArgs};
 use crate::query::builder::QueryBuilder;
 use crate::query::clause::{AsFieldName, ClauseAdder};
 use crate::relations::{HasRelations, Relationship};
+use std::marker::PhantomData;
 
 mod exec;
+mod load;
+pub use load::load_related;
+pub(crate) use load::{keyed_sql, select_in};
 pub(crate) mod related_query;
 use related_query::{IncludeQuery, RelatedQuery};
 #[cfg(test)]
 mod tests;
 
 /// An un-executed Query Selecting a model AND its relationship objects.
-pub struct IncludeBuilder<T> {
+///
+/// `I` lists the included models, one `Included` per include.
+/// Serializing the `DataSet` only requires these models to be `serde::Serialize`
+pub struct IncludeBuilder<T, I = ()> {
     qb: QueryBuilder<T>,
     related: Vec<Box<dyn RelatedQuery<T> + Sync + Send>>,
+    included: PhantomData<fn() -> I>,
 }
 
+/// A model included in a query, and the models included along with it
+pub struct Included<R, Nested = ()>(PhantomData<fn() -> (R, Nested)>);
+
 impl<T> IncludeBuilder<T>
 where
     T: Send + HasSchema,
@@ -25,6 +36,23 @@ where
         Self {
             qb,
             related: Vec::default(),
+            included: PhantomData,
+        }
+    }
+}
+
+impl<T, I> IncludeBuilder<T, I>
+where
+    T: Send + HasSchema,
+{
+    // moves the includes over to a builder that lists one more included model
+    fn push<J>(self, query: Box<dyn RelatedQuery<T> + Sync + Send>) -> IncludeBuilder<T, J> {
+        let mut related = self.related;
+        related.push(query);
+        IncludeBuilder {
+            qb: self.qb,
+            related,
+            included: PhantomData,
         }
     }
 
@@ -32,17 +60,17 @@ where
     /// querying will continue over your current Object, but the related object will be
     /// accessible in the resulting dataset off of each instance of your model
     pub fn include<R, Ship>(
-        mut self,
+        self,
         relationship: impl Fn(<T as HasRelations>::Relation) -> Ship,
-    ) -> IncludeBuilder<T>
+    ) -> IncludeBuilder<T, (I, Included<R>)>
     where
-        T: 'static + HasRelations,
+        T: 'static + Sync + HasRelations + WriteToArgs,
         Ship: 'static + Sync + Relationship<T, R>,
         R: HasSchema,
         R: 'static,
         R: Send + Sync + HasSchema,
-        <R as HasSchema>::Schema: TableInfo + TableColumns + UniqueIdentifier,
-        <T as HasSchema>::Schema: TableInfo + TableColumns + UniqueIdentifier,
+        <R as HasSchema>::Schema: TableInfo + TableColumns,
+        <T as HasSchema>::Schema: TableInfo + TableColumns,
         <T as HasRelations>::Relation: Default,
         R: TryFrom<Row>,
         WeldsError: From<<R as TryFrom<Row>>::Error>,
@@ -50,21 +78,19 @@ where
         // capture how to run this query as a sub-query in on the related table
         let ship = relationship(Default::default());
         let out_col = ship.their_key();
-        let inner_tn = <T as HasSchema>::Schema::identifier();
         let inner_col = ship.my_key();
 
         let include_query: IncludeQuery<T, R, Ship> = IncludeQuery::<T, R, Ship> {
             _t: Default::default(),
             row_type: Default::default(),
             out_col,
-            inner_tn,
             inner_col,
             ship: ship.clone(),
             qb: QueryBuilder::new(),
+            nested: Vec::default(),
         };
 
-        self.related.push(Box::new(include_query));
-        self
+        self.push(Box::new(include_query))
     }
 
     /// Include models related to this model in the returned data. `BelongsTo` `HasMany`.
@@ -73,39 +99,92 @@ where
     ///
     /// This is identical the `include` but allows for a filter to be applied to the included data
     pub fn include_where<R, Ship>(
-        mut self,
+        self,
         relationship: impl Fn(<T as HasRelations>::Relation) -> Ship,
         qb: QueryBuilder<R>,
-    ) -> IncludeBuilder<T>
+    ) -> IncludeBuilder<T, (I, Included<R>)>
     where
-        T: 'static + HasRelations,
+        T: 'static + Sync + HasRelations + WriteToArgs,
         Ship: 'static + Sync + Relationship<T, R>,
         R: HasSchema,
         R: 'static,
         R: Send + Sync + HasSchema,
-        <R as HasSchema>::Schema: TableInfo + TableColumns + UniqueIdentifier,
-        <T as HasSchema>::Schema: TableInfo + TableColumns + UniqueIdentifier,
+        <R as HasSchema>::Schema: TableInfo + TableColumns,
+        <T as HasSchema>::Schema: TableInfo + TableColumns,
         <T as HasRelations>::Relation: Default,
         R: TryFrom<Row>,
         WeldsError: From<<R as TryFrom<Row>>::Error>,
     {
         let ship = relationship(Default::default());
         let out_col = ship.their_key();
-        let inner_tn = <T as HasSchema>::Schema::identifier();
         let inner_col = ship.my_key();
 
         let include_query: IncludeQuery<T, R, Ship> = IncludeQuery::<T, R, Ship> {
             _t: Default::default(),
             row_type: Default::default(),
             out_col,
-            inner_tn,
             inner_col,
             ship: ship.clone(),
             qb,
+            nested: Vec::default(),
         };
 
-        self.related.push(Box::new(include_query));
-        self
+        self.push(Box::new(include_query))
+    }
+
+    /// Include models related to this model, and models related to those models.
+    ///
+    /// The second closure is given an `IncludeBuilder` for the related model.
+    /// Includes and filters added to it are applied to the related rows.
+    /// One query is ran per level. Each level below the first is selected
+    /// with `IN (...)` lists of the keys of the rows selected by the level above it.
+    /// ```rust,ignore
+    /// let dataset = Customer::all()
+    ///     .then_include(|c| c.orders, |orders| {
+    ///         orders.then_include(|o| o.line_items, |lines| lines.include(|l| l.product))
+    ///     })
+    ///     .run(db)
+    ///     .await?;
+    ///
+    /// for customer in dataset.iter() {
+    ///     for order in customer.get_nested(|c| c.orders) {
+    ///         let lines = order.get_nested(|o| o.line_items);
+    ///     }
+    /// }
+    /// ```
+    pub fn then_include<R, Ship, N>(
+        self,
+        relationship: impl Fn(<T as HasRelations>::Relation) -> Ship,
+        nested: impl FnOnce(IncludeBuilder<R>) -> IncludeBuilder<R, N>,
+    ) -> IncludeBuilder<T, (I, Included<R, N>)>
+    where
+        T: 'static + Sync + HasRelations + WriteToArgs,
+        Ship: 'static + Sync + Relationship<T, R>,
+        R: HasSchema,
+        R: 'static,
+        R: Send + Sync + HasSchema,
+        <R as HasSchema>::Schema: TableInfo + TableColumns,
+        <T as HasSchema>::Schema: TableInfo + TableColumns,
+        <T as HasRelations>::Relation: Default,
+        R: TryFrom<Row>,
+        WeldsError: From<<R as TryFrom<Row>>::Error>,
+    {
+        let ship = relationship(Default::default());
+        let out_col = ship.their_key();
+        let inner_col = ship.my_key();
+        let nested = nested(IncludeBuilder::new(QueryBuilder::new()));
+
+        let include_query: IncludeQuery<T, R, Ship> = IncludeQuery::<T, R, Ship> {
+            _t: Default::default(),
+            row_type: Default::default(),
+            out_col,
+            inner_col,
+            ship: ship.clone(),
+            qb: nested.qb,
+            nested: nested.related,
+        };
+
+        self.push(Box::new(include_query))
     }
 
     /// Filter the results returned by this query.
@@ -121,6 +200,13 @@ where
         self
     }
 
+    /// Run this query on another table with the same columns as the model.
+    /// See `QueryBuilder::in_table`
+    pub fn in_table(mut self, table: impl AsRef<str>) -> Result<Self> {
+        self.qb = self.qb.in_table(table)?;
+        Ok(self)
+    }
+
     /// Limit the number of rows returned by this query
     pub fn limit(mut self, x: i64) -> Self {
         self.qb = self.qb.limit(x);
@@ -3,10 +3,13 @@ use crate::connections::Row;
 use crate::errors::Result;
 use crate::errors::WeldsError;
 use crate::exts::VecStateExt;
-use crate::model_traits::{HasSchema, TableColumns, TableInfo, UniqueIdentifier};
+use crate::model_traits::{HasSchema, TableColumns, TableInfo, WriteToArgs};
 use crate::query::builder::QueryBuilder;
 use crate::query::clause::exists::ExistIn;
-use crate::relations::Relationship;
+use crate::query::include::load::{keyed_sql, select_in};
+#[cfg(feature = "serde")]
+use crate::relations::describe::CompareFn;
+use crate::relations::{DiscriminatorSide, Linked, Relationship, RelationshipCompare};
 use async_trait::async_trait;
 use std::any::Any;
 use std::marker::PhantomData;
@@ -19,7 +22,18 @@ pub(crate) trait RelatedQuery<R> {
         primary_query: &QueryBuilder<R>,
         client: &dyn Client,
     ) -> Result<Box<dyn RelatedSetAccesser + Send>>;
-    fn to_sql(&self, primary_query: &QueryBuilder<R>, syntax: crate::Syntax) -> String;
+    /// The SQL of this query, followed by the SQL of any nested includes
+    fn to_sql(&self, primary_query: &QueryBuilder<R>, syntax: crate::Syntax) -> Vec<String>;
+
+    /// Selects the rows related to `rows` with `IN (...)` lists of their keys.
+    /// Used by nested includes, the rows of the level above are already selected
+    async fn run_keyed(
+        &self,
+        rows: &[&R],
+        client: &dyn Client,
+    ) -> Result<Box<dyn RelatedSetAccesser + Send>>;
+    /// The SQL of `run_keyed`, keyed on a single row
+    fn to_sql_keyed(&self, syntax: crate::Syntax) -> Vec<String>;
 }
 
 pub(crate) struct IncludeQuery<T, R, Ship>
@@ -30,10 +44,11 @@ where
     pub(crate) _t: PhantomData<T>,
     pub(crate) row_type: std::marker::PhantomData<R>,
     pub(crate) out_col: String,
-    pub(crate) inner_tn: &'static [&'static str],
     pub(crate) inner_col: String,
     pub(crate) ship: Ship,
     pub(crate) qb: QueryBuilder<R>,
+    // The includes of the included model, ran against this query
+    pub(crate) nested: Vec<Box<dyn RelatedQuery<R> + Sync + Send>>,
 }
 
 #[maybe_async::maybe_async]
@@ -43,9 +58,9 @@ where
     for<'r> &'r IncludeQuery<T, R, Ship>: Send,
     for<'b> &'b QueryBuilder<T>: Send,
     Ship: 'static + Relationship<T, R>,
-    T: 'static + Send,
+    T: 'static + Send + Sync + HasSchema + WriteToArgs,
     R: 'static,
-    <R as HasSchema>::Schema: TableInfo + TableColumns + UniqueIdentifier,
+    <R as HasSchema>::Schema: TableInfo + TableColumns,
     R: Send + Sync + HasSchema,
     R: TryFrom<Row>,
     WeldsError: From<<R as TryFrom<Row>>::Error>,
@@ -55,40 +70,144 @@ where
         primary_query: &QueryBuilder<T>,
         client: &dyn Client,
     ) -> Result<Box<dyn RelatedSetAccesser + Send>> {
-        let primary_query = primary_query.clone();
+        let mut primary_query = primary_query.clone();
 
         let mut qb: QueryBuilder<R> = self.qb.clone();
         qb.set_aliases(&primary_query.alias_asigner);
+        filter_discriminator(&self.ship, &mut primary_query, &mut qb);
 
-        let exist_in = ExistIn::new(
-            &primary_query,
-            self.out_col.clone(),
-            self.inner_tn,
-            self.inner_col.clone(),
-        );
-        qb.exist_ins.push(exist_in);
+        // many-to-many rows are found through the join table,
+        // the links are selected along with them to match the rows up
+        let links = match self.ship.join_table() {
+            Some(join_table) => {
+                join_table.filter_linked(&primary_query, &mut qb);
+                Some(join_table.links(&primary_query, client).await?)
+            }
+            None => {
+                let exist_in =
+                    ExistIn::new(&primary_query, self.out_col.clone(), self.inner_col.clone());
+                qb.exist_ins.push(exist_in);
+                None
+            }
+        };
 
-        let rows = qb.run(client).await?;
+        let rows = qb.run(client).await?.into_inners();
 
-        Ok(Box::new(RelatedSet::<T, R, Ship> {
-            _t: Default::default(),
-            data: rows.into_inners(),
-            ship: self.ship.clone(),
-        }))
+        // each level down is keyed on the rows of this level
+        let refs: Vec<&R> = rows.iter().collect();
+        let mut nested = Vec::default();
+        for nested_query in &self.nested {
+            nested.push(nested_query.run_keyed(&refs, client).await?);
+        }
+
+        Ok(Box::new(RelatedSet::<T, R, Ship>::new(
+            rows,
+            self.ship.clone(),
+            links,
+            nested,
+        )))
     }
 
-    fn to_sql(&self, primary_query: &QueryBuilder<T>, syntax: crate::Syntax) -> String {
-        let primary_query = primary_query.clone();
-        let mut qb: QueryBuilder<R> = QueryBuilder::new();
+    fn to_sql(&self, primary_query: &QueryBuilder<T>, syntax: crate::Syntax) -> Vec<String> {
+        let mut primary_query = primary_query.clone();
+        let mut qb: QueryBuilder<R> = self.qb.clone();
         qb.set_aliases(&primary_query.alias_asigner);
-        let exist_in = ExistIn::new(
-            &primary_query,
-            self.out_col.clone(),
-            self.inner_tn,
-            self.inner_col.clone(),
-        );
-        qb.exist_ins.push(exist_in);
-        qb.to_sql(syntax)
+        filter_discriminator(&self.ship, &mut primary_query, &mut qb);
+        let mut sqls = Vec::default();
+        match self.ship.join_table() {
+            Some(join_table) => {
+                join_table.filter_linked(&primary_query, &mut qb);
+                sqls.push(join_table.links_sql(&primary_query, syntax));
+            }
+            None => {
+                let exist_in =
+                    ExistIn::new(&primary_query, self.out_col.clone(), self.inner_col.clone());
+                qb.exist_ins.push(exist_in);
+            }
+        }
+        sqls.push(qb.to_sql(syntax));
+        for nested_query in &self.nested {
+            sqls.extend(nested_query.to_sql_keyed(syntax));
+        }
+        sqls
+    }
+
+    async fn run_keyed(
+        &self,
+        rows: &[&T],
+        client: &dyn Client,
+    ) -> Result<Box<dyn RelatedSetAccesser + Send>> {
+        let qb = self.keyed_query();
+        let (data, links) = match self.ship.join_table() {
+            Some(join_table) => {
+                let (data, links) = join_table.load(rows, &qb, client).await?;
+                (data, Some(links))
+            }
+            None => {
+                let data = select_in(client, &qb, &self.out_col, rows, &self.inner_col).await?;
+                (data, None)
+            }
+        };
+
+        let refs: Vec<&R> = data.iter().collect();
+        let mut nested = Vec::default();
+        for nested_query in &self.nested {
+            nested.push(nested_query.run_keyed(&refs, client).await?);
+        }
+
+        Ok(Box::new(RelatedSet::<T, R, Ship>::new(
+            data,
+            self.ship.clone(),
+            links,
+            nested,
+        )))
+    }
+
+    fn to_sql_keyed(&self, syntax: crate::Syntax) -> Vec<String> {
+        let qb = self.keyed_query();
+        let mut sqls = match self.ship.join_table() {
+            Some(join_table) => join_table.load_sql(&qb, syntax),
+            None => vec![keyed_sql(syntax, &qb, &self.out_col, 1, &mut None)],
+        };
+        for nested_query in &self.nested {
+            sqls.extend(nested_query.to_sql_keyed(syntax));
+        }
+        sqls
+    }
+}
+
+impl<T, R, Ship> IncludeQuery<T, R, Ship>
+where
+    Ship: Relationship<T, R>,
+    R: Send + HasSchema,
+{
+    // The query of the related rows when keyed on the rows of the level above.
+    // Rows of the wrong type on the level above are skipped when matched up, see `RelationshipCompare`
+    fn keyed_query(&self) -> QueryBuilder<R> {
+        let mut qb = self.qb.clone();
+        let related_side = self
+            .ship
+            .discriminator()
+            .filter(|d| d.side == DiscriminatorSide::Related);
+        if let Some(d) = related_side {
+            d.filter(&mut qb);
+        }
+        qb
+    }
+}
+
+// polymorphic relationships only select the rows of the right type
+fn filter_discriminator<T, R, Ship>(
+    ship: &Ship,
+    primary_query: &mut QueryBuilder<T>,
+    qb: &mut QueryBuilder<R>,
+) where
+    Ship: Relationship<T, R>,
+{
+    match ship.discriminator() {
+        Some(d) if d.side == DiscriminatorSide::Source => d.filter(primary_query),
+        Some(d) => d.filter(qb),
+        None => {}
     }
 }
 
@@ -99,10 +218,58 @@ where
     _t: PhantomData<T>,
     pub(crate) data: Vec<R>,
     pub(crate) ship: Ship,
+    // set for many-to-many, matches the rows using the selected links
+    links: Option<Linked<T, R>>,
+    pub(crate) nested: Vec<Box<dyn RelatedSetAccesser + Send>>,
+}
+
+impl<T, R, Ship> RelatedSet<T, R, Ship>
+where
+    Ship: Relationship<T, R>,
+{
+    pub(crate) fn new(
+        data: Vec<R>,
+        ship: Ship,
+        links: Option<Linked<T, R>>,
+        nested: Vec<Box<dyn RelatedSetAccesser + Send>>,
+    ) -> Self {
+        Self {
+            _t: Default::default(),
+            data,
+            ship,
+            links,
+            nested,
+        }
+    }
+}
+
+impl<T, R, Ship> RelatedSet<T, R, Ship>
+where
+    Ship: RelationshipCompare<T, R>,
+{
+    /// true if `other` is one of the rows related to `source`
+    pub(crate) fn is_related(&self, source: &T, other: &R) -> bool {
+        match &self.links {
+            Some(linked) => linked(source, other),
+            None => self.ship.is_related(source, other),
+        }
+    }
 }
 
 pub(crate) trait RelatedSetAccesser {
     fn as_any(&self) -> &dyn Any;
+
+    /// The relationship of this set, see `HasRelations::describe_relation`
+    #[cfg(feature = "serde")]
+    fn ship(&self) -> &dyn Any;
+
+    /// The rows related to `source`. `compare` is used unless the set has links
+    #[cfg(feature = "serde")]
+    fn related_to(&self, source: &dyn Any, compare: Option<CompareFn>) -> Vec<&dyn Any>;
+
+    /// The includes of the rows in this set
+    #[cfg(feature = "serde")]
+    fn nested(&self) -> &[Box<dyn RelatedSetAccesser + Send>];
 }
 
 impl<T: 'static, R: 'static, Ship: 'static> RelatedSetAccesser for RelatedSet<T, R, Ship>
@@ -112,6 +279,30 @@ where
     fn as_any(&self) -> &dyn Any {
         self
     }
+
+    #[cfg(feature = "serde")]
+    fn ship(&self) -> &dyn Any {
+        &self.ship
+    }
+
+    #[cfg(feature = "serde")]
+    fn related_to(&self, source: &dyn Any, compare: Option<CompareFn>) -> Vec<&dyn Any> {
+        let is_related = |other: &R| match (&self.links, compare) {
+            (Some(linked), _) => source.downcast_ref::<T>().is_some_and(|t| linked(t, other)),
+            (None, Some(compare)) => compare(&self.ship, source, other),
+            (None, None) => false,
+        };
+        self.data
+            .iter()
+            .filter(|d| is_related(d))
+            .map(|d| d as &dyn Any)
+            .collect()
+    }
+
+    #[cfg(feature = "serde")]
+    fn nested(&self) -> &[Box<dyn RelatedSetAccesser + Send>] {
+        &self.nested
+    }
 }
 
 pub(crate) trait SetDowncast {
@@ -1,16 +1,17 @@
 use crate::WeldsModel;
 
-#[derive(Debug, Default, WeldsModel)]
+#[derive(Debug, Default, WeldsModel, serde::Serialize)]
 #[welds(table = "products")]
 #[welds_path(crate)] // needed only within the welds crate.
 #[welds(HasMany(orders, Order, "product_id"))]
+#[welds(HasMany(lines, OrderLine, "product_id"))]
 struct Product {
     #[welds(primary_key)]
     pub id: i32,
     pub name: String,
 }
 
-#[derive(Debug, Default, WeldsModel)]
+#[derive(Debug, Default, WeldsModel, serde::Serialize)]
 #[welds(table = "orders")]
 #[welds_path(crate)] // needed only within the welds crate.
 #[welds(BelongsTo(product, Product, "product_id"))]
@@ -21,9 +22,322 @@ struct Order {
     pub price: i32,
 }
 
+// not Serialize, products can still be serialized when their lines are not included
+#[derive(Debug, Default, WeldsModel)]
+#[welds(table = "order_lines")]
+#[welds_path(crate)] // needed only within the welds crate.
+#[welds(BelongsTo(product, Product, "product_id"))]
+struct OrderLine {
+    #[welds(primary_key)]
+    pub order_id: i32,
+    #[welds(primary_key)]
+    pub line_no: i32,
+    pub product_id: i32,
+}
+
 #[test]
 fn should_be_able_to_build_a_query_including_both_objects() {
     futures::executor::block_on(async move {
         let _q = Product::all().include(|p| p.orders);
     });
 }
+
+#[test]
+fn should_be_able_to_include_with_a_composite_key_model() {
+    use welds_connections::Syntax;
+    let sqls = Product::all().include(|p| p.lines).to_sql(Syntax::Postgres);
+    assert_eq!(
+        sqls[1],
+        r#"SELECT t2."order_id", t2."line_no", t2."product_id" FROM order_lines t2 WHERE ( EXISTS ( SELECT "id" FROM products t1 WHERE t1."id" = t2."product_id" ) )"#
+    );
+    let sqls = OrderLine::all()
+        .include(|l| l.product)
+        .to_sql(Syntax::Postgres);
+    assert_eq!(
+        sqls[1],
+        r#"SELECT t2."id", t2."name" FROM products t2 WHERE ( EXISTS ( SELECT "product_id" FROM order_lines t1 WHERE t1."product_id" = t2."id" ) )"#
+    );
+}
+
+#[test]
+fn should_select_each_nested_level_keyed_on_the_level_above() {
+    use welds_connections::Syntax;
+    let q = Product::where_col(|p| p.id.equal(1))
+        .include(|p| p.lines)
+        .then_include(
+            |p| p.orders,
+            |orders| orders.include_where(|o| o.product, Product::where_col(|p| p.id.gt(3))),
+        );
+    let sqls = q.to_sql(Syntax::Postgres);
+    assert_eq!(sqls.len(), 4);
+    assert_eq!(
+        sqls[3],
+        r#"SELECT t1."id", t1."name" FROM products t1 WHERE ( t1."id" IN ($1) AND t1."id" > $2 )"#
+    );
+}
+
+#[test]
+fn should_load_related_with_an_in_list_of_the_rows_keys() {
+    use crate::state::DbState;
+    use welds_connections::Syntax;
+    futures::executor::block_on(async move {
+        let client = welds_connections::noop::build(Syntax::Postgres);
+        let products = vec![
+            DbState::db_loaded(Product {
+                id: 1,
+                ..Default::default()
+            }),
+            DbState::db_loaded(Product {
+                id: 2,
+                ..Default::default()
+            }),
+        ];
+        let loaded = super::load_related(&client, &products, |p| p.orders)
+            .await
+            .unwrap();
+        assert_eq!(loaded.len(), 2);
+        assert_eq!(
+            client.last_sql().unwrap(),
+            r#"SELECT t1."id", t1."product_id", t1."price" FROM orders t1 WHERE ( t1."product_id" IN ($1, $2) )"#
+        );
+        assert_eq!(client.args_count(), Some(2));
+    });
+}
+
+#[test]
+fn should_bind_each_shared_key_once_when_loading_related() {
+    use crate::state::DbState;
+    use welds_connections::Syntax;
+    futures::executor::block_on(async move {
+        let client = welds_connections::noop::build(Syntax::Postgres);
+        let orders: Vec<_> = [(1, 7), (2, 7), (3, 8), (4, 7)]
+            .into_iter()
+            .map(|(id, product_id)| {
+                DbState::db_loaded(Order {
+                    id,
+                    product_id,
+                    price: 0,
+                })
+            })
+            .collect();
+        let loaded = super::load_related(&client, &orders, |o| o.product)
+            .await
+            .unwrap();
+        assert_eq!(loaded.len(), 4);
+        assert_eq!(
+            client.last_sql().unwrap(),
+            r#"SELECT t1."id", t1."name" FROM products t1 WHERE ( t1."id" IN ($1, $2) )"#
+        );
+        assert_eq!(client.args_count(), Some(2));
+    });
+}
+
+#[derive(Debug, Default, WeldsModel)]
+#[welds(table = "dogs")]
+#[welds_path(crate)] // needed only within the welds crate.
+#[welds(ManyToMany(cats, Cat, DogCat))]
+struct Dog {
+    #[welds(primary_key)]
+    pub id: i32,
+    pub name: String,
+}
+
+#[derive(Debug, Default, WeldsModel)]
+#[welds(table = "cats")]
+#[welds_path(crate)] // needed only within the welds crate.
+#[welds(ManyToMany(dogs, Dog, DogCat))]
+struct Cat {
+    #[welds(primary_key)]
+    pub id: i32,
+    pub name: String,
+}
+
+#[derive(Debug, Default, WeldsModel)]
+#[welds(table = "dog_cats")]
+#[welds_path(crate)] // needed only within the welds crate.
+#[welds(JoinTable(Dog, "dog_id", Cat, "cat_id"))]
+struct DogCat {
+    #[welds(primary_key)]
+    pub id: i32,
+    pub dog_id: i32,
+    pub cat_id: i32,
+}
+
+#[test]
+fn should_include_many_to_many_through_the_join_table() {
+    use welds_connections::Syntax;
+    let q = Dog::where_col(|d| d.id.equal(1)).include(|d| d.cats);
+    let sqls = q.to_sql(Syntax::Postgres);
+    assert_eq!(sqls.len(), 3);
+    assert_eq!(
+        sqls[1],
+        r#"SELECT t5."id", t5."dog_id", t5."cat_id" FROM dog_cats t5 WHERE ( EXISTS ( SELECT "id" FROM dogs t1 WHERE t1."id" = $1 AND t1."id" = t5."dog_id" ) )"#
+    );
+    assert_eq!(
+        sqls[2],
+        r#"SELECT t2."id", t2."name" FROM cats t2 WHERE ( EXISTS ( SELECT "cat_id" FROM dog_cats t3 WHERE t3."cat_id" = t2."id" AND EXISTS ( SELECT "id" FROM dogs t4 WHERE t4."id" = $1 AND t4."id" = t3."dog_id" ) ) )"#
+    );
+}
+
+#[test]
+fn should_filter_on_many_to_many_through_the_join_table() {
+    use welds_connections::Syntax;
+    let q = Dog::all().where_relation(|d| d.cats, Cat::where_col(|c| c.name.equal("tom")));
+    assert_eq!(
+        q.to_sql(Syntax::Postgres),
+        r#"SELECT t1."id", t1."name" FROM dogs t1 WHERE ( EXISTS ( SELECT "dog_id" FROM dog_cats t2 WHERE t2."dog_id" = t1."id" AND EXISTS ( SELECT "id" FROM cats t3 WHERE t3."name" = $1 AND t3."id" = t2."cat_id" ) ) )"#
+    );
+}
+
+#[derive(Debug, Default, WeldsModel)]
+#[welds(table = "posts")]
+#[welds_path(crate)] // needed only within the welds crate.
+#[welds(HasManyPolymorphic(comments, Comment, "commentable"))]
+struct Post {
+    #[welds(primary_key)]
+    pub id: i32,
+}
+
+#[derive(Debug, Default, WeldsModel)]
+#[welds(table = "photos")]
+#[welds_path(crate)] // needed only within the welds crate.
+#[welds(HasManyPolymorphic(comments, Comment, "commentable", "Photo"))]
+struct Photo {
+    #[welds(primary_key)]
+    pub id: i32,
+}
+
+#[derive(Debug, Default, WeldsModel)]
+#[welds(table = "comments")]
+#[welds_path(crate)] // needed only within the welds crate.
+#[welds(BelongsToPolymorphic(post, Post, "commentable"))]
+#[welds(BelongsToPolymorphic(photo, Photo, "commentable", "Photo"))]
+struct Comment {
+    #[welds(primary_key)]
+    pub id: i32,
+    pub commentable_id: i32,
+    pub commentable_type: String,
+}
+
+#[test]
+fn should_filter_polymorphic_includes_on_the_type_column() {
+    use welds_connections::Syntax;
+    let sqls = Post::all().include(|p| p.comments).to_sql(Syntax::Postgres);
+    assert_eq!(
+        sqls[1],
+        r#"SELECT t2."id", t2."commentable_id", t2."commentable_type" FROM comments t2 WHERE ( t2."commentable_type" = $1 AND EXISTS ( SELECT "id" FROM posts t1 WHERE t1."id" = t2."commentable_id" ) )"#
+    );
+    let sqls = Comment::all().include(|c| c.photo).to_sql(Syntax::Postgres);
+    assert_eq!(
+        sqls[1],
+        r#"SELECT t2."id" FROM photos t2 WHERE ( EXISTS ( SELECT "commentable_id" FROM comments t1 WHERE t1."commentable_type" = $1 AND t1."commentable_id" = t2."id" ) )"#
+    );
+}
+
+#[test]
+fn should_filter_polymorphic_relations_on_the_type_column() {
+    use welds_connections::Syntax;
+    let q = Post::all().where_relation(|p| p.comments, Comment::where_col(|c| c.id.equal(1)));
+    assert_eq!(
+        q.to_sql(Syntax::Postgres),
+        r#"SELECT t1."id" FROM posts t1 WHERE ( EXISTS ( SELECT "commentable_id" FROM comments t2 WHERE t2."id" = $1 AND t2."commentable_type" = $2 AND t2."commentable_id" = t1."id" ) )"#
+    );
+    let q = Photo::where_col(|p| p.id.equal(1)).map_query(|p| p.comments);
+    assert_eq!(
+        q.to_sql(Syntax::Postgres),
+        r#"SELECT t2."id", t2."commentable_id", t2."commentable_type" FROM comments t2 WHERE ( t2."commentable_type" = $1 AND EXISTS ( SELECT "id" FROM photos t1 WHERE t1."id" = $2 AND t1."id" = t2."commentable_id" ) )"#
+    );
+}
+
+#[test]
+fn should_match_polymorphic_rows_on_the_type() {
+    use crate::relations::{HasRelations, RelationshipCompare};
+    let post = Post { id: 1 };
+    let photo = Photo { id: 1 };
+    let comment = Comment {
+        id: 1,
+        commentable_id: 1,
+        commentable_type: "Photo".to_owned(),
+    };
+    let posts = <Post as HasRelations>::Relation::default().comments;
+    let photos = <Photo as HasRelations>::Relation::default().comments;
+    assert!(!posts.is_related(&post, &comment));
+    assert!(photos.is_related(&photo, &comment));
+    let photo_ship = <Comment as HasRelations>::Relation::default().photo;
+    assert!(photo_ship.is_related(&comment, &photo));
+}
+
+#[derive(Debug, Default, WeldsModel)]
+#[welds(table = "customers")]
+#[welds_path(crate)] // needed only within the welds crate.
+#[welds(HasMany(invoices, Invoice, "customer_id"))]
+#[welds(HasManyThrough(items, InvoiceItem, Invoice, "customer_id", "invoice_id"))]
+struct Customer {
+    #[welds(primary_key)]
+    pub id: i32,
+}
+
+#[derive(Debug, Default, WeldsModel)]
+#[welds(table = "invoices")]
+#[welds_path(crate)] // needed only within the welds crate.
+#[welds(BelongsTo(customer, Customer, "customer_id"))]
+struct Invoice {
+    #[welds(primary_key)]
+    pub id: i32,
+    pub customer_id: i32,
+}
+
+#[derive(Debug, Default, WeldsModel)]
+#[welds(table = "invoice_items")]
+#[welds_path(crate)] // needed only within the welds crate.
+#[welds(BelongsTo(invoice, Invoice, "invoice_id"))]
+struct InvoiceItem {
+    #[welds(primary_key)]
+    pub id: i32,
+    pub invoice_id: i32,
+}
+
+#[test]
+fn should_include_has_many_through_the_intermediate_model() {
+    use welds_connections::Syntax;
+    let q = Customer::where_col(|c| c.id.equal(1)).include(|c| c.items);
+    let sqls = q.to_sql(Syntax::Postgres);
+    assert_eq!(sqls.len(), 3);
+    assert_eq!(
+        sqls[1],
+        r#"SELECT t5."id", t5."customer_id" FROM invoices t5 WHERE ( EXISTS ( SELECT "id" FROM customers t1 WHERE t1."id" = $1 AND t1."id" = t5."customer_id" ) )"#
+    );
+    assert_eq!(
+        sqls[2],
+        r#"SELECT t2."id", t2."invoice_id" FROM invoice_items t2 WHERE ( EXISTS ( SELECT "id" FROM invoices t3 WHERE t3."id" = t2."invoice_id" AND EXISTS ( SELECT "id" FROM customers t4 WHERE t4."id" = $1 AND t4."id" = t3."customer_id" ) ) )"#
+    );
+}
+
+#[test]
+fn should_filter_and_map_has_many_through_the_intermediate_model() {
+    use welds_connections::Syntax;
+    let q = Customer::all().where_relation(|c| c.items, InvoiceItem::where_col(|i| i.id.equal(2)));
+    assert_eq!(
+        q.to_sql(Syntax::Postgres),
+        r#"SELECT t1."id" FROM customers t1 WHERE ( EXISTS ( SELECT "customer_id" FROM invoices t2 WHERE t2."customer_id" = t1."id" AND EXISTS ( SELECT "invoice_id" FROM invoice_items t3 WHERE t3."id" = $1 AND t3."invoice_id" = t2."id" ) ) )"#
+    );
+    let q = Customer::where_col(|c| c.id.equal(1)).map_query(|c| c.items);
+    assert_eq!(
+        q.to_sql(Syntax::Postgres),
+        r#"SELECT t2."id", t2."invoice_id" FROM invoice_items t2 WHERE ( EXISTS ( SELECT "id" FROM invoices t3 WHERE t3."id" = t2."invoice_id" AND EXISTS ( SELECT "id" FROM customers t4 WHERE t4."id" = $1 AND t4."id" = t3."customer_id" ) ) )"#
+    );
+}
+
+#[test]
+fn should_serialize_without_the_relations_that_were_not_included() {
+    futures::executor::block_on(async move {
+        let client = welds_connections::noop::build(welds_connections::Syntax::Postgres);
+        let dataset = Product::all()
+            .include(|p| p.orders)
+            .run(&client)
+            .await
+            .unwrap();
+        let json = serde_json::to_string(&dataset).unwrap();
+        assert_eq!(json, "[]");
+    });
+}
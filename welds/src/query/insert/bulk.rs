@@ -1,6 +1,8 @@
 use crate::Client;
 use crate::errors::Result;
-use crate::model_traits::{HasSchema, TableColumns, TableInfo, WriteToArgs};
+use crate::model_traits::TableIdent;
+use crate::model_traits::hooks::{AfterBulkInsert, BeforeBulkInsert};
+use crate::model_traits::{ColumnDefaultCheck, HasSchema, TableColumns, TableInfo, WriteToArgs};
 use crate::query::clause::ParamArgs;
 use crate::writers::ColumnWriter;
 use crate::writers::NextParam;
@@ -11,12 +13,12 @@ use crate::writers::TableWriter;
 #[maybe_async::maybe_async]
 pub async fn bulk_insert_with_ids<T>(conn: &dyn Client, data: &[T]) -> Result<()>
 where
-    T: WriteToArgs + HasSchema,
+    T: WriteToArgs + HasSchema + ColumnDefaultCheck + BeforeBulkInsert + AfterBulkInsert,
     <T as HasSchema>::Schema: TableInfo + TableColumns,
 {
     let syntax = conn.syntax();
-    let parts = <<T as HasSchema>::Schema>::identifier();
-    let tablename: String = TableWriter::new(syntax).write2(parts);
+    let table = TableIdent::from_model::<T>().or_schema(conn.default_schema());
+    let tablename: String = TableWriter::new(syntax).write(&table);
     run(conn, data, true, &tablename).await
 }
 
@@ -25,12 +27,12 @@ where
 #[maybe_async::maybe_async]
 pub async fn bulk_insert<T>(conn: &dyn Client, data: &[T]) -> Result<()>
 where
-    T: WriteToArgs + HasSchema,
+    T: WriteToArgs + HasSchema + ColumnDefaultCheck + BeforeBulkInsert + AfterBulkInsert,
     <T as HasSchema>::Schema: TableInfo + TableColumns,
 {
     let syntax = conn.syntax();
-    let parts = <<T as HasSchema>::Schema>::identifier();
-    let tablename: String = TableWriter::new(syntax).write2(parts);
+    let table = TableIdent::from_model::<T>().or_schema(conn.default_schema());
+    let tablename: String = TableWriter::new(syntax).write(&table);
     run(conn, data, false, &tablename).await
 }
 
@@ -46,7 +48,7 @@ pub async fn bulk_insert_with_ids_override_tablename_unsafe<T>(
     tablename: impl Into<String>,
 ) -> Result<()>
 where
-    T: WriteToArgs + HasSchema,
+    T: WriteToArgs + HasSchema + ColumnDefaultCheck + BeforeBulkInsert + AfterBulkInsert,
     <T as HasSchema>::Schema: TableInfo + TableColumns,
 {
     let tablename: String = tablename.into();
@@ -65,7 +67,7 @@ pub async fn bulk_insert_override_tablename_unsafe<T>(
     tablename: impl Into<String>,
 ) -> Result<()>
 where
-    T: WriteToArgs + HasSchema,
+    T: WriteToArgs + HasSchema + ColumnDefaultCheck + BeforeBulkInsert + AfterBulkInsert,
     <T as HasSchema>::Schema: TableInfo + TableColumns,
 {
     let tablename: String = tablename.into();
@@ -73,15 +75,25 @@ where
 }
 
 /// Executes the query in the database Bulk Inserting values
+///
+/// `#[welds(created_at)]` and `#[welds(updated_at)]` columns without a value are set to now
+///
+/// `#[welds(db_default)]` columns are only written when at least one of the rows has a value
+///
+/// Each row is given to `BeforeBulkInsert` before anything is written,
+/// and to `AfterBulkInsert` once all the rows are written
 #[maybe_async::maybe_async]
 async fn run<T>(conn: &dyn Client, data: &[T], with_ids: bool, tablename: &str) -> Result<()>
 where
-    T: WriteToArgs + HasSchema,
+    T: WriteToArgs + HasSchema + ColumnDefaultCheck + BeforeBulkInsert + AfterBulkInsert,
     <T as HasSchema>::Schema: TableInfo + TableColumns,
 {
     if data.is_empty() {
         return Ok(());
     }
+    for d in data {
+        <T as BeforeBulkInsert>::before(d).await?;
+    }
     let syntax = conn.syntax();
 
     // // If postgres do the fast bulk insert
@@ -93,9 +105,18 @@ where
     let all_columns = <<T as HasSchema>::Schema as TableColumns>::insert_columns();
     let pks = <<T as HasSchema>::Schema as TableColumns>::primary_keys();
 
+    // #[welds(db_default)] columns are left to the database when none of the rows give them a value
+    let mut db_defaults = Vec::default();
+    for col in <T as HasSchema>::Schema::db_default_columns() {
+        if all_default(data, col)? {
+            db_defaults.push(*col);
+        }
+    }
+
     let columns: Vec<_> = all_columns
         .iter()
         .filter(|c| with_ids || !pks.contains(c))
+        .filter(|c| !db_defaults.contains(&c.name()))
         .collect();
 
     let colnames: Vec<String> = columns
@@ -109,6 +130,17 @@ where
     let chunk_size = max_params as usize / colnames.len();
     let param_size = chunk_size + columns.len();
 
+    // The same "now" is used for all the rows
+    let schema_timestamps = [
+        <T as HasSchema>::Schema::created_at_column(),
+        <T as HasSchema>::Schema::updated_at_column(),
+    ];
+    let timestamps: Vec<_> = schema_timestamps
+        .iter()
+        .flatten()
+        .map(|col| (col.name(), col.now_param()))
+        .collect();
+
     for chunk in data.chunks(chunk_size) {
         let next_params = NextParam::new(syntax);
         let mut args: ParamArgs = Vec::with_capacity(param_size);
@@ -117,7 +149,11 @@ where
         for d in chunk {
             let mut row: Vec<String> = Vec::default();
             for col in &columns {
-                d.bind(col.name(), &mut args)?;
+                let now = timestamps.iter().find(|(name, _)| *name == col.name());
+                match now {
+                    Some((_, now)) if d.col_is_default(col.name())? => args.push(now.as_ref()),
+                    _ => d.bind(col.name(), &mut args)?,
+                }
                 row.push(next_params.next());
             }
             rows.push(format!("({})", row.join(",")));
@@ -127,16 +163,28 @@ where
         conn.execute(&sql, &args).await?;
     }
 
+    for d in data {
+        <T as AfterBulkInsert>::after(d).await?;
+    }
     Ok(())
 }
 
+fn all_default<T: ColumnDefaultCheck>(data: &[T], column: &str) -> Result<bool> {
+    for d in data {
+        if !d.col_is_default(column)? {
+            return Ok(false);
+        }
+    }
+    Ok(true)
+}
+
 //  /// Executes the query in the database Bulk Inserting the values
 //  /// This method of bulk inserting is faster, but is not available for all data structures.
 //  /// This method is preferred if available
 //  pub(crate) async fn run_fast<T, C>(conn: &C, data: &[T]) -> Result<()>
 //  where
 //      C: Client,
-//      T: WriteToArgs + HasSchema,
+//      T: WriteToArgs + HasSchema + ColumnDefaultCheck + BeforeBulkInsert + AfterBulkInsert,
 //      <T as HasSchema>::Schema: TableInfo + TableColumns,
 //      //'c: 'r,
 //      //DB: Database,
@@ -1,6 +1,7 @@
 mod bulk;
 mod single;
 
+pub(crate) use single::insert_into;
 pub use single::insert_one;
 
 pub use bulk::bulk_insert;
@@ -2,9 +2,11 @@ use crate::Row;
 use crate::errors::Result;
 use crate::errors::WeldsError::InsertFailed;
 use crate::model_traits::hooks::{AfterCreate, BeforeCreate};
-use crate::model_traits::{ColumnDefaultCheck, UpdateFromRow};
-use crate::model_traits::{HasSchema, TableColumns, TableInfo, WriteToArgs};
+use crate::model_traits::timestamp::touch_for_insert;
+use crate::model_traits::{AutoTimestamps, ColumnDefaultCheck, UpdateFromRow};
+use crate::model_traits::{HasSchema, TableColumns, TableIdent, TableInfo, WriteToArgs};
 use crate::query::clause::ParamArgs;
+use crate::query::helpers::build_select_by_pks;
 use crate::writers::NextParam;
 use crate::writers::TableWriter;
 use crate::writers::column::ColumnWriter;
@@ -17,24 +19,45 @@ pub async fn insert_one<T>(obj: &mut T, client: &dyn Client) -> Result<()>
 where
     T: WriteToArgs + HasSchema + ColumnDefaultCheck,
     <T as HasSchema>::Schema: TableInfo + TableColumns,
-    T: UpdateFromRow,
+    T: UpdateFromRow + AutoTimestamps,
+    T: BeforeCreate + AfterCreate,
+{
+    let table = TableIdent::from_model::<T>().or_schema(client.default_schema());
+    insert_into(obj, &table, client).await
+}
+
+/// Inserts a model into the given table
+#[maybe_async::maybe_async]
+pub(crate) async fn insert_into<T>(
+    obj: &mut T,
+    table: &TableIdent,
+    client: &dyn Client,
+) -> Result<()>
+where
+    T: WriteToArgs + HasSchema + ColumnDefaultCheck,
+    <T as HasSchema>::Schema: TableInfo + TableColumns,
+    T: UpdateFromRow + AutoTimestamps,
     T: BeforeCreate + AfterCreate,
 {
     BeforeCreate::before(obj).await?;
+    touch_for_insert(obj)?;
 
     let syntax = client.syntax();
     let mut args: ParamArgs = Vec::default();
-    let args2: ParamArgs = Vec::default();
+    let mut args2: ParamArgs = Vec::default();
 
     let col_writer = ColumnWriter::new(syntax);
     let next_params = NextParam::new(syntax);
     let writer = InsertWriter::new(syntax);
 
-    let parts = <<T as HasSchema>::Schema>::identifier();
-    let identifier = TableWriter::new(syntax).write2(parts);
+    let identifier = TableWriter::new(syntax).write(table);
 
     let columns = <<T as HasSchema>::Schema as TableColumns>::insert_columns();
     let pks = <<T as HasSchema>::Schema as TableColumns>::primary_keys();
+    let db_defaults = <<T as HasSchema>::Schema as TableInfo>::db_default_columns();
+    // The row needs to be read back to get the values the database wrote
+    let refresh_required =
+        !<<T as HasSchema>::Schema as TableInfo>::db_managed_columns().is_empty();
 
     let mut colargs = Vec::default();
     let mut id_return_required = true;
@@ -44,6 +67,11 @@ where
 
         match pk {
             None => {
+                // Leave it to the database until the user gives it a value
+                if db_defaults.contains(&col.name()) && obj.col_is_default(col.name())? {
+                    log::trace!("col: {} is default, using the db default", col.name());
+                    continue;
+                }
                 // column isn't PK just insert it
                 obj.bind(col.name(), &mut args)?;
                 let col = col_writer.excape(col.name());
@@ -91,6 +119,14 @@ where
                 params: &args2,
             })
         }
+    } else if refresh_required && select.is_some() {
+        // The ID was given, the row is found by it instead of the last inserted ID
+        let columns = <<T as HasSchema>::Schema as TableColumns>::select_columns();
+        sql2 = build_select_by_pks(syntax, obj, table, &columns, &mut args2)?;
+        statements.push(Fetch {
+            sql: &sql2,
+            params: &args2,
+        })
     }
 
     // WARNING: these statements MUST be ran on the same DB connection in the pool
@@ -100,8 +136,9 @@ where
     let mut rows: Vec<Row> = datasets.drain(..).flatten().collect();
 
     // If we are providing the DB with the ID, (string/uuid) it doesn't need to return the id, and will not
-    if !id_return_required {
+    if !id_return_required && !refresh_required {
         AfterCreate::after(obj).await.ok();
+        AfterCreate::after_commit(obj, client);
         return Ok(());
     }
 
@@ -110,6 +147,7 @@ where
         row.ok_or_else(|| InsertFailed("Insert didn't return inserted ID/Row".to_owned()))?;
     UpdateFromRow::update_from_row(obj, &mut row)?;
     AfterCreate::after(obj).await.ok();
+    AfterCreate::after_commit(obj, client);
 
     Ok(())
 }
@@ -15,6 +15,104 @@ struct Product {
     pub b: i32,
 }
 
+#[derive(Debug, Default, WeldsModel)]
+#[welds(table = "posts")]
+#[welds_path(crate)] // needed only within the welds crate.
+struct Post {
+    #[welds(primary_key)]
+    pub id: i32,
+    pub title: String,
+    #[welds(created_at)]
+    pub created_at: String,
+    #[welds(updated_at)]
+    pub updated_at: Option<String>,
+}
+
+#[derive(Debug, Default, WeldsModel)]
+#[welds(table = "comments")]
+#[welds_path(crate)] // needed only within the welds crate.
+struct Comment {
+    #[welds(primary_key)]
+    pub id: i32,
+    pub body: String,
+    #[welds(created_at(db_default))]
+    pub created_at: String,
+}
+
+#[derive(Debug, Default, WeldsModel)]
+#[welds(table = "orders")]
+#[welds_path(crate)] // needed only within the welds crate.
+struct Order {
+    #[welds(primary_key)]
+    pub id: i32,
+    pub qty: i32,
+    #[welds(db_default)]
+    pub code: String,
+    #[welds(generated)]
+    pub total: i32,
+}
+
+#[derive(Debug, Default, Clone, PartialEq)]
+struct Money(i64);
+
+struct CentsCodec;
+
+impl welds_connections::codec::Codec<Money> for CentsCodec {
+    type Db = String;
+    fn encode(value: &Money) -> String {
+        format!("{}.{:02}", value.0 / 100, value.0 % 100)
+    }
+    fn decode(value: String) -> Result<Money, welds_connections::codec::BoxError> {
+        let (dollars, cents) = value.split_once('.').ok_or("missing cents")?;
+        Ok(Money(dollars.parse::<i64>()? * 100 + cents.parse::<i64>()?))
+    }
+}
+
+#[derive(Debug, Default, WeldsModel)]
+#[welds(table = "invoices")]
+#[welds_path(crate)] // needed only within the welds crate.
+struct Invoice {
+    #[welds(primary_key)]
+    pub id: i32,
+    #[welds(with = "CentsCodec")]
+    pub total: Money,
+    #[welds(with = "CentsCodec")]
+    pub tip: Option<Money>,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+struct Settings {
+    pub theme: String,
+}
+
+#[derive(Debug, Default, WeldsModel)]
+#[welds(table = "profiles")]
+#[welds_path(crate)] // needed only within the welds crate.
+struct Profile {
+    #[welds(primary_key)]
+    pub id: i32,
+    pub settings: crate::Json<Settings>,
+    pub extra: Option<crate::Json<Settings>>,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, serde::Deserialize)]
+struct Unwritable;
+
+impl serde::Serialize for Unwritable {
+    fn serialize<S: serde::Serializer>(&self, _: S) -> std::result::Result<S::Ok, S::Error> {
+        Err(serde::ser::Error::custom("can't be written"))
+    }
+}
+
+#[derive(Debug, Default, WeldsModel)]
+#[welds(table = "profiles")]
+#[welds_path(crate)] // needed only within the welds crate.
+struct UnwritableProfile {
+    #[welds(primary_key)]
+    pub id: i32,
+    pub settings: crate::Json<Unwritable>,
+}
+
 // Tests
 
 #[test]
@@ -30,3 +128,198 @@ fn should_be_able_to_insert_simple_object() {
         assert_eq!(expected, &ran_sql);
     });
 }
+
+#[test]
+fn should_set_the_timestamps_on_insert() {
+    futures::executor::block_on(async move {
+        let obj = Post::default();
+        let mut obj = DbState::new_uncreated(obj);
+        let client = welds_connections::noop::build(Syntax::Postgres);
+        let _ = obj.save(&client).await;
+        let ran_sql = client.last_sql().unwrap();
+
+        let expected = "INSERT INTO posts (\"title\", \"created_at\", \"updated_at\") VALUES ($1, $2, $3) RETURNING *";
+        assert_eq!(expected, &ran_sql);
+        assert_eq!(obj.created_at, "now");
+        assert_eq!(obj.updated_at.as_deref(), Some("now"));
+    });
+}
+
+#[test]
+fn should_keep_a_timestamp_that_was_already_set() {
+    futures::executor::block_on(async move {
+        let obj = Post {
+            created_at: "yesterday".to_owned(),
+            ..Default::default()
+        };
+        let mut obj = DbState::new_uncreated(obj);
+        let client = welds_connections::noop::build(Syntax::Postgres);
+        let _ = obj.save(&client).await;
+
+        assert_eq!(obj.created_at, "yesterday");
+        assert_eq!(obj.updated_at.as_deref(), Some("now"));
+    });
+}
+
+#[test]
+fn should_leave_db_default_timestamps_to_the_database() {
+    futures::executor::block_on(async move {
+        let obj = Comment::default();
+        let mut obj = DbState::new_uncreated(obj);
+        let client = welds_connections::noop::build(Syntax::Postgres);
+        let _ = obj.save(&client).await;
+        let ran_sql = client.last_sql().unwrap();
+
+        let expected = "INSERT INTO comments (\"body\") VALUES ($1) RETURNING *";
+        assert_eq!(expected, &ran_sql);
+        assert_eq!(obj.created_at, "");
+    });
+}
+
+#[test]
+fn should_set_the_timestamps_on_bulk_insert() {
+    futures::executor::block_on(async move {
+        let posts = vec![Post::default(), Post::default()];
+        let client = welds_connections::noop::build(Syntax::Postgres);
+        let _ = crate::query::insert::bulk_insert(&client, &posts).await;
+        let ran_sql = client.last_sql().unwrap();
+
+        let expected = "INSERT INTO posts (\"title\", \"created_at\", \"updated_at\") VALUES ($1,$2,$3),($4,$5,$6)";
+        assert_eq!(expected, &ran_sql);
+        assert_eq!(client.args_count().unwrap(), 6);
+    });
+}
+
+#[test]
+fn should_leave_db_default_columns_out_of_the_insert() {
+    futures::executor::block_on(async move {
+        let obj = Order::default();
+        let mut obj = DbState::new_uncreated(obj);
+        let client = welds_connections::noop::build(Syntax::Postgres);
+        let _ = obj.save(&client).await;
+        let ran_sql = client.last_sql().unwrap();
+
+        let expected = "INSERT INTO orders (\"qty\") VALUES ($1) RETURNING *";
+        assert_eq!(expected, &ran_sql);
+    });
+}
+
+#[test]
+fn should_insert_db_default_columns_that_have_a_value() {
+    futures::executor::block_on(async move {
+        let obj = Order {
+            code: "A1".to_owned(),
+            ..Default::default()
+        };
+        let mut obj = DbState::new_uncreated(obj);
+        let client = welds_connections::noop::build(Syntax::Postgres);
+        let _ = obj.save(&client).await;
+        let ran_sql = client.last_sql().unwrap();
+
+        let expected = "INSERT INTO orders (\"qty\", \"code\") VALUES ($1, $2) RETURNING *";
+        assert_eq!(expected, &ran_sql);
+    });
+}
+
+#[test]
+fn should_read_back_db_columns_when_the_id_is_given() {
+    futures::executor::block_on(async move {
+        let obj = Order {
+            id: 5,
+            ..Default::default()
+        };
+        let mut obj = DbState::new_uncreated(obj);
+        let client = welds_connections::noop::build(Syntax::Sqlite);
+        let _ = obj.save(&client).await;
+        let ran_sql = client.last_sql().unwrap();
+
+        let expected = "INSERT INTO orders (\"id\", \"qty\") VALUES (?, ?);SELECT \"id\", \"qty\", \"code\", \"total\" FROM orders where \"id\"=?";
+        assert_eq!(expected, &ran_sql);
+    });
+}
+
+#[test]
+fn should_leave_db_default_columns_out_of_bulk_inserts() {
+    futures::executor::block_on(async move {
+        let orders = vec![Order::default(), Order::default()];
+        let client = welds_connections::noop::build(Syntax::Postgres);
+        let _ = crate::query::insert::bulk_insert(&client, &orders).await;
+        let ran_sql = client.last_sql().unwrap();
+
+        let expected = "INSERT INTO orders (\"qty\") VALUES ($1),($2)";
+        assert_eq!(expected, &ran_sql);
+    });
+}
+
+#[test]
+fn should_insert_columns_through_their_codec() {
+    futures::executor::block_on(async move {
+        let obj = Invoice {
+            total: Money(1250),
+            ..Default::default()
+        };
+        let mut obj = DbState::new_uncreated(obj);
+        let client = welds_connections::noop::build(Syntax::Postgres);
+        let _ = obj.save(&client).await;
+        let ran_sql = client.last_sql().unwrap();
+
+        let expected = "INSERT INTO invoices (\"total\", \"tip\") VALUES ($1, $2) RETURNING *";
+        assert_eq!(expected, &ran_sql);
+        assert_eq!(client.args_count().unwrap(), 2);
+    });
+}
+
+#[test]
+fn should_encode_and_decode_with_a_codec() {
+    use welds_connections::codec::{Codec, Encoded, OptionCodec};
+    let total = Money(1205);
+    assert_eq!(Encoded::<CentsCodec, Money>::wrap(&total).encode(), "12.05");
+    assert_eq!(CentsCodec::decode("3.10".to_owned()).unwrap(), Money(310));
+    assert!(CentsCodec::decode("3".to_owned()).is_err());
+    let tip: Option<Money> = None;
+    assert_eq!(OptionCodec::<CentsCodec>::encode(&tip), None);
+}
+
+#[test]
+fn should_insert_json_columns() {
+    futures::executor::block_on(async move {
+        let settings = Settings {
+            theme: "dark".to_owned(),
+        };
+        let obj = Profile {
+            settings: crate::Json(settings.clone()),
+            extra: Some(crate::Json(settings)),
+            ..Default::default()
+        };
+        let mut obj = DbState::new_uncreated(obj);
+        let client = welds_connections::noop::build(Syntax::Mssql);
+        let _ = obj.save(&client).await;
+        let ran_sql = client.last_sql().unwrap();
+
+        let expected = "INSERT INTO profiles (\"settings\", \"extra\") OUTPUT Inserted.\"id\", Inserted.\"settings\", Inserted.\"extra\" VALUES (@p1, @p2)";
+        assert_eq!(expected, &ran_sql);
+        assert_eq!(client.args_count().unwrap(), 2);
+    });
+}
+
+#[test]
+fn should_return_an_error_when_json_can_not_be_written() {
+    futures::executor::block_on(async move {
+        let mut obj = DbState::new_uncreated(UnwritableProfile::default());
+        let client = welds_connections::noop::build(Syntax::Mssql);
+        let err = obj.save(&client).await.unwrap_err();
+        assert!(err.to_string().contains("settings"));
+        assert!(client.last_sql().is_none());
+    });
+}
+
+#[test]
+fn json_columns_should_serialize_as_their_value() {
+    let settings = crate::Json(Settings {
+        theme: "dark".to_owned(),
+    });
+    let json = serde_json::to_string(&settings).unwrap();
+    assert_eq!(json, r#"{"theme":"dark"}"#);
+    let back: crate::Json<Settings> = serde_json::from_str(&json).unwrap();
+    assert_eq!(back, settings);
+}
@@ -1,11 +1,14 @@
-use crate::Client;
 use crate::errors::{Result, WeldsError};
-use crate::model_traits::{HasSchema, TableColumns, TableInfo, WriteToArgs};
+use crate::model_traits::hooks::{AfterBulkInsert, BeforeBulkInsert};
+use crate::model_traits::{Column, ColumnDefaultCheck, ForeignKeyPartialEq, PrimaryKeyValue};
+use crate::model_traits::{HasSchema, TableColumns, TableIdent, TableInfo, WriteToArgs};
 use crate::query::clause::ParamArgs;
+use crate::query::insert::bulk_insert;
 use crate::relations::HasJoinTableForeignkey;
 use crate::writers::ColumnWriter;
 use crate::writers::NextParam;
 use crate::writers::TableWriter;
+use crate::{Client, Row, Syntax};
 use std::any::type_name;
 
 /// Create a many-to-many join table link.
@@ -27,8 +30,8 @@ where
     <B as HasSchema>::Schema: TableInfo + TableColumns,
 {
     let syntax = conn.syntax();
-    let tablename_parts = <<Link as HasSchema>::Schema>::identifier();
-    let tablename: String = TableWriter::new(syntax).write2(tablename_parts);
+    let table = TableIdent::from_model::<Link>().or_schema(conn.default_schema());
+    let tablename: String = TableWriter::new(syntax).write(&table);
 
     // Make sure each model has exactly one PK
     let a_pks = <A as HasSchema>::Schema::primary_keys();
@@ -93,8 +96,8 @@ where
     <B as HasSchema>::Schema: TableInfo + TableColumns,
 {
     let syntax = conn.syntax();
-    let tablename_parts = <<Link as HasSchema>::Schema>::identifier();
-    let tablename: String = TableWriter::new(syntax).write2(tablename_parts);
+    let table = TableIdent::from_model::<Link>().or_schema(conn.default_schema());
+    let tablename: String = TableWriter::new(syntax).write(&table);
 
     // Make sure each model has exactly one PK
     let a_pks = <A as HasSchema>::Schema::primary_keys();
@@ -138,3 +141,430 @@ where
 
     Ok(())
 }
+
+/// Links model_a to each of models_b, skipping the links that already exist.
+/// Ran in a transaction, unless the client is already a transaction
+///
+/// Warning: This function DOES NOT check that model_a or models_b are in the database.
+#[maybe_async::maybe_async]
+pub async fn attach_many<Link, A, B>(conn: &dyn Client, model_a: &A, models_b: &[B]) -> Result<()>
+where
+    Link: WriteToArgs + HasSchema,
+    <Link as HasSchema>::Schema: TableInfo + TableColumns,
+    Link: HasJoinTableForeignkey<A> + HasJoinTableForeignkey<B>,
+    Link: ForeignKeyPartialEq<B::PrimaryKeyType>,
+    Link: TryFrom<Row>,
+    WeldsError: From<<Link as TryFrom<Row>>::Error>,
+    A: WriteToArgs + HasSchema,
+    <A as HasSchema>::Schema: TableInfo + TableColumns,
+    B: WriteToArgs + HasSchema + PrimaryKeyValue,
+    <B as HasSchema>::Schema: TableInfo + TableColumns,
+    B::PrimaryKeyType: PartialEq,
+{
+    let trans = conn.begin_if_needed().await?;
+    let conn: &dyn Client = match &trans {
+        Some(trans) => trans,
+        None => conn,
+    };
+    let existing: Vec<Link> = select_links(conn, model_a).await?;
+    let missing = missing_links::<Link, B>(&existing, models_b);
+    insert_links::<Link, A, B>(conn, model_a, &missing).await?;
+    if let Some(trans) = trans {
+        trans.commit().await?;
+    }
+    Ok(())
+}
+
+/// Links model_a to each of models_b, skipping the links that already exist.
+///
+/// The new links are built with `build`, used when the join table has extra columns.
+/// Ran in a transaction, unless the client is already a transaction
+#[maybe_async::maybe_async]
+pub async fn attach_many_with<Link, A, B>(
+    conn: &dyn Client,
+    model_a: &A,
+    models_b: &[B],
+    build: impl Fn(&A, &B) -> Link,
+) -> Result<()>
+where
+    Link: WriteToArgs + HasSchema + ColumnDefaultCheck + BeforeBulkInsert + AfterBulkInsert,
+    <Link as HasSchema>::Schema: TableInfo + TableColumns,
+    Link: HasJoinTableForeignkey<A> + HasJoinTableForeignkey<B>,
+    Link: ForeignKeyPartialEq<B::PrimaryKeyType>,
+    Link: TryFrom<Row>,
+    WeldsError: From<<Link as TryFrom<Row>>::Error>,
+    A: WriteToArgs + HasSchema,
+    <A as HasSchema>::Schema: TableInfo + TableColumns,
+    B: WriteToArgs + HasSchema + PrimaryKeyValue,
+    <B as HasSchema>::Schema: TableInfo + TableColumns,
+    B::PrimaryKeyType: PartialEq,
+{
+    let trans = conn.begin_if_needed().await?;
+    let conn: &dyn Client = match &trans {
+        Some(trans) => trans,
+        None => conn,
+    };
+    let existing: Vec<Link> = select_links(conn, model_a).await?;
+    let missing = missing_links::<Link, B>(&existing, models_b);
+    let links: Vec<Link> = missing.iter().map(|b| build(model_a, b)).collect();
+    if !links.is_empty() {
+        bulk_insert(conn, &links).await?;
+    }
+    if let Some(trans) = trans {
+        trans.commit().await?;
+    }
+    Ok(())
+}
+
+/// Removes all the links of model_a. Returns the number of links removed
+#[maybe_async::maybe_async]
+pub async fn detach_all<Link, A>(conn: &dyn Client, model_a: &A) -> Result<u64>
+where
+    Link: HasSchema + HasJoinTableForeignkey<A>,
+    <Link as HasSchema>::Schema: TableInfo + TableColumns,
+    A: WriteToArgs + HasSchema,
+    <A as HasSchema>::Schema: TableInfo + TableColumns,
+{
+    let syntax = conn.syntax();
+    let table = TableIdent::from_model::<Link>().or_schema(conn.default_schema());
+    let tablename: String = TableWriter::new(syntax).write(&table);
+    let a_pk = join_table_pk::<A>()?;
+
+    let mut args: ParamArgs = Vec::with_capacity(1);
+    model_a.bind(a_pk.name(), &mut args)?;
+
+    let a_fk = <Link as HasJoinTableForeignkey<A>>::fk_column();
+    let a_fk = ColumnWriter::new(syntax).excape(a_fk);
+    let next_param = NextParam::new(syntax);
+    let sql = format!(
+        "DELETE FROM {tablename} WHERE {a_fk} = {}",
+        next_param.next()
+    );
+
+    let result = conn.execute(&sql, &args).await?;
+    Ok(result.rows_affected())
+}
+
+/// Makes the links of model_a exactly models_b.
+///
+/// The current links are compared to models_b. Only the links that are missing are created
+/// and only the links to other models are removed.
+/// Ran in a transaction, unless the client is already a transaction
+#[maybe_async::maybe_async]
+pub async fn sync<Link, A, B>(conn: &dyn Client, model_a: &A, models_b: &[B]) -> Result<()>
+where
+    Link: WriteToArgs + HasSchema,
+    <Link as HasSchema>::Schema: TableInfo + TableColumns,
+    Link: HasJoinTableForeignkey<A> + HasJoinTableForeignkey<B>,
+    Link: ForeignKeyPartialEq<B::PrimaryKeyType>,
+    Link: TryFrom<Row>,
+    WeldsError: From<<Link as TryFrom<Row>>::Error>,
+    A: WriteToArgs + HasSchema,
+    <A as HasSchema>::Schema: TableInfo + TableColumns,
+    B: WriteToArgs + HasSchema + PrimaryKeyValue,
+    <B as HasSchema>::Schema: TableInfo + TableColumns,
+    B::PrimaryKeyType: PartialEq,
+{
+    let trans = conn.begin_if_needed().await?;
+    let conn: &dyn Client = match &trans {
+        Some(trans) => trans,
+        None => conn,
+    };
+    let existing: Vec<Link> = select_links(conn, model_a).await?;
+    delete_stale_links::<Link, A, B>(conn, model_a, &existing, models_b).await?;
+    let missing = missing_links::<Link, B>(&existing, models_b);
+    insert_links::<Link, A, B>(conn, model_a, &missing).await?;
+    if let Some(trans) = trans {
+        trans.commit().await?;
+    }
+    Ok(())
+}
+
+/// Makes the links of model_a exactly models_b.
+///
+/// The new links are built with `build`, used when the join table has extra columns.
+/// Links that already exist are left as they are.
+/// Ran in a transaction, unless the client is already a transaction
+#[maybe_async::maybe_async]
+pub async fn sync_with<Link, A, B>(
+    conn: &dyn Client,
+    model_a: &A,
+    models_b: &[B],
+    build: impl Fn(&A, &B) -> Link,
+) -> Result<()>
+where
+    Link: WriteToArgs + HasSchema + ColumnDefaultCheck + BeforeBulkInsert + AfterBulkInsert,
+    <Link as HasSchema>::Schema: TableInfo + TableColumns,
+    Link: HasJoinTableForeignkey<A> + HasJoinTableForeignkey<B>,
+    Link: ForeignKeyPartialEq<B::PrimaryKeyType>,
+    Link: TryFrom<Row>,
+    WeldsError: From<<Link as TryFrom<Row>>::Error>,
+    A: WriteToArgs + HasSchema,
+    <A as HasSchema>::Schema: TableInfo + TableColumns,
+    B: WriteToArgs + HasSchema + PrimaryKeyValue,
+    <B as HasSchema>::Schema: TableInfo + TableColumns,
+    B::PrimaryKeyType: PartialEq,
+{
+    let trans = conn.begin_if_needed().await?;
+    let conn: &dyn Client = match &trans {
+        Some(trans) => trans,
+        None => conn,
+    };
+    let existing: Vec<Link> = select_links(conn, model_a).await?;
+    delete_stale_links::<Link, A, B>(conn, model_a, &existing, models_b).await?;
+    let missing = missing_links::<Link, B>(&existing, models_b);
+    let links: Vec<Link> = missing.iter().map(|b| build(model_a, b)).collect();
+    if !links.is_empty() {
+        bulk_insert(conn, &links).await?;
+    }
+    if let Some(trans) = trans {
+        trans.commit().await?;
+    }
+    Ok(())
+}
+
+// The most links written in one statement, keeps the params under the limit of the database
+fn links_per_statement(syntax: Syntax, params_per_link: u32, other_params: u32) -> usize {
+    let max = NextParam::new(syntax).max_params() - other_params;
+    (max / params_per_link).max(1) as usize
+}
+
+fn join_table_pk<M>() -> Result<Column>
+where
+    M: HasSchema,
+    <M as HasSchema>::Schema: TableColumns,
+{
+    let mut pks = <M as HasSchema>::Schema::primary_keys();
+    if pks.len() != 1 {
+        let key_error = format!(
+            "Error: the struct \"{}\" can't be used in a join table. It must have exactly one PK.",
+            type_name::<M>()
+        );
+        return Err(WeldsError::InsertFailed(key_error));
+    }
+    Ok(pks.remove(0))
+}
+
+// The link rows of model_a
+#[maybe_async::maybe_async]
+async fn select_links<Link, A>(conn: &dyn Client, model_a: &A) -> Result<Vec<Link>>
+where
+    Link: HasSchema + HasJoinTableForeignkey<A>,
+    <Link as HasSchema>::Schema: TableInfo + TableColumns,
+    Link: TryFrom<Row>,
+    WeldsError: From<<Link as TryFrom<Row>>::Error>,
+    A: WriteToArgs + HasSchema,
+    <A as HasSchema>::Schema: TableInfo + TableColumns,
+{
+    let syntax = conn.syntax();
+    let table = TableIdent::from_model::<Link>().or_schema(conn.default_schema());
+    let tablename: String = TableWriter::new(syntax).write(&table);
+    let a_pk = join_table_pk::<A>()?;
+
+    let mut args: ParamArgs = Vec::with_capacity(1);
+    model_a.bind(a_pk.name(), &mut args)?;
+
+    let col_write = ColumnWriter::new(syntax);
+    let columns: Vec<String> = <Link as HasSchema>::Schema::select_columns()
+        .iter()
+        .map(|c| col_write.excape(c.name()))
+        .collect();
+    let columns = columns.join(", ");
+    let a_fk = col_write.excape(<Link as HasJoinTableForeignkey<A>>::fk_column());
+    let next_param = NextParam::new(syntax);
+    let sql = format!(
+        "SELECT {columns} FROM {tablename} WHERE {a_fk} = {}",
+        next_param.next()
+    );
+
+    let rows = conn.fetch_rows(&sql, &args).await?;
+    let mut links = Vec::with_capacity(rows.len());
+    for row in rows {
+        links.push(Link::try_from(row)?);
+    }
+    Ok(links)
+}
+
+// The models of models_b that aren't linked yet, each only once
+fn missing_links<'b, Link, B>(existing: &[Link], models_b: &'b [B]) -> Vec<&'b B>
+where
+    Link: HasJoinTableForeignkey<B> + ForeignKeyPartialEq<B::PrimaryKeyType>,
+    B: PrimaryKeyValue,
+    B::PrimaryKeyType: PartialEq,
+{
+    let b_fk = <Link as HasJoinTableForeignkey<B>>::fk_column();
+    let mut missing: Vec<(&B, B::PrimaryKeyType)> = Vec::default();
+    for model_b in models_b {
+        let pk = model_b.primary_key_value();
+        let linked = existing.iter().any(|link| link.eq(b_fk, &pk));
+        let added = missing.iter().any(|(_, x)| *x == pk);
+        if !linked && !added {
+            missing.push((model_b, pk));
+        }
+    }
+    missing.into_iter().map(|(b, _)| b).collect()
+}
+
+// Removes the links of model_a that don't point at any of models_b
+#[maybe_async::maybe_async]
+async fn delete_stale_links<Link, A, B>(
+    conn: &dyn Client,
+    model_a: &A,
+    existing: &[Link],
+    models_b: &[B],
+) -> Result<()>
+where
+    Link: WriteToArgs + HasSchema,
+    <Link as HasSchema>::Schema: TableInfo + TableColumns,
+    Link: HasJoinTableForeignkey<A> + HasJoinTableForeignkey<B>,
+    Link: ForeignKeyPartialEq<B::PrimaryKeyType>,
+    A: WriteToArgs + HasSchema,
+    <A as HasSchema>::Schema: TableInfo + TableColumns,
+    B: PrimaryKeyValue,
+{
+    let a_fk = <Link as HasJoinTableForeignkey<A>>::fk_column();
+    let b_fk = <Link as HasJoinTableForeignkey<B>>::fk_column();
+    let wanted: Vec<B::PrimaryKeyType> = models_b.iter().map(|b| b.primary_key_value()).collect();
+    let stale: Vec<&Link> = existing
+        .iter()
+        .filter(|link| !wanted.iter().any(|pk| link.eq(b_fk, pk)))
+        .collect();
+
+    let syntax = conn.syntax();
+    let table = TableIdent::from_model::<Link>().or_schema(conn.default_schema());
+    let tablename: String = TableWriter::new(syntax).write(&table);
+    let a_pk = join_table_pk::<A>()?;
+    let col_write = ColumnWriter::new(syntax);
+    let a_fk_col = col_write.excape(a_fk);
+    let b_fk_col = col_write.excape(b_fk);
+
+    for chunk in stale.chunks(links_per_statement(syntax, 1, 1)) {
+        let mut args: ParamArgs = Vec::with_capacity(chunk.len() + 1);
+        model_a.bind(a_pk.name(), &mut args)?;
+        for link in chunk {
+            link.bind(b_fk, &mut args)?;
+        }
+        let next_param = NextParam::new(syntax);
+        let a_param = next_param.next();
+        let b_params: Vec<String> = chunk.iter().map(|_| next_param.next()).collect();
+        let sql = format!(
+            "DELETE FROM {tablename} WHERE {a_fk_col} = {a_param} AND {b_fk_col} IN ({})",
+            b_params.join(", ")
+        );
+        conn.execute(&sql, &args).await?;
+    }
+    Ok(())
+}
+
+// Writes a link from model_a to each of models_b
+#[maybe_async::maybe_async]
+async fn insert_links<Link, A, B>(conn: &dyn Client, model_a: &A, models_b: &[&B]) -> Result<()>
+where
+    Link: HasSchema + HasJoinTableForeignkey<A> + HasJoinTableForeignkey<B>,
+    <Link as HasSchema>::Schema: TableInfo + TableColumns,
+    A: WriteToArgs + HasSchema,
+    <A as HasSchema>::Schema: TableInfo + TableColumns,
+    B: WriteToArgs + HasSchema,
+    <B as HasSchema>::Schema: TableInfo + TableColumns,
+{
+    let syntax = conn.syntax();
+    let table = TableIdent::from_model::<Link>().or_schema(conn.default_schema());
+    let tablename: String = TableWriter::new(syntax).write(&table);
+    let a_pk = join_table_pk::<A>()?;
+    let b_pk = join_table_pk::<B>()?;
+    let col_write = ColumnWriter::new(syntax);
+    let a_fk = col_write.excape(<Link as HasJoinTableForeignkey<A>>::fk_column());
+    let b_fk = col_write.excape(<Link as HasJoinTableForeignkey<B>>::fk_column());
+
+    for chunk in models_b.chunks(links_per_statement(syntax, 2, 0)) {
+        let mut args: ParamArgs = Vec::with_capacity(chunk.len() * 2);
+        let next_param = NextParam::new(syntax);
+        let mut values: Vec<String> = Vec::with_capacity(chunk.len());
+        for model_b in chunk {
+            model_a.bind(a_pk.name(), &mut args)?;
+            model_b.bind(b_pk.name(), &mut args)?;
+            values.push(format!("({}, {})", next_param.next(), next_param.next()));
+        }
+        let sql = format!(
+            "INSERT INTO {tablename} ({a_fk}, {b_fk}) VALUES {}",
+            values.join(", ")
+        );
+        conn.execute(&sql, &args).await?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::WeldsModel;
+    use welds_connections::Syntax;
+
+    #[derive(Debug, Default, WeldsModel)]
+    #[welds(table = "posts")]
+    #[welds_path(crate)] // needed only within the welds crate.
+    struct Post {
+        #[welds(primary_key)]
+        pub id: i32,
+    }
+
+    #[derive(Debug, Default, WeldsModel)]
+    #[welds(table = "tags")]
+    #[welds_path(crate)] // needed only within the welds crate.
+    struct Tag {
+        #[welds(primary_key)]
+        pub id: i32,
+    }
+
+    #[derive(Debug, Default, WeldsModel)]
+    #[welds(table = "post_tags")]
+    #[welds_path(crate)] // needed only within the welds crate.
+    #[welds(JoinTable(Post, "post_id", Tag, "tag_id"))]
+    struct PostTag {
+        pub post_id: i32,
+        pub tag_id: i32,
+    }
+
+    #[test]
+    fn should_attach_each_missing_model_once_in_one_insert() {
+        futures::executor::block_on(async move {
+            let client = welds_connections::noop::build(Syntax::Postgres);
+            let post = Post { id: 1 };
+            let tags = vec![Tag { id: 2 }, Tag { id: 3 }, Tag { id: 2 }];
+            attach_many::<PostTag, _, _>(&client, &post, &tags)
+                .await
+                .unwrap();
+            let sql = client.last_sql().unwrap();
+            assert_eq!(
+                sql,
+                r#"INSERT INTO post_tags ("post_id", "tag_id") VALUES ($1, $2), ($3, $4)"#
+            );
+            assert_eq!(client.args_count(), Some(4));
+        });
+    }
+
+    #[test]
+    fn should_keep_each_insert_under_the_param_limit_of_sqlite() {
+        futures::executor::block_on(async move {
+            let client = welds_connections::noop::build(Syntax::Sqlite);
+            let post = Post { id: 1 };
+            let tags: Vec<Tag> = (0..600).map(|id| Tag { id }).collect();
+            attach_many::<PostTag, _, _>(&client, &post, &tags)
+                .await
+                .unwrap();
+            // 499 links in the first insert, the last 101 in the second
+            assert_eq!(client.args_count(), Some(202));
+        });
+    }
+
+    #[test]
+    fn should_detach_all_the_links_of_a_model() {
+        futures::executor::block_on(async move {
+            let client = welds_connections::noop::build(Syntax::Postgres);
+            let post = Post { id: 1 };
+            detach_all::<PostTag, _>(&client, &post).await.unwrap();
+            let sql = client.last_sql().unwrap();
+            assert_eq!(sql, r#"DELETE FROM post_tags WHERE "post_id" = $1"#);
+        });
+    }
+}
@@ -1,12 +1,14 @@
 use super::builder::QueryBuilder;
 use super::clause::ParamArgs;
 use crate::errors::Result;
-use crate::model_traits::{HasSchema, TableColumns, TableIdent, TableInfo};
+use crate::model_traits::{HasSchema, TableColumns, TableInfo};
 use crate::state::DbState;
 use crate::{Syntax, WeldsError};
 use welds_connections::Client;
 use welds_connections::Row;
 
+#[cfg(all(not(feature = "__sync"), feature = "unstable-api"))]
+use crate::query::clause::ClauseAdder;
 #[cfg(all(not(feature = "__sync"), feature = "unstable-api"))]
 use futures::StreamExt;
 #[cfg(all(not(feature = "__sync"), feature = "unstable-api"))]
@@ -34,7 +36,7 @@ where
         T: HasSchema,
         <T as HasSchema>::Schema: TableInfo + TableColumns,
     {
-        let table = TableIdent::from_model::<T>();
+        let table = self.table_ident();
         let writer = SelectWriter::new_with_alias(syntax, &table, &self.alias);
         writer.sql_count(
             &self.wheres,
@@ -55,16 +57,17 @@ where
         <T as HasSchema>::Schema: TableInfo + TableColumns,
     {
         let syntax = client.syntax();
+        let qb = self.for_client(client);
         let mut args: Option<ParamArgs> = Some(Vec::default());
 
-        let table = TableIdent::from_model::<T>();
-        let writer = SelectWriter::new_with_alias(syntax, &table, &self.alias);
+        let table = qb.table_ident();
+        let writer = SelectWriter::new_with_alias(syntax, &table, &qb.alias);
         let sql = writer.sql_count(
-            &self.wheres,
-            &self.exist_ins,
-            &self.limit,
-            &self.offset,
-            &self.orderby,
+            &qb.wheres,
+            &qb.exist_ins,
+            &qb.limit,
+            &qb.offset,
+            &qb.orderby,
             &mut args,
         );
 
@@ -80,7 +83,7 @@ where
     where
         <T as HasSchema>::Schema: TableInfo + TableColumns,
     {
-        let table = TableIdent::from_model::<T>();
+        let table = self.table_ident();
         let columns = <T as HasSchema>::Schema::select_columns();
         let writer = SelectWriter::new_with_alias(syntax, &table, &self.alias);
         writer.sql(
@@ -103,18 +106,19 @@ where
         WeldsError: From<<T as TryFrom<Row>>::Error>,
     {
         let syntax = client.syntax();
+        let qb = self.for_client(client);
         let mut args: Option<ParamArgs> = Some(Vec::default());
 
-        let table = TableIdent::from_model::<T>();
+        let table = qb.table_ident();
         let columns = <T as HasSchema>::Schema::select_columns();
-        let writer = SelectWriter::new_with_alias(syntax, &table, &self.alias);
+        let writer = SelectWriter::new_with_alias(syntax, &table, &qb.alias);
         let sql = writer.sql(
             &columns,
-            &self.wheres,
-            &self.exist_ins,
-            &self.limit,
-            &self.offset,
-            &self.orderby,
+            &qb.wheres,
+            &qb.exist_ins,
+            &qb.limit,
+            &qb.offset,
+            &qb.orderby,
             &mut args,
         );
 
@@ -124,7 +128,43 @@ where
         let mut objs = Vec::default();
         for row in rows {
             let obj: T = T::try_from(row)?;
-            objs.push(DbState::db_loaded(obj));
+            objs.push(DbState::db_loaded_tracked(obj).with_table(self.table.clone()));
+        }
+        Ok(objs)
+    }
+
+    /// Executes the query adding a `FOR UPDATE` to lock the selected rows.
+    /// Used to select rows ahead of changing them when the DB can't return them (MySql)
+    pub(crate) async fn run_for_update(&self, client: &dyn Client) -> Result<Vec<T>>
+    where
+        <T as HasSchema>::Schema: TableInfo + TableColumns,
+        T: TryFrom<Row>,
+        WeldsError: From<<T as TryFrom<Row>>::Error>,
+    {
+        let syntax = client.syntax();
+        let qb = self.for_client(client);
+        let mut args: Option<ParamArgs> = Some(Vec::default());
+
+        let table = qb.table_ident();
+        let columns = <T as HasSchema>::Schema::select_columns();
+        let writer = SelectWriter::new_with_alias(syntax, &table, &qb.alias);
+        let sql = writer.sql(
+            &columns,
+            &qb.wheres,
+            &qb.exist_ins,
+            &qb.limit,
+            &qb.offset,
+            &qb.orderby,
+            &mut args,
+        );
+        let sql = format!("{} FOR UPDATE", sql);
+
+        let args = args.unwrap();
+        let rows = client.fetch_rows(&sql, &args).await?;
+
+        let mut objs = Vec::default();
+        for row in rows {
+            objs.push(T::try_from(row)?);
         }
         Ok(objs)
     }
@@ -143,21 +183,29 @@ where
         C: StreamClient,
     {
         let syntax = client.syntax();
-        let mut args: Option<ParamArgs> = Some(Vec::default());
+        let qb = self.for_client(client);
 
-        let table = TableIdent::from_model::<T>();
+        let table = qb.table_ident();
         let columns = <T as HasSchema>::Schema::select_columns();
-        let writer = SelectWriter::new_with_alias(syntax, &table, &self.alias);
+        let writer = SelectWriter::new_with_alias(syntax, &table, &qb.alias);
         let sql = writer.sql(
             &columns,
-            &self.wheres,
-            &self.exist_ins,
-            &self.limit,
-            &self.offset,
-            &self.orderby,
-            &mut args,
+            &qb.wheres,
+            &qb.exist_ins,
+            &qb.limit,
+            &qb.offset,
+            &qb.orderby,
+            &mut None,
         );
-        let args = args.unwrap();
+
+        // the stream outlives the query placed in the client's schema, bind the params from self
+        let mut args: ParamArgs = Vec::default();
+        for clause in &self.wheres {
+            clause.bind(&mut args);
+        }
+        for clause in &self.exist_ins {
+            clause.bind(&mut args);
+        }
 
         let stream = client.stream(&sql, &args).await;
 
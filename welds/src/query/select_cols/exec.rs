@@ -73,8 +73,9 @@ where
     {
         trace::db_error(self.validate_group_by())?;
         let syntax = client.syntax();
+        let sb = self.for_client(client);
         let mut args: Option<ParamArgs> = Some(Vec::default());
-        let sql = self.sql_internal(syntax, &mut args);
+        let sql = sb.sql_internal(syntax, &mut args);
         let args = args.unwrap();
         let rows = client.fetch_rows(&sql, &args).await?;
         Ok(rows)
@@ -168,8 +169,7 @@ where
 
     head.push("FROM");
 
-    let parts = <T as HasSchema>::Schema::identifier();
-    let tn = TableWriter::new(syntax).write2(parts);
+    let tn = TableWriter::new(syntax).write(&sb.qb.table_ident());
 
     let alias = &sb.qb.alias;
     let identifier = format!("{} {}", tn, alias);
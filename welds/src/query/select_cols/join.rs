@@ -2,7 +2,7 @@ use super::SelectBuilder;
 use super::SelectColumn;
 use super::select_column::SelectRender;
 use crate::Syntax;
-use crate::model_traits::{HasSchema, TableInfo};
+use crate::model_traits::{HasSchema, TableIdent, TableInfo};
 use crate::query::clause::ClauseAdder;
 use crate::query::clause::ParamArgs;
 use crate::writers::ColumnWriter;
@@ -16,7 +16,7 @@ pub(crate) struct JoinBuilder {
     pub(crate) alias_asigner: Arc<TableAlias>,
     pub(crate) outer_key: String,
     pub(crate) inner_alias: String,
-    pub(crate) inner_table: &'static [&'static str],
+    pub(crate) inner_table: TableIdent,
     pub(crate) inner_key: String,
     pub(crate) wheres: Vec<Arc<Box<dyn ClauseAdder>>>,
     pub(crate) selects: Vec<SelectColumn>,
@@ -49,6 +49,14 @@ impl JoinBuilder {
         self.inner_alias = self.alias_asigner.next();
     }
 
+    // place the tables without a schema in the client's default schema
+    pub(crate) fn set_default_schema(&mut self, schema: Option<&str>) {
+        self.inner_table = self.inner_table.clone().or_schema(schema);
+        for sub in &mut self.subs {
+            sub.set_default_schema(schema);
+        }
+    }
+
     pub(super) fn append_select_renders(&self, list: &mut Vec<SelectRender>) {
         let alias = &self.inner_alias;
         // Add these columns
@@ -68,7 +76,7 @@ impl JoinBuilder {
     ) {
         let writer = ColumnWriter::new(syntax);
 
-        let inner_table = TableWriter::new(syntax).write2(self.inner_table);
+        let inner_table = TableWriter::new(syntax).write(&self.inner_table);
 
         let sql = format!(
             "{jointy} {itn} {ita} ON {ota}.{otk} = {ita}.{itk}",
@@ -112,7 +120,7 @@ impl JoinBuilder {
         T: Send + HasSchema,
         <T as HasSchema>::Schema: TableInfo,
     {
-        let tn = <T as HasSchema>::Schema::identifier();
+        let tn = sb.qb.table_ident();
 
         JoinBuilder {
             alias_asigner: sb.qb.alias_asigner.clone(),
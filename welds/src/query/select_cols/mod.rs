@@ -1,3 +1,4 @@
+use crate::Client;
 use crate::model_traits::{HasSchema, TableColumns, TableInfo};
 use crate::query::builder::QueryBuilder;
 use crate::query::clause::{AsFieldName, ClauseAdder};
@@ -8,6 +9,7 @@ use crate::writers::alias::TableAlias;
 pub use join::Join;
 use join::JoinBuilder;
 use select_column::SelectColumn;
+use std::borrow::Cow;
 use std::sync::Arc;
 
 mod exec;
@@ -326,6 +328,20 @@ where
         self
     }
 
+    /// The query with its tables placed in the client's default schema. `SchemaClient`
+    pub(crate) fn for_client(&self, client: &dyn Client) -> Cow<'_, Self> {
+        let schema = client.default_schema();
+        if schema.is_none() {
+            return Cow::Borrowed(self);
+        }
+        let mut sb = self.clone();
+        sb.qb = self.qb.for_client(client).into_owned();
+        for join in &mut sb.joins {
+            join.set_default_schema(schema);
+        }
+        Cow::Owned(sb)
+    }
+
     pub(crate) fn set_aliases(&mut self, alias_asigner: &Arc<TableAlias>) {
         self.qb.set_aliases(alias_asigner);
         for join in &mut self.joins {
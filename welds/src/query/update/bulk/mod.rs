@@ -1,8 +1,8 @@
 use crate::Client;
 use crate::Syntax;
 use crate::errors::Result;
-use crate::model_traits::UniqueIdentifier;
-use crate::model_traits::{HasSchema, TableColumns, TableInfo};
+use crate::model_traits::hooks::{AfterBulkUpdate, BeforeBulkUpdate, BulkAffected};
+use crate::model_traits::{HasSchema, TableColumns, TableIdent, TableInfo, WriteToArgs};
 use crate::query::builder::QueryBuilder;
 pub use crate::query::clause::manualparam::ManualParam;
 use crate::query::clause::wherein::WhereIn;
@@ -10,10 +10,16 @@ use crate::query::clause::{AsFieldName, AsOptField};
 use crate::query::clause::{AssignmentAdder, ClauseAdder};
 use crate::query::clause::{AssignmentManual, ParamArgs};
 use crate::query::clause::{SetColNull, SetColVal};
-use crate::query::helpers::{build_where, join_sql_parts};
+use crate::query::helpers::{build_where, build_where_ids_in, join_sql_parts};
 use crate::query::optional::Optional;
+use crate::query::select::SelectWriter;
+use crate::state::DbState;
+use crate::writers::ColumnWriter;
 use crate::writers::NextParam;
 use crate::writers::TableWriter;
+use crate::writers::{ReturningRow, ReturningWriter};
+use crate::{Row, WeldsError};
+use std::borrow::Cow;
 use std::marker::PhantomData;
 use std::sync::Arc;
 use welds_connections::Param;
@@ -25,6 +31,19 @@ pub struct UpdateBuilder<T> {
     _t: PhantomData<T>,
     pub(crate) query_builder: QueryBuilder<T>,
     pub(crate) sets: Vec<Arc<Box<dyn AssignmentAdder>>>,
+    // sets the `#[welds(updated_at)]` column, unless it is already being set
+    pub(crate) updated_at: Option<Arc<Box<dyn AssignmentAdder>>>,
+}
+
+impl<T> Clone for UpdateBuilder<T> {
+    fn clone(&self) -> Self {
+        Self {
+            _t: Default::default(),
+            query_builder: self.query_builder.clone(),
+            sets: self.sets.clone(),
+            updated_at: self.updated_at.clone(),
+        }
+    }
 }
 
 #[maybe_async::maybe_async]
@@ -33,13 +52,34 @@ where
     T: Send + HasSchema,
 {
     pub(crate) fn new(query_builder: QueryBuilder<T>) -> Self {
+        let updated_at = <T as HasSchema>::Schema::updated_at_column().map(|col| {
+            let now = AssignmentManual {
+                col: col.name(),
+                sql: "?".to_string(),
+                params: vec![col.now_param()],
+            };
+            Arc::new(Box::new(now) as Box<dyn AssignmentAdder>)
+        });
         Self {
             _t: Default::default(),
             sets: Vec::default(),
+            updated_at,
             query_builder,
         }
     }
 
+    /// All the columns being SET, including the `#[welds(updated_at)]` column
+    fn all_sets(&self) -> Vec<&Arc<Box<dyn AssignmentAdder>>> {
+        let mut sets: Vec<_> = self.sets.iter().collect();
+        if let Some(updated_at) = &self.updated_at {
+            let col = updated_at.column();
+            if !self.sets.iter().any(|s| s.column() == col) {
+                sets.push(updated_at);
+            }
+        }
+        sets
+    }
+
     /// Sets the value from the lambda in the database
     ///
     /// ```
@@ -181,14 +221,46 @@ where
         self
     }
 
+    /// Run this update on another table with the same columns as the model.
+    ///
+    /// Returns `WeldsError::InvalidTableName` if the name is not a plain table name
+    pub fn in_table(mut self, table: impl AsRef<str>) -> Result<Self> {
+        self.query_builder = self.query_builder.in_table(table)?;
+        Ok(self)
+    }
+
     /// Get a copy of the SQL that will be executed when this query runs
     pub fn to_sql(&self, syntax: Syntax) -> String
     where
-        <T as HasSchema>::Schema: UniqueIdentifier + TableInfo + TableColumns,
+        <T as HasSchema>::Schema: TableInfo + TableColumns,
+    {
+        let mut w_in = WhereIn::new(&self.query_builder);
+
+        self.sql_internal(syntax, &mut w_in, &mut None, false)
+    }
+
+    /// Get a copy of the SQL that will be executed by `run_returning`
+    ///
+    /// NOTE: MySql doesn't support returning rows, this is the plain UPDATE for MySql
+    pub fn to_sql_returning(&self, syntax: Syntax) -> String
+    where
+        <T as HasSchema>::Schema: TableInfo + TableColumns,
     {
         let mut w_in = WhereIn::new(&self.query_builder);
 
-        self.sql_internal(syntax, &mut w_in, &mut None)
+        self.sql_internal(syntax, &mut w_in, &mut None, true)
+    }
+
+    /// The update with its tables placed in the client's default schema. `SchemaClient`
+    fn for_client(&self, client: &dyn Client) -> Cow<'_, Self> {
+        match self.query_builder.for_client(client) {
+            Cow::Borrowed(_) => Cow::Borrowed(self),
+            Cow::Owned(query_builder) => {
+                let mut update = self.clone();
+                update.query_builder = query_builder;
+                Cow::Owned(update)
+            }
+        }
     }
 
     fn sql_internal<'s, 'w, 'args, 'p>(
@@ -196,20 +268,32 @@ where
         syntax: Syntax,
         w_in: &'w mut WhereIn<T>,
         args: &'args mut Option<ParamArgs<'p>>,
+        returning: bool,
     ) -> String
     where
         'w: 'p,
         's: 'p,
-        <T as HasSchema>::Schema: UniqueIdentifier + TableInfo + TableColumns,
+        <T as HasSchema>::Schema: TableInfo + TableColumns,
     {
         let next_params = NextParam::new(syntax);
-        let sets = self.sets.as_slice();
+        let sets = self.all_sets();
 
-        let alias_parts = <T as HasSchema>::Schema::identifier();
-        let alias = TableWriter::new(syntax).write2(alias_parts);
+        let table = self.query_builder.table_ident();
+        let alias = TableWriter::new(syntax).write(&table);
+
+        let columns = <T as HasSchema>::Schema::select_columns();
+        let ret_writer = ReturningWriter::new(syntax);
+        let (output, returning) = match returning {
+            true => (
+                ret_writer.output(ReturningRow::Inserted, &columns),
+                ret_writer.returning(&columns),
+            ),
+            false => (None, None),
+        };
 
         join_sql_parts(&[
-            build_head::<<T as HasSchema>::Schema>(syntax, &next_params, &alias, args, sets),
+            build_head(syntax, &next_params, &alias, args, &sets),
+            output,
             build_where_update(
                 syntax,
                 w_in,
@@ -218,46 +302,220 @@ where
                 args,
                 &self.query_builder,
             ),
+            returning,
         ])
     }
 
     /// Executes the query in the database Bulk updating the values
+    ///
+    /// Calls the model's `BeforeBulkUpdate` and `AfterBulkUpdate` hooks
     pub async fn run(&self, client: &dyn Client) -> Result<u64>
     where
-        <T as HasSchema>::Schema: UniqueIdentifier + TableInfo + TableColumns,
+        <T as HasSchema>::Schema: TableInfo + TableColumns,
+        T: BeforeBulkUpdate + AfterBulkUpdate,
+    {
+        <T as BeforeBulkUpdate>::before(self).await?;
+        let count = self.execute(client).await?;
+        <T as AfterBulkUpdate>::after(self, &BulkAffected::Count(count)).await?;
+        Ok(count)
+    }
+
+    /// Runs the UPDATE without calling any hooks
+    pub(crate) async fn execute(&self, client: &dyn Client) -> Result<u64>
+    where
+        <T as HasSchema>::Schema: TableInfo + TableColumns,
     {
         let syntax = client.syntax();
+        let update = self.for_client(client);
         let mut args: Option<ParamArgs> = Some(Vec::default());
-        let mut w_in = WhereIn::new(&self.query_builder);
-        let sql = self.sql_internal(syntax, &mut w_in, &mut args);
+        let mut w_in = WhereIn::new(&update.query_builder);
+        let sql = update.sql_internal(syntax, &mut w_in, &mut args, false);
         let args = args.unwrap();
         let results = client.execute(&sql, &args).await?;
 
         Ok(results.rows_affected())
     }
+
+    /// Executes the query in the database Bulk updating the values.
+    /// Returns the updated rows
+    ///
+    /// Postgres/Sqlite use `RETURNING`, MSSQL uses `OUTPUT INSERTED.*`
+    ///
+    /// MySql can't return rows from an UPDATE. The rows are selected `FOR UPDATE`,
+    /// updated by their id, then re-read by the keys they have after the update.
+    /// This is done in a transaction, unless the client is already a transaction.
+    ///
+    /// Calls the model's `BeforeBulkUpdate` and `AfterBulkUpdate` hooks, the after hook gets the rows
+    pub async fn run_returning(&self, client: &dyn Client) -> Result<Vec<DbState<T>>>
+    where
+        <T as HasSchema>::Schema: TableInfo + TableColumns,
+        T: TryFrom<Row> + WriteToArgs + BeforeBulkUpdate + AfterBulkUpdate,
+        WeldsError: From<<T as TryFrom<Row>>::Error>,
+    {
+        <T as BeforeBulkUpdate>::before(self).await?;
+        let rows = self.execute_returning(client).await?;
+        <T as AfterBulkUpdate>::after(self, &BulkAffected::Rows(&rows)).await?;
+        Ok(rows)
+    }
+
+    /// Runs the UPDATE returning the rows without calling any hooks
+    pub(crate) async fn execute_returning(&self, client: &dyn Client) -> Result<Vec<DbState<T>>>
+    where
+        <T as HasSchema>::Schema: TableInfo + TableColumns,
+        T: TryFrom<Row> + WriteToArgs,
+        WeldsError: From<<T as TryFrom<Row>>::Error>,
+    {
+        let syntax = client.syntax();
+        if !ReturningWriter::new(syntax).supported() {
+            return self.run_returning_fallback(client).await;
+        }
+
+        let update = self.for_client(client);
+        let mut args: Option<ParamArgs> = Some(Vec::default());
+        let mut w_in = WhereIn::new(&update.query_builder);
+        let sql = update.sql_internal(syntax, &mut w_in, &mut args, true);
+        let args = args.unwrap();
+        let rows = client.fetch_rows(&sql, &args).await?;
+
+        let mut objs = Vec::default();
+        for row in rows {
+            let obj: T = T::try_from(row)?;
+            objs.push(DbState::db_loaded_tracked(obj).with_table(self.query_builder.table.clone()));
+        }
+        Ok(objs)
+    }
+
+    /// select-then-update for databases without RETURNING
+    async fn run_returning_fallback(&self, client: &dyn Client) -> Result<Vec<DbState<T>>>
+    where
+        <T as HasSchema>::Schema: TableInfo + TableColumns,
+        T: TryFrom<Row> + WriteToArgs,
+        WeldsError: From<<T as TryFrom<Row>>::Error>,
+    {
+        // the rows are locked until the transaction is committed
+        let trans = client.begin_if_needed().await?;
+        let conn: &dyn Client = match &trans {
+            Some(trans) => trans,
+            None => client,
+        };
+
+        let syntax = conn.syntax();
+        let found = self.query_builder.run_for_update(conn).await?;
+        if found.is_empty() {
+            return Ok(Vec::default());
+        }
+
+        let table = self.query_builder.table_ident();
+        let table = table.or_schema(conn.default_schema());
+        let moved = self.keys_after_update(conn, &table, &found).await?;
+
+        // update the rows we found by their id
+        let alias = TableWriter::new(syntax).write(&table);
+        let next_params = NextParam::new(syntax);
+        let mut args: Option<ParamArgs> = Some(Vec::default());
+        let sets = self.all_sets();
+        let head = build_head(syntax, &next_params, &alias, &mut args, &sets);
+        let mut args = args.unwrap();
+        let wheres = build_where_ids_in(syntax, &next_params, &alias, &found, &mut args)?;
+        let sql = join_sql_parts(&[head, wheres]);
+        conn.execute(&sql, &args).await?;
+
+        // re-read the rows with their new values
+        let keys = moved.as_deref().unwrap_or(&found);
+        let columns = <T as HasSchema>::Schema::select_columns();
+        let select = SelectWriter::new_with_alias(syntax, &table, &self.query_builder.alias);
+        let head = select.sql(&columns, &[], &[], &None, &None, &[], &mut None);
+        let next_params = NextParam::new(syntax);
+        let mut args: ParamArgs = Vec::default();
+        let select_alias = &self.query_builder.alias;
+        let wheres = build_where_ids_in(syntax, &next_params, select_alias, keys, &mut args)?;
+        let sql = join_sql_parts(&[Some(head), wheres]);
+        let rows = conn.fetch_rows(&sql, &args).await?;
+
+        let mut objs = Vec::default();
+        for row in rows {
+            let obj: T = T::try_from(row)?;
+            objs.push(DbState::db_loaded_tracked(obj).with_table(self.query_builder.table.clone()));
+        }
+        if let Some(trans) = trans {
+            trans.commit().await?;
+        }
+        Ok(objs)
+    }
+
+    /// When the update assigns a primary key, selects the rows with the keys they will have
+    /// after the update. `None` when the keys are not changed.
+    async fn keys_after_update(
+        &self,
+        client: &dyn Client,
+        table: &TableIdent,
+        found: &[T],
+    ) -> Result<Option<Vec<T>>>
+    where
+        <T as HasSchema>::Schema: TableInfo + TableColumns,
+        T: TryFrom<Row> + WriteToArgs,
+        WeldsError: From<<T as TryFrom<Row>>::Error>,
+    {
+        let sets = self.all_sets();
+        let pks = <T as HasSchema>::Schema::primary_keys();
+        let is_pk = |name: Option<&str>| pks.iter().any(|pk| name == Some(pk.name()));
+        if !sets.iter().any(|set| is_pk(set.column())) {
+            return Ok(None);
+        }
+
+        let syntax = client.syntax();
+        let writer = ColumnWriter::new(syntax);
+        let alias = &self.query_builder.alias;
+        let next_params = NextParam::new(syntax);
+        let mut args: ParamArgs = Vec::default();
+        let mut cols = Vec::default();
+        for col in <T as HasSchema>::Schema::select_columns() {
+            // the last assignment to a column is the one that sticks
+            let set = sets
+                .iter()
+                .rev()
+                .find(|set| set.column() == Some(col.name()));
+            match set {
+                Some(set) if is_pk(Some(col.name())) => {
+                    set.bind(&mut args);
+                    let value = set.value(syntax, alias, &next_params);
+                    cols.push(format!("{} AS {}", value, writer.excape(col.name())));
+                }
+                _ => cols.push(writer.write(alias, &col)),
+            }
+        }
+        let tn = TableWriter::new(syntax).write(table);
+        let head = format!("SELECT {} FROM {} {}", cols.join(", "), tn, alias);
+        let wheres = build_where_ids_in(syntax, &next_params, alias, found, &mut args)?;
+        let sql = join_sql_parts(&[Some(head), wheres]);
+        let rows = client.fetch_rows(&sql, &args).await?;
+
+        let mut objs = Vec::default();
+        for row in rows {
+            objs.push(T::try_from(row)?);
+        }
+        Ok(Some(objs))
+    }
 }
 
-fn build_head<'s, 'args, 'p, S>(
+// the alias of an UPDATE is the full name of the table
+fn build_head<'s, 'args, 'p>(
     syntax: Syntax,
     next_params: &NextParam,
-    alias: &str,
+    tn: &str,
     args: &'args mut Option<ParamArgs<'p>>,
-    sets: &'s [Arc<Box<dyn AssignmentAdder>>],
+    sets: &[&'s Arc<Box<dyn AssignmentAdder>>],
 ) -> Option<String>
 where
     's: 'p,
-    S: TableInfo + TableColumns,
 {
-    let parts = S::identifier();
-    let tn = TableWriter::new(syntax).write2(parts);
-
     let mut set_parts: Vec<String> = Vec::default();
 
     for clause in sets {
         if let Some(args) = args {
             clause.bind(args);
         }
-        if let Some(p) = clause.clause(syntax, alias, next_params) {
+        if let Some(p) = clause.clause(syntax, tn, next_params) {
             set_parts.push(p);
         }
     }
@@ -278,7 +536,7 @@ where
     'q: 'p,
     'w: 'p,
     T: HasSchema,
-    <T as HasSchema>::Schema: UniqueIdentifier + TableInfo + TableColumns,
+    <T as HasSchema>::Schema: TableInfo + TableColumns,
 {
     // If we have a limit, we need to wrap the wheres in an IN clause
     // this is to limit the number of row to that will be updated
@@ -294,8 +552,7 @@ where
     }
 
     // use fulltable name for alias when updating
-    let tableparts = T::Schema::identifier();
-    let outer_tablealias = TableWriter::new(syntax).write2(tableparts);
+    let outer_tablealias = TableWriter::new(syntax).write(&qb.table_ident());
 
     if let Some(p) = w_in.clause(syntax, &outer_tablealias, next_params) {
         where_sql.push(p);
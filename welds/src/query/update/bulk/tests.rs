@@ -16,6 +16,17 @@ struct Product {
     pub b: i32,
 }
 
+#[derive(Debug, Default, WeldsModel)]
+#[welds(table = "posts")]
+#[welds_path(crate)] // needed only within the welds crate.
+struct Post {
+    #[welds(primary_key)]
+    pub id: i32,
+    pub title: String,
+    #[welds(updated_at)]
+    pub updated_at: String,
+}
+
 // Tests
 
 #[test]
@@ -104,3 +115,117 @@ fn should_be_able_to_write_a_custom_set_statement() {
         assert_eq!(expected, &ran_sql);
     });
 }
+
+#[test]
+fn should_be_able_to_write_update_returning_postgres() {
+    futures::executor::block_on(async move {
+        let q = QueryBuilder::<Product>::new().where_col(|c| c.id.gt(10));
+        let bulk = q.set(|p| p.a, 1);
+
+        let client = welds_connections::noop::build(Syntax::Postgres);
+        let _ = bulk.run_returning(&client).await;
+
+        let ran_sql = client
+            .last_sql()
+            .expect("Expected to get SQL back from client");
+
+        let expected =
+            "UPDATE nums SET \"a\"=$1 WHERE ( nums.\"id\" > $2 ) RETURNING \"id\", \"a\", \"b\"";
+        assert_eq!(expected, &ran_sql);
+        assert_eq!(client.args_count().unwrap(), 2);
+    });
+}
+
+#[test]
+fn should_be_able_to_write_update_returning_mssql() {
+    let q = QueryBuilder::<Product>::new().where_col(|c| c.id.gt(10));
+    let bulk = q.set(|p| p.a, 1);
+    let sql = bulk.to_sql_returning(Syntax::Mssql);
+    let expected = "UPDATE nums SET \"a\"=@p1 OUTPUT INSERTED.\"id\", INSERTED.\"a\", INSERTED.\"b\" WHERE ( nums.\"id\" > @p2 )";
+    assert_eq!(expected, &sql);
+}
+
+#[test]
+fn should_select_rows_for_update_when_returning_on_mysql() {
+    futures::executor::block_on(async move {
+        let q = QueryBuilder::<Product>::new().where_col(|c| c.id.gt(10));
+        let bulk = q.set(|p| p.a, 1);
+
+        let client = welds_connections::noop::build(Syntax::Mysql);
+        let rows = bulk.run_returning(&client).await.unwrap();
+        assert!(rows.is_empty());
+
+        let ran_sql = client
+            .last_sql()
+            .expect("Expected to get SQL back from client");
+
+        let expected = "SELECT t1.id, t1.a, t1.b FROM nums t1 WHERE ( t1.id > ? ) FOR UPDATE";
+        assert_eq!(expected, &ran_sql);
+    });
+}
+
+#[test]
+fn should_select_the_new_keys_when_an_update_changes_the_primary_key_on_mysql() {
+    futures::executor::block_on(async move {
+        let q = QueryBuilder::<Product>::new().where_col(|c| c.a.equal(1));
+        let bulk = q.set(|p| p.id, 20);
+        let table = bulk.query_builder.table_ident();
+        let found = vec![Product { id: 5, a: 1, b: 2 }];
+
+        let client = welds_connections::noop::build(Syntax::Mysql);
+        let moved = bulk.keys_after_update(&client, &table, &found).await;
+        assert!(moved.unwrap().unwrap().is_empty());
+
+        let ran_sql = client.last_sql().unwrap();
+        let expected = "SELECT ? AS id, t1.a, t1.b FROM nums t1 WHERE ( t1.id IN (?) )";
+        assert_eq!(expected, &ran_sql);
+        assert_eq!(client.args_count().unwrap(), 2);
+    });
+}
+
+#[test]
+fn should_not_select_new_keys_when_the_primary_key_is_not_changed() {
+    futures::executor::block_on(async move {
+        let q = QueryBuilder::<Product>::new().where_col(|c| c.a.equal(1));
+        let bulk = q.set(|p| p.a, 2);
+        let table = bulk.query_builder.table_ident();
+        let found = vec![Product { id: 5, a: 1, b: 2 }];
+
+        let client = welds_connections::noop::build(Syntax::Mysql);
+        let moved = bulk.keys_after_update(&client, &table, &found).await;
+        assert!(moved.unwrap().is_none());
+        assert!(client.last_sql().is_none());
+    });
+}
+
+#[test]
+fn should_set_updated_at_on_bulk_update() {
+    futures::executor::block_on(async move {
+        let q = QueryBuilder::<Post>::new();
+        let bulk = q.set(|p| p.title, "new".to_owned());
+
+        let client = welds_connections::noop::build(Syntax::Postgres);
+        let _ = bulk.run(&client).await;
+        let ran_sql = client.last_sql().unwrap();
+
+        let expected = r#"UPDATE posts SET "title"=$1, "updated_at" = ( $2 )"#;
+        assert_eq!(expected, &ran_sql);
+        assert_eq!(client.args_count().unwrap(), 2);
+    });
+}
+
+#[test]
+fn should_not_set_updated_at_twice() {
+    futures::executor::block_on(async move {
+        let q = QueryBuilder::<Post>::new();
+        let bulk = q.set(|p| p.updated_at, "then".to_owned());
+
+        let client = welds_connections::noop::build(Syntax::Postgres);
+        let _ = bulk.run(&client).await;
+        let ran_sql = client.last_sql().unwrap();
+
+        let expected = r#"UPDATE posts SET "updated_at"=$1"#;
+        assert_eq!(expected, &ran_sql);
+        assert_eq!(client.args_count().unwrap(), 1);
+    });
+}
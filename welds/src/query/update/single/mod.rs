@@ -1,49 +1,89 @@
 use crate::errors::{Result, WeldsError};
 use crate::model_traits::hooks::{AfterUpdate, BeforeUpdate};
-use crate::model_traits::{HasSchema, TableColumns, TableInfo, UpdateFromRow, WriteToArgs};
+use crate::model_traits::{AutoTimestamps, ChangeSet, HasVersion, Snapshot, TrackChanges};
+use crate::model_traits::{HasSchema, TableColumns, TableIdent, TableInfo};
+use crate::model_traits::{UpdateFromRow, WriteToArgs};
 use crate::query::clause::ParamArgs;
+use crate::query::helpers::{build_select_by_pks, db_managed_columns, join_sql_parts};
 use crate::writers::ColumnWriter;
 use crate::writers::NextParam;
 use crate::writers::TableWriter;
+use crate::writers::{ReturningRow, ReturningWriter};
 use welds_connections::Client;
 
+/// Updates the columns of a model that have changed since the snapshot was taken.
+/// Without a snapshot all the columns are written
+///
+/// The `#[welds(updated_at)]` column is set to now.
+/// `#[welds(db_default)]` and `#[welds(generated)]` columns are read back from the database.
+///
+/// Versioned models only update the row if its version hasn't moved since it was loaded.
+/// The version is moved forward, or `WeldsError::StaleObject` is returned
 #[maybe_async::maybe_async]
-pub async fn update_one<T>(obj: &mut T, client: &dyn Client) -> Result<()>
+pub async fn update_one<T>(
+    obj: &mut T,
+    snapshot: Option<&Snapshot>,
+    table: &TableIdent,
+    client: &dyn Client,
+) -> Result<()>
 where
     T: WriteToArgs + HasSchema,
     <T as HasSchema>::Schema: TableInfo + TableColumns,
-    T: UpdateFromRow,
+    T: UpdateFromRow + TrackChanges + HasVersion + AutoTimestamps,
     T: AfterUpdate + BeforeUpdate,
 {
+    let changes = ChangeSet::new(obj, snapshot);
+    if changes.is_empty() {
+        return Ok(());
+    }
+    BeforeUpdate::before_with_changes(obj, &changes).await?;
     BeforeUpdate::before(obj).await?;
+    // The hooks are allowed to edit the model, re-check what changed
+    let changes = ChangeSet::new(obj, snapshot);
 
     let syntax = client.syntax();
-    let mut args: ParamArgs = Vec::default();
     let col_writer = ColumnWriter::new(syntax);
     let next_params = NextParam::new(syntax);
 
-    let parts = <<T as HasSchema>::Schema>::identifier();
-    let identifier = TableWriter::new(syntax).write2(parts);
+    let identifier = TableWriter::new(syntax).write(table);
 
     let columns = <<T as HasSchema>::Schema as TableColumns>::update_columns();
     let pks = <<T as HasSchema>::Schema as TableColumns>::primary_keys();
     if pks.is_empty() {
         return Err(WeldsError::NoPrimaryKey);
     }
-    let mut sets = Vec::default();
 
-    for col in columns {
-        if !pks.contains(&col) {
-            obj.bind(col.name(), &mut args)?;
-            let p = next_params.next();
-            let colname = col_writer.excape(col.name());
-            sets.push(format!("{}={}", colname, p));
+    let version_col = T::version_column();
+    let mut set_columns: Vec<&str> = columns
+        .iter()
+        .filter(|col| !pks.contains(col) && changes.is_changed(col.name()))
+        .map(|col| col.name())
+        .filter(|name| Some(*name) != version_col)
+        .collect();
+    if set_columns.is_empty() {
+        return Ok(());
+    }
+    set_columns.extend(version_col);
+
+    if let Some(col) = <T as HasSchema>::Schema::updated_at_column() {
+        obj.touch_updated_at();
+        if !set_columns.contains(&col.name()) {
+            set_columns.push(col.name());
         }
     }
 
-    if sets.is_empty() {
-        return Ok(());
+    // Move the version forward, the old version is used to find the row
+    let old_version = obj.bump_version();
+
+    let mut args: ParamArgs = Vec::default();
+    let mut sets = Vec::default();
+    for col in set_columns {
+        obj.bind(col, &mut args)?;
+        let p = next_params.next();
+        let colname = col_writer.excape(col);
+        sets.push(format!("{}={}", colname, p));
     }
+
     let mut wheres = Vec::default();
     for col in pks {
         obj.bind(col.name(), &mut args)?;
@@ -51,15 +91,73 @@ where
         let colname = col_writer.excape(col.name());
         wheres.push(format!("{}={}", colname, p));
     }
+    if let (Some(col), Some(old)) = (version_col, &old_version) {
+        args.push(old.as_param());
+        let p = next_params.next();
+        let colname = col_writer.excape(col);
+        wheres.push(format!("{}={}", colname, p));
+    }
 
-    let sets = sets.join(", ");
-    let wheres = wheres.join(" AND ");
+    // Read back the columns the database wrote, in the UPDATE itself when possible
+    let refresh = db_managed_columns::<<T as HasSchema>::Schema>();
+    let ret_writer = ReturningWriter::new(syntax);
+    let returning = !refresh.is_empty() && ret_writer.supported();
+    let (output, returning_sql) = match returning {
+        true => (
+            ret_writer.output(ReturningRow::Inserted, &refresh),
+            ret_writer.returning(&refresh),
+        ),
+        false => (None, None),
+    };
 
-    let sql = format!("UPDATE {} SET {} where {}", identifier, sets, wheres);
+    let sql = join_sql_parts(&[
+        Some(format!("UPDATE {} SET {}", identifier, sets.join(", "))),
+        output,
+        Some(format!("where {}", wheres.join(" AND "))),
+        returning_sql,
+    ]);
 
-    client.execute(&sql, &args).await?;
+    let result = match returning {
+        true => client
+            .fetch_rows(&sql, &args)
+            .await
+            .map(|mut rows| (rows.len() as u64, rows.pop())),
+        false => client
+            .execute(&sql, &args)
+            .await
+            .map(|r| (r.rows_affected(), None)),
+    };
+    if let Some(old) = old_version {
+        match result {
+            Ok((affected, _)) if affected > 0 => {}
+            Ok(_) => {
+                obj.restore_version(old);
+                return Err(WeldsError::StaleObject);
+            }
+            Err(err) => {
+                obj.restore_version(old);
+                return Err(err.into());
+            }
+        }
+    }
+    let (_, row) = result?;
+
+    // MySql can't return rows from an UPDATE, select them instead
+    let row = match row {
+        Some(row) => Some(row),
+        None if !refresh.is_empty() && !returning => {
+            let mut args: ParamArgs = Vec::default();
+            let sql = build_select_by_pks(syntax, obj, table, &refresh, &mut args)?;
+            client.fetch_rows(&sql, &args).await?.pop()
+        }
+        None => None,
+    };
+    if let Some(mut row) = row {
+        UpdateFromRow::update_from_row(obj, &mut row)?;
+    }
 
     AfterUpdate::after(obj).await.ok();
+    AfterUpdate::after_commit(obj, client);
     Ok(())
 }
 
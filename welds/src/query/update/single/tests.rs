@@ -15,13 +15,61 @@ struct Product {
     pub b: i32,
 }
 
+#[derive(Debug, Default, WeldsModel)]
+#[welds(table = "order_lines")]
+#[welds_path(crate)] // needed only within the welds crate.
+struct OrderLine {
+    #[welds(primary_key)]
+    pub order_id: i32,
+    #[welds(primary_key)]
+    pub line_no: i32,
+    pub sku: String,
+}
+
+#[derive(Debug, Default, WeldsModel)]
+#[welds(table = "accounts")]
+#[welds_path(crate)] // needed only within the welds crate.
+struct Account {
+    #[welds(primary_key)]
+    pub id: i32,
+    pub balance: i32,
+    #[welds(version)]
+    pub lock_version: i32,
+}
+
+#[derive(Debug, Default, WeldsModel)]
+#[welds(table = "posts")]
+#[welds_path(crate)] // needed only within the welds crate.
+struct Post {
+    #[welds(primary_key)]
+    pub id: i32,
+    pub title: String,
+    #[welds(created_at)]
+    pub created_at: String,
+    #[welds(updated_at)]
+    pub updated_at: String,
+}
+
+#[derive(Debug, Default, WeldsModel)]
+#[welds(table = "orders")]
+#[welds_path(crate)] // needed only within the welds crate.
+struct Order {
+    #[welds(primary_key)]
+    pub id: i32,
+    pub qty: i32,
+    #[welds(db_default)]
+    pub code: String,
+    #[welds(generated)]
+    pub total: i32,
+}
+
 // Tests
 
 #[test]
 fn no_changes_should_do_nothing() {
     futures::executor::block_on(async move {
         let obj = Product::default();
-        let mut obj = DbState::db_loaded(obj);
+        let mut obj = DbState::db_loaded_tracked(obj);
         let client = welds_connections::noop::build(Syntax::Postgres);
         let _ = obj.save(&client).await;
         let ran_sql = client.last_sql();
@@ -33,14 +81,182 @@ fn no_changes_should_do_nothing() {
 fn changing_a_value_should_update() {
     futures::executor::block_on(async move {
         let obj = Product::default();
-        let mut obj = DbState::db_loaded(obj);
+        let mut obj = DbState::db_loaded_tracked(obj);
         obj.a = 42;
         let client = welds_connections::noop::build(Syntax::Postgres);
         let _ = obj.save(&client).await;
         let ran_sql = client
             .last_sql()
             .expect("Expected to get SQL back from client");
-        let expected = "UPDATE nums SET \"a\"=$1, \"b\"=$2 where \"id\"=$3";
+        let expected = "UPDATE nums SET \"a\"=$1 where \"id\"=$2";
+        assert_eq!(expected, &ran_sql);
+    });
+}
+
+#[test]
+fn composite_key_update_should_find_the_row_by_every_key() {
+    futures::executor::block_on(async move {
+        let mut obj = DbState::db_loaded_tracked(OrderLine::default());
+        obj.sku = "abc".to_owned();
+        let client = welds_connections::noop::build(Syntax::Postgres);
+        let _ = obj.save(&client).await;
+        let ran_sql = client.last_sql().unwrap();
+        let expected = "UPDATE order_lines SET \"sku\"=$1 where \"order_id\"=$2 AND \"line_no\"=$3";
+        assert_eq!(expected, &ran_sql);
+    });
+}
+
+#[test]
+fn changing_a_value_back_should_do_nothing() {
+    futures::executor::block_on(async move {
+        let obj = Product::default();
+        let mut obj = DbState::db_loaded_tracked(obj);
+        obj.a = 42;
+        obj.a = 0;
+        let client = welds_connections::noop::build(Syntax::Postgres);
+        let _ = obj.save(&client).await;
+        let ran_sql = client.last_sql();
+        assert!(ran_sql.is_none());
+    });
+}
+
+#[test]
+fn changes_should_list_the_changed_columns() {
+    let obj = Product::default();
+    let mut obj = DbState::db_loaded_tracked(obj);
+    assert!(obj.changes().is_empty());
+    obj.b = 7;
+    let changes = obj.changes();
+    assert_eq!(changes.changed_columns(), vec!["b"]);
+    assert_eq!(changes.old_value::<i32>("b"), Some(&0));
+    assert_eq!(changes.new_value::<i32>("b"), Some(&7));
+}
+
+#[test]
+fn replacing_the_inner_model_should_update_the_changed_columns() {
+    futures::executor::block_on(async move {
+        let obj = Product::default();
+        let mut obj = DbState::db_loaded_tracked(obj);
+        obj.replace_inner(Product { id: 0, a: 0, b: 3 });
+        let client = welds_connections::noop::build(Syntax::Postgres);
+        let _ = obj.save(&client).await;
+        let ran_sql = client
+            .last_sql()
+            .expect("Expected to get SQL back from client");
+        let expected = "UPDATE nums SET \"b\"=$1 where \"id\"=$2";
+        assert_eq!(expected, &ran_sql);
+    });
+}
+
+#[test]
+fn versioned_update_should_check_and_bump_the_version() {
+    futures::executor::block_on(async move {
+        let obj = Account {
+            id: 1,
+            balance: 0,
+            lock_version: 4,
+        };
+        let mut obj = DbState::db_loaded_tracked(obj);
+        obj.balance = 10;
+        let client = welds_connections::noop::build(Syntax::Postgres);
+        let _ = obj.save(&client).await;
+        let ran_sql = client
+            .last_sql()
+            .expect("Expected to get SQL back from client");
+        let expected = "UPDATE accounts SET \"balance\"=$1, \"lock_version\"=$2 where \"id\"=$3 AND \"lock_version\"=$4";
+        assert_eq!(expected, &ran_sql);
+        assert_eq!(client.args_count(), Some(4));
+    });
+}
+
+#[test]
+fn versioned_update_without_affected_rows_should_be_stale() {
+    futures::executor::block_on(async move {
+        let obj = Account {
+            id: 1,
+            balance: 0,
+            lock_version: 4,
+        };
+        let mut obj = DbState::db_loaded_tracked(obj);
+        obj.balance = 10;
+        // The noop client never affects any rows
+        let client = welds_connections::noop::build(Syntax::Postgres);
+        let result = obj.save(&client).await;
+        assert!(matches!(result, Err(crate::WeldsError::StaleObject)));
+        // the version is put back so the model can be reloaded or retried
+        assert_eq!(obj.lock_version, 4);
+    });
+}
+
+#[test]
+fn versioned_update_without_changes_should_do_nothing() {
+    futures::executor::block_on(async move {
+        let obj = Account::default();
+        let mut obj = DbState::db_loaded_tracked(obj);
+        obj.balance = 0;
+        let client = welds_connections::noop::build(Syntax::Postgres);
+        let result = obj.save(&client).await;
+        assert!(result.is_ok());
+        assert!(client.last_sql().is_none());
+    });
+}
+
+#[test]
+fn should_set_updated_at_on_update() {
+    futures::executor::block_on(async move {
+        let obj = Post::default();
+        let mut obj = DbState::db_loaded_tracked(obj);
+        obj.title = "new".to_owned();
+        let client = welds_connections::noop::build(Syntax::Postgres);
+        let _ = obj.save(&client).await;
+        let ran_sql = client.last_sql().unwrap();
+
+        let expected = r#"UPDATE posts SET "title"=$1, "updated_at"=$2 where "id"=$3"#;
+        assert_eq!(expected, &ran_sql);
+        assert_eq!(obj.updated_at, "now");
+        assert_eq!(obj.created_at, "");
+    });
+}
+
+#[test]
+fn should_not_touch_updated_at_without_changes() {
+    futures::executor::block_on(async move {
+        let obj = Post::default();
+        let mut obj = DbState::db_loaded_tracked(obj);
+        let client = welds_connections::noop::build(Syntax::Postgres);
+        let _ = obj.save(&client).await;
+        assert!(client.last_sql().is_none());
+        assert_eq!(obj.updated_at, "");
+    });
+}
+
+#[test]
+fn should_return_db_columns_from_the_update() {
+    futures::executor::block_on(async move {
+        let obj = Order::default();
+        let mut obj = DbState::db_loaded_tracked(obj);
+        obj.qty = 3;
+        obj.code = "ignored".to_owned();
+        let client = welds_connections::noop::build(Syntax::Postgres);
+        let _ = obj.save(&client).await;
+        let ran_sql = client.last_sql().unwrap();
+
+        let expected = r#"UPDATE orders SET "qty"=$1 where "id"=$2 RETURNING "code", "total""#;
+        assert_eq!(expected, &ran_sql);
+    });
+}
+
+#[test]
+fn should_reselect_db_columns_without_returning() {
+    futures::executor::block_on(async move {
+        let obj = Order::default();
+        let mut obj = DbState::db_loaded_tracked(obj);
+        obj.qty = 3;
+        let client = welds_connections::noop::build(Syntax::Mysql);
+        let _ = obj.save(&client).await;
+        let ran_sql = client.last_sql().unwrap();
+
+        let expected = "SELECT code, total FROM orders where id=?";
         assert_eq!(expected, &ran_sql);
     });
 }
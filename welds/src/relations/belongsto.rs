@@ -56,8 +56,6 @@ where
     Self: Relationship<T, R>,
     R: PrimaryKeyValue + HasSchema,
     R::Schema: UniqueIdentifier,
-    T: HasSchema,
-    T::Schema: UniqueIdentifier,
     T: ForeignKeyPartialEq<R::PrimaryKeyType>,
 {
     fn is_related(&self, source: &T, other: &R) -> bool {
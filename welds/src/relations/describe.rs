@@ -0,0 +1,144 @@
+//! Type erased descriptions of the relationships on a model.
+//!
+//! Written by the Welds Macros in `HasRelations::describe_relation`,
+//! used to walk a `DataSet` without knowing the types of the included models.
+//!
+//! The `Probe` traits pick the best description for each model at compile time,
+//! falling back to `None` when a model doesn't implement a trait.
+
+use super::{HasRelations, RelationshipCompare};
+use std::any::Any;
+use std::marker::PhantomData;
+
+/// Describes the relationship behind a related model
+pub type DescribeFn = fn(&dyn Any) -> Option<RelationInfo>;
+
+/// Compares (ship, source, other), true if other is related to source
+pub type CompareFn = fn(&dyn Any, &dyn Any, &dyn Any) -> bool;
+
+/// Serializes a related model into JSON. Empty without the `serde` feature
+#[derive(Clone, Copy, Default)]
+pub struct JsonFn {
+    #[cfg(feature = "serde")]
+    pub(crate) to_value: Option<fn(&dyn Any) -> Option<serde_json::Value>>,
+}
+
+/// A relationship field of a model's `HasRelations::Relation`
+// only read when serializing a DataSet
+#[cfg_attr(not(feature = "serde"), allow(dead_code))]
+pub struct RelationInfo {
+    pub(crate) name: &'static str,
+    pub(crate) compare: Option<CompareFn>,
+    pub(crate) json: JsonFn,
+    pub(crate) describe: Option<DescribeFn>,
+}
+
+impl RelationInfo {
+    pub fn new(
+        name: &'static str,
+        compare: Option<CompareFn>,
+        json: JsonFn,
+        describe: Option<DescribeFn>,
+    ) -> Self {
+        Self {
+            name,
+            compare,
+            json,
+            describe,
+        }
+    }
+
+    /// The name of the field on `HasRelations::Relation`
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+}
+
+/// Probes `X` for the traits needed to describe a relationship.
+/// call methods on `&&Probe` to prefer the specific traits over `ProbeFallback`
+pub struct Probe<X>(PhantomData<X>);
+
+impl<X> Probe<X> {
+    #[allow(clippy::new_without_default)]
+    pub fn new() -> Self {
+        Self(PhantomData)
+    }
+}
+
+/// Used when the probed model doesn't implement the trait
+pub trait ProbeFallback {
+    fn describe_fn(&self) -> Option<DescribeFn> {
+        None
+    }
+    fn compare_fn(&self) -> Option<CompareFn> {
+        None
+    }
+    fn json_fn(&self) -> JsonFn {
+        JsonFn::default()
+    }
+}
+
+impl<X> ProbeFallback for Probe<X> {}
+
+/// Used when the probed model has relationships of its own
+pub trait ProbeRelations {
+    fn describe_fn(&self) -> Option<DescribeFn>;
+}
+
+impl<R: HasRelations> ProbeRelations for &Probe<R> {
+    fn describe_fn(&self) -> Option<DescribeFn> {
+        Some(R::describe_relation)
+    }
+}
+
+/// Used when the probed (Model, Related, Ship) can be compared
+pub trait ProbeCompare {
+    fn compare_fn(&self) -> Option<CompareFn>;
+}
+
+impl<T, R, Ship> ProbeCompare for &Probe<(T, R, Ship)>
+where
+    T: 'static,
+    R: 'static,
+    Ship: 'static + RelationshipCompare<T, R>,
+{
+    fn compare_fn(&self) -> Option<CompareFn> {
+        Some(compare::<T, R, Ship>)
+    }
+}
+
+fn compare<T, R, Ship>(ship: &dyn Any, source: &dyn Any, other: &dyn Any) -> bool
+where
+    T: 'static,
+    R: 'static,
+    Ship: 'static + RelationshipCompare<T, R>,
+{
+    match (
+        ship.downcast_ref::<Ship>(),
+        source.downcast_ref::<T>(),
+        other.downcast_ref::<R>(),
+    ) {
+        (Some(ship), Some(source), Some(other)) => ship.is_related(source, other),
+        _ => false,
+    }
+}
+
+/// Used when the probed model can be serialized
+#[cfg(feature = "serde")]
+pub trait ProbeJson {
+    fn json_fn(&self) -> JsonFn;
+}
+
+#[cfg(feature = "serde")]
+impl<R: 'static + serde::Serialize> ProbeJson for &Probe<R> {
+    fn json_fn(&self) -> JsonFn {
+        JsonFn {
+            to_value: Some(to_value::<R>),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+fn to_value<R: 'static + serde::Serialize>(row: &dyn Any) -> Option<serde_json::Value> {
+    serde_json::to_value(row.downcast_ref::<R>()?).ok()
+}
@@ -55,8 +55,6 @@ where
     Self: Relationship<T, R>,
     T: PrimaryKeyValue + HasSchema,
     T::Schema: UniqueIdentifier,
-    R: HasSchema,
-    R::Schema: UniqueIdentifier,
     R: ForeignKeyPartialEq<T::PrimaryKeyType>,
 {
     fn is_related(&self, source: &T, other: &R) -> bool {
@@ -0,0 +1,206 @@
+use super::JoinTableQuery;
+use super::Linked;
+use super::Relationship;
+use super::RelationshipCompare;
+use super::manytomany::{KeyPairs, exists_through, rows_pointing_at};
+use crate::Client;
+use crate::Syntax;
+use crate::connections::Row;
+use crate::errors::{Result, WeldsError};
+use crate::exts::VecStateExt;
+use crate::model_traits::ForeignKeyPartialEq;
+use crate::model_traits::HasSchema;
+use crate::model_traits::PrimaryKeyValue;
+use crate::model_traits::UniqueIdentifier;
+use crate::model_traits::{TableColumns, TableInfo, WriteToArgs};
+use crate::query::builder::QueryBuilder;
+use crate::query::include::{keyed_sql, select_in};
+use async_trait::async_trait;
+use std::marker::PhantomData;
+
+/// A relationship to the models of a `HasMany` on the models of another `HasMany`.
+///
+/// `Mid` points at this model with `through_key`, `R` points at `Mid` with `foreign_key`.
+/// Both `Mid` and `R` need a `BelongsTo` back, to match up included rows.
+///
+/// Works with `include`, `where_relation` and `map_query`
+/// ```rust,ignore
+/// #[derive(WeldsModel)]
+/// #[welds(table = "customers")]
+/// #[welds(HasMany(orders, Order, "customer_id"))]
+/// #[welds(HasManyThrough(line_items, LineItem, Order, "customer_id", "order_id"))]
+/// struct Customer { .. }
+/// ```
+pub struct HasManyThrough<T, R, Mid> {
+    _t: PhantomData<T>,
+    _r: PhantomData<R>,
+    _mid: PhantomData<Mid>,
+    through_key: &'static str,
+    foreign_key: &'static str,
+}
+
+impl<T, R, Mid> HasManyThrough<T, R, Mid> {
+    pub fn using(through_key: &'static str, fk: &'static str) -> HasManyThrough<T, R, Mid> {
+        HasManyThrough {
+            _t: Default::default(),
+            _r: Default::default(),
+            _mid: Default::default(),
+            through_key,
+            foreign_key: fk,
+        }
+    }
+}
+
+// writing these by hand to ignore PhantomData
+impl<T, R, Mid> PartialEq for HasManyThrough<T, R, Mid> {
+    fn eq(&self, other: &Self) -> bool {
+        self.through_key == other.through_key && self.foreign_key == other.foreign_key
+    }
+}
+impl<T, R, Mid> Clone for HasManyThrough<T, R, Mid> {
+    fn clone(&self) -> Self {
+        Self::using(self.through_key, self.foreign_key)
+    }
+}
+
+impl<T, R, Mid> Relationship<T, R> for HasManyThrough<T, R, Mid>
+where
+    T: 'static + Send + Sync + HasSchema + PrimaryKeyValue + WriteToArgs,
+    <T as HasSchema>::Schema: UniqueIdentifier + TableInfo + TableColumns,
+    R: 'static + Send + Sync + HasSchema + WriteToArgs,
+    <R as HasSchema>::Schema: TableInfo + TableColumns,
+    R: TryFrom<Row> + ForeignKeyPartialEq<Mid::PrimaryKeyType>,
+    WeldsError: From<<R as TryFrom<Row>>::Error>,
+    Mid: 'static + Send + Sync + HasSchema + PrimaryKeyValue + WriteToArgs,
+    <Mid as HasSchema>::Schema: UniqueIdentifier + TableInfo + TableColumns,
+    Mid: TryFrom<Row> + ForeignKeyPartialEq<T::PrimaryKeyType>,
+    WeldsError: From<<Mid as TryFrom<Row>>::Error>,
+{
+    fn my_key(&self) -> String {
+        <T as HasSchema>::Schema::id_column().name().to_owned()
+    }
+    fn their_key(&self) -> String {
+        self.foreign_key.to_owned()
+    }
+    fn join_table(&self) -> Option<Box<dyn JoinTableQuery<T, R>>> {
+        Some(Box::new(self.clone()))
+    }
+}
+
+// The rows aren't linked directly.
+// Included rows are matched through the `Mid` rows selected by the include, see `JoinTableQuery::links`
+impl<T, R, Mid> RelationshipCompare<T, R> for HasManyThrough<T, R, Mid>
+where
+    Self: Relationship<T, R>,
+{
+    fn is_related(&self, _source: &T, _other: &R) -> bool {
+        false
+    }
+}
+
+#[maybe_async::maybe_async]
+#[async_trait]
+impl<T, R, Mid> JoinTableQuery<T, R> for HasManyThrough<T, R, Mid>
+where
+    T: 'static + Send + Sync + HasSchema + PrimaryKeyValue + WriteToArgs,
+    <T as HasSchema>::Schema: UniqueIdentifier + TableInfo + TableColumns,
+    R: 'static + Send + Sync + HasSchema + WriteToArgs,
+    <R as HasSchema>::Schema: TableInfo + TableColumns,
+    R: TryFrom<Row> + ForeignKeyPartialEq<Mid::PrimaryKeyType>,
+    WeldsError: From<<R as TryFrom<Row>>::Error>,
+    Mid: 'static + Send + Sync + HasSchema + PrimaryKeyValue + WriteToArgs,
+    <Mid as HasSchema>::Schema: UniqueIdentifier + TableInfo + TableColumns,
+    Mid: TryFrom<Row> + ForeignKeyPartialEq<T::PrimaryKeyType>,
+    WeldsError: From<<Mid as TryFrom<Row>>::Error>,
+{
+    // R WHERE EXISTS ( mid WHERE mid.pk = R.fk AND EXISTS ( primary WHERE primary.pk = mid.through_key ) )
+    fn filter_linked(&self, primary: &QueryBuilder<T>, qb: &mut QueryBuilder<R>) {
+        let mid_pk = <Mid as HasSchema>::Schema::id_column();
+        let pk = <T as HasSchema>::Schema::id_column();
+        let keys = [self.foreign_key, mid_pk.name(), self.through_key, pk.name()];
+        exists_through::<Mid, R, T>(qb, primary, keys);
+    }
+
+    // T WHERE EXISTS ( mid WHERE mid.through_key = T.pk AND EXISTS ( filter WHERE filter.fk = mid.pk ) )
+    fn filter_linking(&self, filter: &QueryBuilder<R>, qb: &mut QueryBuilder<T>) {
+        let mid_pk = <Mid as HasSchema>::Schema::id_column();
+        let pk = <T as HasSchema>::Schema::id_column();
+        let keys = [pk.name(), self.through_key, mid_pk.name(), self.foreign_key];
+        exists_through::<Mid, T, R>(qb, filter, keys);
+    }
+
+    fn links_sql(&self, primary: &QueryBuilder<T>, syntax: Syntax) -> String {
+        rows_pointing_at::<Mid, T>(primary, self.through_key).to_sql(syntax)
+    }
+
+    async fn links(&self, primary: &QueryBuilder<T>, client: &dyn Client) -> Result<Linked<T, R>> {
+        let mids = rows_pointing_at::<Mid, T>(primary, self.through_key)
+            .run(client)
+            .await?;
+        Ok(self.linked(mids.into_inners()))
+    }
+
+    fn load_sql(&self, query: &QueryBuilder<R>, syntax: Syntax) -> Vec<String> {
+        let all_mids: QueryBuilder<Mid> = QueryBuilder::new();
+        vec![
+            keyed_sql(syntax, &all_mids, self.through_key, 1, &mut None),
+            keyed_sql(syntax, query, self.foreign_key, 1, &mut None),
+        ]
+    }
+
+    async fn load(
+        &self,
+        rows: &[&T],
+        query: &QueryBuilder<R>,
+        client: &dyn Client,
+    ) -> Result<(Vec<R>, Linked<T, R>)> {
+        let pk = <T as HasSchema>::Schema::id_column();
+        let all_mids = QueryBuilder::new();
+        let mids: Vec<Mid> =
+            select_in(client, &all_mids, self.through_key, rows, pk.name()).await?;
+
+        let mid_pk = <Mid as HasSchema>::Schema::id_column();
+        let mid_refs: Vec<&Mid> = mids.iter().collect();
+        let others: Vec<R> =
+            select_in(client, query, self.foreign_key, &mid_refs, mid_pk.name()).await?;
+
+        Ok((others, self.linked(mids)))
+    }
+}
+
+impl<T, R, Mid> HasManyThrough<T, R, Mid>
+where
+    T: PrimaryKeyValue + HasSchema + WriteToArgs,
+    <T as HasSchema>::Schema: UniqueIdentifier,
+    R: ForeignKeyPartialEq<Mid::PrimaryKeyType> + WriteToArgs,
+    Mid: 'static + Send + Sync + PrimaryKeyValue + ForeignKeyPartialEq<T::PrimaryKeyType>,
+    Mid: HasSchema + WriteToArgs,
+    <Mid as HasSchema>::Schema: UniqueIdentifier,
+{
+    // Matches the rows through the `mids` between them
+    fn linked(&self, mids: Vec<Mid>) -> Linked<T, R> {
+        let through_key = self.through_key;
+        let foreign_key = self.foreign_key;
+        let pk = <T as HasSchema>::Schema::id_column().name().to_owned();
+        let mid_pk = <Mid as HasSchema>::Schema::id_column().name().to_owned();
+        let pairs = KeyPairs::new(
+            mids.iter()
+                .map(|mid| (mid.column_key(through_key), mid.column_key(&mid_pk))),
+        );
+        Box::new(move |source: &T, other: &R| {
+            let keys = (source.column_key(&pk), other.column_key(foreign_key));
+            if let Some(linked) = pairs.as_ref().and_then(|p| p.linked(keys)) {
+                return linked;
+            }
+            let pk = source.primary_key_value();
+            mids.iter().any(|mid| {
+                ForeignKeyPartialEq::<T::PrimaryKeyType>::eq(mid, through_key, &pk)
+                    && ForeignKeyPartialEq::<Mid::PrimaryKeyType>::eq(
+                        other,
+                        foreign_key,
+                        &mid.primary_key_value(),
+                    )
+            })
+        })
+    }
+}
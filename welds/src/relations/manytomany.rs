@@ -0,0 +1,359 @@
+use super::HasJoinTableForeignkey;
+use super::Relationship;
+use super::RelationshipCompare;
+use crate::Client;
+use crate::Syntax;
+use crate::connections::Row;
+use crate::errors::{Result, WeldsError};
+use crate::exts::VecStateExt;
+use crate::model_traits::ColumnKey;
+use crate::model_traits::ForeignKeyPartialEq;
+use crate::model_traits::HasSchema;
+use crate::model_traits::PrimaryKeyValue;
+use crate::model_traits::UniqueIdentifier;
+use crate::model_traits::{TableColumns, TableInfo, WriteToArgs};
+use crate::query::builder::QueryBuilder;
+use crate::query::clause::exists::ExistIn;
+use crate::query::include::{keyed_sql, select_in};
+use async_trait::async_trait;
+use std::collections::HashSet;
+use std::marker::PhantomData;
+
+/// A many-to-many relationship, crossing the join table `Link`
+///
+/// `Link` is the model with `#[welds(JoinTable(..))]` pointing at both models
+///
+/// Works with `include`, `where_relation` and `map_query`
+/// ```rust,ignore
+/// #[derive(WeldsModel)]
+/// #[welds(table = "dogs")]
+/// #[welds(ManyToMany(cats, Cat, DogCat))]
+/// struct Dog { .. }
+///
+/// #[derive(WeldsModel)]
+/// #[welds(table = "dog_cats")]
+/// #[welds(JoinTable(Dog, "dog_id", Cat, "cat_id"))]
+/// struct DogCat { .. }
+/// ```
+pub struct ManyToMany<T, R, Link> {
+    _t: PhantomData<T>,
+    _r: PhantomData<R>,
+    _link: PhantomData<Link>,
+}
+
+impl<T, R, Link> ManyToMany<T, R, Link> {
+    pub fn new() -> ManyToMany<T, R, Link> {
+        ManyToMany {
+            _t: Default::default(),
+            _r: Default::default(),
+            _link: Default::default(),
+        }
+    }
+}
+
+impl<T, R, Link> Default for ManyToMany<T, R, Link> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// writing these by hand to ignore PhantomData
+impl<T, R, Link> PartialEq for ManyToMany<T, R, Link> {
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
+}
+impl<T, R, Link> Clone for ManyToMany<T, R, Link> {
+    fn clone(&self) -> Self {
+        Self::new()
+    }
+}
+
+/// Matches the rows on each side of a join table
+pub type Linked<T, R> = Box<dyn Fn(&T, &R) -> bool + Send + Sync>;
+
+/// How a relationship crosses a join table. See `Relationship::join_table`
+#[maybe_async::maybe_async]
+#[async_trait]
+pub trait JoinTableQuery<T, R>: Send + Sync {
+    /// Filters `qb` down to the rows linked to the rows selected by `primary`
+    fn filter_linked(&self, primary: &QueryBuilder<T>, qb: &mut QueryBuilder<R>);
+
+    /// Filters `qb` down to the rows linked to the rows selected by `filter`
+    fn filter_linking(&self, filter: &QueryBuilder<R>, qb: &mut QueryBuilder<T>);
+
+    /// The SQL selecting the links of the rows selected by `primary`
+    fn links_sql(&self, primary: &QueryBuilder<T>, syntax: Syntax) -> String;
+
+    /// Selects the links of the rows selected by `primary`
+    async fn links(&self, primary: &QueryBuilder<T>, client: &dyn Client) -> Result<Linked<T, R>>;
+
+    /// The SQL of `load`, keyed on a single row
+    fn load_sql(&self, query: &QueryBuilder<R>, syntax: Syntax) -> Vec<String>;
+
+    /// Selects the rows of `query` linked to `rows`, along with their links
+    async fn load(
+        &self,
+        rows: &[&T],
+        query: &QueryBuilder<R>,
+        client: &dyn Client,
+    ) -> Result<(Vec<R>, Linked<T, R>)>;
+}
+
+impl<T, R, Link> Relationship<T, R> for ManyToMany<T, R, Link>
+where
+    T: 'static + Send + Sync + HasSchema + PrimaryKeyValue + WriteToArgs,
+    <T as HasSchema>::Schema: UniqueIdentifier + TableInfo + TableColumns,
+    R: 'static + Send + Sync + HasSchema + PrimaryKeyValue + WriteToArgs,
+    <R as HasSchema>::Schema: UniqueIdentifier + TableInfo + TableColumns,
+    Link: 'static + Send + Sync + HasSchema + WriteToArgs,
+    <Link as HasSchema>::Schema: TableInfo + TableColumns,
+    Link: HasJoinTableForeignkey<T> + HasJoinTableForeignkey<R>,
+    Link: ForeignKeyPartialEq<T::PrimaryKeyType> + ForeignKeyPartialEq<R::PrimaryKeyType>,
+    Link: TryFrom<Row>,
+    WeldsError: From<<Link as TryFrom<Row>>::Error>,
+    R: TryFrom<Row>,
+    WeldsError: From<<R as TryFrom<Row>>::Error>,
+{
+    fn my_key(&self) -> String {
+        <T as HasSchema>::Schema::id_column().name().to_owned()
+    }
+    fn their_key(&self) -> String {
+        <R as HasSchema>::Schema::id_column().name().to_owned()
+    }
+    fn join_table(&self) -> Option<Box<dyn JoinTableQuery<T, R>>> {
+        Some(Box::new(self.clone()))
+    }
+}
+
+// The links aren't on either model.
+// Included rows are matched with the links selected by the include, see `JoinTableQuery::links`
+impl<T, R, Link> RelationshipCompare<T, R> for ManyToMany<T, R, Link>
+where
+    Self: Relationship<T, R>,
+{
+    fn is_related(&self, _source: &T, _other: &R) -> bool {
+        false
+    }
+}
+
+#[maybe_async::maybe_async]
+#[async_trait]
+impl<T, R, Link> JoinTableQuery<T, R> for ManyToMany<T, R, Link>
+where
+    T: 'static + Send + Sync + HasSchema + PrimaryKeyValue + WriteToArgs,
+    <T as HasSchema>::Schema: UniqueIdentifier + TableInfo + TableColumns,
+    R: 'static + Send + Sync + HasSchema + PrimaryKeyValue + WriteToArgs,
+    <R as HasSchema>::Schema: UniqueIdentifier + TableInfo + TableColumns,
+    Link: 'static + Send + Sync + HasSchema + WriteToArgs,
+    <Link as HasSchema>::Schema: TableInfo + TableColumns,
+    Link: HasJoinTableForeignkey<T> + HasJoinTableForeignkey<R>,
+    Link: ForeignKeyPartialEq<T::PrimaryKeyType> + ForeignKeyPartialEq<R::PrimaryKeyType>,
+    Link: TryFrom<Row>,
+    WeldsError: From<<Link as TryFrom<Row>>::Error>,
+    R: TryFrom<Row>,
+    WeldsError: From<<R as TryFrom<Row>>::Error>,
+{
+    fn filter_linked(&self, primary: &QueryBuilder<T>, qb: &mut QueryBuilder<R>) {
+        through_link::<Link, R, T>(qb, primary);
+    }
+
+    fn filter_linking(&self, filter: &QueryBuilder<R>, qb: &mut QueryBuilder<T>) {
+        through_link::<Link, T, R>(qb, filter);
+    }
+
+    fn links_sql(&self, primary: &QueryBuilder<T>, syntax: Syntax) -> String {
+        links_query::<Link, T>(primary).to_sql(syntax)
+    }
+
+    async fn links(&self, primary: &QueryBuilder<T>, client: &dyn Client) -> Result<Linked<T, R>> {
+        let links = links_query::<Link, T>(primary).run(client).await?;
+        Ok(linked::<Link, T, R>(links.into_inners()))
+    }
+
+    fn load_sql(&self, query: &QueryBuilder<R>, syntax: Syntax) -> Vec<String> {
+        let my_fk = <Link as HasJoinTableForeignkey<T>>::fk_column();
+        let their_pk = <R as HasSchema>::Schema::id_column();
+        let all_links: QueryBuilder<Link> = QueryBuilder::new();
+        vec![
+            keyed_sql(syntax, &all_links, my_fk, 1, &mut None),
+            keyed_sql(syntax, query, their_pk.name(), 1, &mut None),
+        ]
+    }
+
+    async fn load(
+        &self,
+        rows: &[&T],
+        query: &QueryBuilder<R>,
+        client: &dyn Client,
+    ) -> Result<(Vec<R>, Linked<T, R>)> {
+        let my_fk = <Link as HasJoinTableForeignkey<T>>::fk_column();
+        let my_pk = <T as HasSchema>::Schema::id_column();
+        let all_links = QueryBuilder::new();
+        let links: Vec<Link> = select_in(client, &all_links, my_fk, rows, my_pk.name()).await?;
+
+        let their_fk = <Link as HasJoinTableForeignkey<R>>::fk_column();
+        let their_pk = <R as HasSchema>::Schema::id_column();
+        let link_refs: Vec<&Link> = links.iter().collect();
+        let others: Vec<R> =
+            select_in(client, query, their_pk.name(), &link_refs, their_fk).await?;
+
+        Ok((others, linked::<Link, T, R>(links)))
+    }
+}
+
+// Matches the rows on each side of `links`
+fn linked<Link, T, R>(links: Vec<Link>) -> Linked<T, R>
+where
+    T: PrimaryKeyValue + HasSchema + WriteToArgs,
+    <T as HasSchema>::Schema: UniqueIdentifier,
+    R: PrimaryKeyValue + HasSchema + WriteToArgs,
+    <R as HasSchema>::Schema: UniqueIdentifier,
+    Link: 'static + Send + Sync + WriteToArgs,
+    Link: HasJoinTableForeignkey<T> + HasJoinTableForeignkey<R>,
+    Link: ForeignKeyPartialEq<T::PrimaryKeyType> + ForeignKeyPartialEq<R::PrimaryKeyType>,
+{
+    let my_fk = <Link as HasJoinTableForeignkey<T>>::fk_column();
+    let their_fk = <Link as HasJoinTableForeignkey<R>>::fk_column();
+    let my_pk = <T as HasSchema>::Schema::id_column().name().to_owned();
+    let their_pk = <R as HasSchema>::Schema::id_column().name().to_owned();
+    let pairs = KeyPairs::new(
+        links
+            .iter()
+            .map(|link| (link.column_key(my_fk), link.column_key(their_fk))),
+    );
+    Box::new(move |source: &T, other: &R| {
+        let keys = (source.column_key(&my_pk), other.column_key(&their_pk));
+        if let Some(linked) = pairs.as_ref().and_then(|p| p.linked(keys)) {
+            return linked;
+        }
+        let my_pk = source.primary_key_value();
+        let their_pk = other.primary_key_value();
+        links.iter().any(|link| {
+            ForeignKeyPartialEq::<T::PrimaryKeyType>::eq(link, my_fk, &my_pk)
+                && ForeignKeyPartialEq::<R::PrimaryKeyType>::eq(link, their_fk, &their_pk)
+        })
+    })
+}
+
+// The keys of the rows on each side of the links,
+// finds out if two rows are linked without looking at every link
+pub(super) struct KeyPairs {
+    pairs: HashSet<(ColumnKey, ColumnKey)>,
+}
+
+impl KeyPairs {
+    // None if any of the links can't be keyed
+    pub(super) fn new(
+        links: impl Iterator<Item = (Option<ColumnKey>, Option<ColumnKey>)>,
+    ) -> Option<Self> {
+        let mut pairs = HashSet::new();
+        for (a, b) in links {
+            pairs.insert((a?, b?));
+        }
+        Some(Self { pairs })
+    }
+
+    // None if the keys can't be compared to the links, the columns are different types.
+    // i.e. an `Option<i32>` foreign key pointing at an `i32` primary key
+    pub(super) fn linked(&self, keys: (Option<ColumnKey>, Option<ColumnKey>)) -> Option<bool> {
+        let (a, b) = (keys.0?, keys.1?);
+        let Some((link_a, link_b)) = self.pairs.iter().next() else {
+            return Some(false);
+        };
+        if !a.same_type(link_a) || !b.same_type(link_b) {
+            return None;
+        }
+        Some(self.pairs.contains(&(a, b)))
+    }
+}
+
+// The links pointing at the rows selected by `primary`
+fn links_query<Link, T>(primary: &QueryBuilder<T>) -> QueryBuilder<Link>
+where
+    T: Send + HasSchema,
+    <T as HasSchema>::Schema: UniqueIdentifier,
+    Link: Send + HasSchema + HasJoinTableForeignkey<T>,
+{
+    let fk = <Link as HasJoinTableForeignkey<T>>::fk_column();
+    rows_pointing_at::<Link, T>(primary, fk)
+}
+
+// The rows of `X` with `fk` pointing at the rows selected by `primary`
+pub(super) fn rows_pointing_at<X, T>(primary: &QueryBuilder<T>, fk: &str) -> QueryBuilder<X>
+where
+    T: Send + HasSchema,
+    <T as HasSchema>::Schema: UniqueIdentifier,
+    X: Send + HasSchema,
+{
+    let mut rows: QueryBuilder<X> = QueryBuilder::new();
+    rows.set_aliases(&primary.alias_asigner);
+    let pk = <T as HasSchema>::Schema::id_column().name().to_owned();
+    rows.exist_ins
+        .push(ExistIn::new(primary, fk.to_owned(), pk));
+    rows
+}
+
+// outer WHERE EXISTS ( link WHERE link.fk = outer.pk AND EXISTS ( inner WHERE inner.pk = link.fk ) )
+fn through_link<Link, X, Y>(outer: &mut QueryBuilder<X>, inner: &QueryBuilder<Y>)
+where
+    X: Send + HasSchema,
+    <X as HasSchema>::Schema: UniqueIdentifier,
+    Y: Send + HasSchema,
+    <Y as HasSchema>::Schema: UniqueIdentifier,
+    Link: Send + HasSchema + HasJoinTableForeignkey<X> + HasJoinTableForeignkey<Y>,
+{
+    let outer_fk = <Link as HasJoinTableForeignkey<X>>::fk_column();
+    let outer_pk = <X as HasSchema>::Schema::id_column();
+    let inner_fk = <Link as HasJoinTableForeignkey<Y>>::fk_column();
+    let inner_pk = <Y as HasSchema>::Schema::id_column();
+    let keys = [outer_pk.name(), outer_fk, inner_fk, inner_pk.name()];
+    exists_through::<Link, X, Y>(outer, inner, keys);
+}
+
+// outer WHERE EXISTS ( mid WHERE mid.b = outer.a AND EXISTS ( inner WHERE inner.d = mid.c ) )
+// keys are [a, b, c, d]
+pub(super) fn exists_through<Mid, X, Y>(
+    outer: &mut QueryBuilder<X>,
+    inner: &QueryBuilder<Y>,
+    keys: [&str; 4],
+) where
+    X: Send + HasSchema,
+    Y: Send + HasSchema,
+    Mid: Send + HasSchema,
+{
+    let [outer_col, mid_outer_col, mid_inner_col, inner_col] = keys.map(str::to_owned);
+    let mut mid: QueryBuilder<Mid> = QueryBuilder::new();
+    mid.exist_ins
+        .push(ExistIn::new(inner, mid_inner_col, inner_col));
+    let mut exist_in = ExistIn::new(&mid, outer_col, mid_outer_col);
+    exist_in.set_aliases(&outer.alias_asigner);
+    outer.exist_ins.push(exist_in);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keys<A, B>(a: A, b: B) -> (Option<ColumnKey>, Option<ColumnKey>)
+    where
+        A: 'static + std::hash::Hash + Eq + Send + Sync,
+        B: 'static + std::hash::Hash + Eq + Send + Sync,
+    {
+        (Some(ColumnKey::new(a)), Some(ColumnKey::new(b)))
+    }
+
+    #[test]
+    fn should_find_links_by_their_keys() {
+        let pairs = KeyPairs::new([keys(1, 2), keys(1, 3)].into_iter()).unwrap();
+        assert_eq!(pairs.linked(keys(1, 3)), Some(true));
+        assert_eq!(pairs.linked(keys(2, 3)), Some(false));
+    }
+
+    #[test]
+    fn should_not_compare_keys_of_another_type() {
+        let pairs = KeyPairs::new([keys(Some(1), 2)].into_iter()).unwrap();
+        assert_eq!(pairs.linked(keys(1, 2)), None);
+        assert!(KeyPairs::new([(None, Some(ColumnKey::new(2)))].into_iter()).is_none());
+    }
+}
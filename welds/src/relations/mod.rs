@@ -4,17 +4,42 @@ pub use belongsto::BelongsTo;
 mod hasmany;
 pub use hasmany::HasMany;
 
+mod hasmanythrough;
+pub use hasmanythrough::HasManyThrough;
+
 mod hasone;
 pub use hasone::HasOne;
 
 mod manual;
 pub use manual::ManualRelationship;
 
+mod manytomany;
+pub use manytomany::{JoinTableQuery, Linked, ManyToMany};
+
+mod polymorphic;
+pub use polymorphic::{BelongsToPolymorphic, HasManyPolymorphic};
+pub use polymorphic::{Discriminator, DiscriminatorSide};
+
+#[doc(hidden)]
+pub mod describe;
+
 /// Describes how a relationship should be wired up.
 /// Gives info about what DB columns to use on both Models
 pub trait Relationship<SELF, R>: Clone + PartialEq + Send {
     fn their_key(&self) -> String;
     fn my_key(&self) -> String;
+
+    /// Set for relationships that cross a join table, `ManyToMany`.
+    /// The keys are then the primary keys of each model
+    fn join_table(&self) -> Option<Box<dyn JoinTableQuery<SELF, R>>> {
+        None
+    }
+
+    /// Set for polymorphic relationships, `HasManyPolymorphic` `BelongsToPolymorphic`.
+    /// Queries across the relationship also filter on the type column
+    fn discriminator(&self) -> Option<Discriminator> {
+        None
+    }
 }
 
 /// Used to check if a relationship holds between two models.
@@ -28,6 +53,14 @@ where
 
 pub trait HasRelations {
     type Relation: Default;
+
+    /// Describes the field of `Relation` holding `ship`. Written by the Welds Macros.
+    /// Used to walk a `DataSet` without knowing the types of its relationships
+    #[doc(hidden)]
+    fn describe_relation(ship: &dyn std::any::Any) -> Option<describe::RelationInfo> {
+        let _ = ship;
+        None
+    }
 }
 
 pub trait RelationAdder {}
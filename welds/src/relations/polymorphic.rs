@@ -0,0 +1,224 @@
+use super::Relationship;
+use super::RelationshipCompare;
+use crate::model_traits::ForeignKeyPartialEq;
+use crate::model_traits::HasSchema;
+use crate::model_traits::PrimaryKeyValue;
+use crate::model_traits::TableInfo;
+use crate::model_traits::UniqueIdentifier;
+use crate::query::builder::QueryBuilder;
+use crate::query::clause::ClauseColManual;
+use std::marker::PhantomData;
+use std::sync::Arc;
+
+/// The models on one side of a polymorphic relationship belong to many types of models.
+/// Each row stores the id of the model it belongs to in `<name>_id`
+/// and which type of model that is in `<name>_type`.
+///
+/// The type is the parent model's table identifier, or the name given to the relationship.
+/// ```rust,ignore
+/// #[derive(WeldsModel)]
+/// #[welds(table = "posts")]
+/// #[welds(HasManyPolymorphic(comments, Comment, "commentable"))]
+/// struct Post { .. }
+///
+/// #[derive(WeldsModel)]
+/// #[welds(table = "comments")]
+/// #[welds(BelongsToPolymorphic(post, Post, "commentable"))]
+/// #[welds(BelongsToPolymorphic(photo, Photo, "commentable", "Photo"))]
+/// struct Comment {
+///     #[welds(primary_key)]
+///     pub id: i32,
+///     pub commentable_id: i32,
+///     pub commentable_type: String,
+/// }
+/// ```
+pub struct HasManyPolymorphic<T, R> {
+    _t: PhantomData<T>,
+    _r: PhantomData<R>,
+    foreign_key: &'static str,
+    type_column: &'static str,
+    type_name: Option<&'static str>,
+}
+
+/// The other side of a `HasManyPolymorphic`. The model with the `<name>_id` and `<name>_type` columns
+pub struct BelongsToPolymorphic<T, R> {
+    _t: PhantomData<T>,
+    _r: PhantomData<R>,
+    foreign_key: &'static str,
+    type_column: &'static str,
+    type_name: Option<&'static str>,
+}
+
+/// The column telling which type of model a row of a polymorphic relationship belongs to
+pub struct Discriminator {
+    /// The column holding the type
+    pub column: &'static str,
+    /// The type of the parent model in this relationship
+    pub value: String,
+    /// Which model in the relationship has the column
+    pub side: DiscriminatorSide,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiscriminatorSide {
+    /// The model the relationship is defined on
+    Source,
+    /// The model the relationship points at
+    Related,
+}
+
+impl Discriminator {
+    /// Filters `qb` down to rows of this type, `qb` must select the model with the column
+    pub(crate) fn filter<X>(&self, qb: &mut QueryBuilder<X>) {
+        qb.wheres.push(Arc::new(Box::new(self.clause())));
+    }
+
+    /// The WHERE clause matching rows of this type
+    pub(crate) fn clause(&self) -> ClauseColManual {
+        ClauseColManual {
+            col: Some(self.column),
+            sql: "= ?".to_owned(),
+            params: vec![Box::new(self.value.clone())],
+        }
+    }
+}
+
+// the type stored for a parent model
+fn type_value<P>(type_name: Option<&'static str>) -> String
+where
+    P: HasSchema,
+    <P as HasSchema>::Schema: TableInfo,
+{
+    match type_name {
+        Some(name) => name.to_owned(),
+        None => <P as HasSchema>::Schema::identifier().join("."),
+    }
+}
+
+impl<T, R> HasManyPolymorphic<T, R> {
+    pub fn using(
+        fk: &'static str,
+        type_column: &'static str,
+        type_name: Option<&'static str>,
+    ) -> HasManyPolymorphic<T, R> {
+        HasManyPolymorphic {
+            _t: Default::default(),
+            _r: Default::default(),
+            foreign_key: fk,
+            type_column,
+            type_name,
+        }
+    }
+}
+
+impl<T, R> BelongsToPolymorphic<T, R> {
+    pub fn using(
+        fk: &'static str,
+        type_column: &'static str,
+        type_name: Option<&'static str>,
+    ) -> BelongsToPolymorphic<T, R> {
+        BelongsToPolymorphic {
+            _t: Default::default(),
+            _r: Default::default(),
+            foreign_key: fk,
+            type_column,
+            type_name,
+        }
+    }
+}
+
+// writing these by hand to ignore PhantomData
+impl<T, R> PartialEq for HasManyPolymorphic<T, R> {
+    fn eq(&self, other: &Self) -> bool {
+        self.foreign_key == other.foreign_key
+            && self.type_column == other.type_column
+            && self.type_name == other.type_name
+    }
+}
+impl<T, R> Clone for HasManyPolymorphic<T, R> {
+    fn clone(&self) -> Self {
+        Self::using(self.foreign_key, self.type_column, self.type_name)
+    }
+}
+impl<T, R> PartialEq for BelongsToPolymorphic<T, R> {
+    fn eq(&self, other: &Self) -> bool {
+        self.foreign_key == other.foreign_key
+            && self.type_column == other.type_column
+            && self.type_name == other.type_name
+    }
+}
+impl<T, R> Clone for BelongsToPolymorphic<T, R> {
+    fn clone(&self) -> Self {
+        Self::using(self.foreign_key, self.type_column, self.type_name)
+    }
+}
+
+impl<T: Send, R: Send> Relationship<T, R> for HasManyPolymorphic<T, R>
+where
+    T: HasSchema,
+    <T as HasSchema>::Schema: UniqueIdentifier + TableInfo,
+{
+    fn my_key(&self) -> String {
+        <T as HasSchema>::Schema::id_column().name().to_owned()
+    }
+    fn their_key(&self) -> String {
+        self.foreign_key.to_owned()
+    }
+    fn discriminator(&self) -> Option<Discriminator> {
+        Some(Discriminator {
+            column: self.type_column,
+            value: type_value::<T>(self.type_name),
+            side: DiscriminatorSide::Related,
+        })
+    }
+}
+
+impl<T: Send, R: Send> Relationship<T, R> for BelongsToPolymorphic<T, R>
+where
+    R: HasSchema,
+    <R as HasSchema>::Schema: UniqueIdentifier + TableInfo,
+{
+    fn my_key(&self) -> String {
+        self.foreign_key.to_owned()
+    }
+    fn their_key(&self) -> String {
+        <R as HasSchema>::Schema::id_column().name().to_owned()
+    }
+    fn discriminator(&self) -> Option<Discriminator> {
+        Some(Discriminator {
+            column: self.type_column,
+            value: type_value::<R>(self.type_name),
+            side: DiscriminatorSide::Source,
+        })
+    }
+}
+
+impl<T, R> RelationshipCompare<T, R> for HasManyPolymorphic<T, R>
+where
+    Self: Relationship<T, R>,
+    T: PrimaryKeyValue + HasSchema,
+    T::Schema: UniqueIdentifier + TableInfo,
+    R: ForeignKeyPartialEq<T::PrimaryKeyType> + ForeignKeyPartialEq<String>,
+{
+    fn is_related(&self, source: &T, other: &R) -> bool {
+        let pk = source.primary_key_value();
+        let type_value = type_value::<T>(self.type_name);
+        ForeignKeyPartialEq::<T::PrimaryKeyType>::eq(other, self.foreign_key, &pk)
+            && ForeignKeyPartialEq::<String>::eq(other, self.type_column, &type_value)
+    }
+}
+
+impl<T, R> RelationshipCompare<T, R> for BelongsToPolymorphic<T, R>
+where
+    Self: Relationship<T, R>,
+    R: PrimaryKeyValue + HasSchema,
+    R::Schema: UniqueIdentifier + TableInfo,
+    T: ForeignKeyPartialEq<R::PrimaryKeyType> + ForeignKeyPartialEq<String>,
+{
+    fn is_related(&self, source: &T, other: &R) -> bool {
+        let pk = other.primary_key_value();
+        let type_value = type_value::<R>(self.type_name);
+        ForeignKeyPartialEq::<R::PrimaryKeyType>::eq(source, self.foreign_key, &pk)
+            && ForeignKeyPartialEq::<String>::eq(source, self.type_column, &type_value)
+    }
+}
@@ -1,13 +1,17 @@
+use crate::audit::{self, Operation};
 use crate::errors::Result;
 use crate::model_traits::hooks::{
     AfterCreate, AfterDelete, AfterUpdate, BeforeCreate, BeforeDelete, BeforeUpdate,
 };
 use crate::model_traits::{
-    ColumnDefaultCheck, HasSchema, TableColumns, TableInfo, UpdateFromRow, WriteToArgs,
+    AutoTimestamps, ChangeSet, ColumnDefaultCheck, HasSchema, HasVersion, Snapshot, SoftDelete,
+    TableColumns, TableIdent, TableInfo, TrackChanges, UpdateFromRow, WriteToArgs,
 };
+use crate::observers::{self, EventKind, ModelEvent};
 use crate::query::delete;
 use crate::query::insert;
 use crate::query::update;
+use crate::writers::TableWriter;
 use std::marker::PhantomData;
 use std::ops::{Deref, DerefMut};
 use std::sync::Arc;
@@ -31,6 +35,12 @@ pub struct DbState<T> {
     _t: PhantomData<T>,
     inner: T,
     status: DbStatus,
+    // The column values as they were in the database. Taken when the model is first edited
+    snapshot: Option<Snapshot>,
+    // Takes the snapshot, set for models loaded by a query or created by save
+    tracker: Option<fn(&T) -> Snapshot>,
+    // The table the row is saved to, in place of the model's table
+    table: Option<TableIdent>,
 }
 
 impl<T> std::fmt::Debug for DbState<T>
@@ -45,7 +55,14 @@ where
 impl<T: PartialEq> PartialEq for DbState<T> {
     fn eq(&self, other: &Self) -> bool {
         // trick to avoid missing fields when adding new ones
-        let Self { inner, status, _t } = &self;
+        let Self {
+            inner,
+            status,
+            _t,
+            snapshot: _,
+            tracker: _,
+            table: _,
+        } = &self;
         inner == &other.inner && status == &other.status
     }
 }
@@ -83,6 +100,9 @@ impl<T> DbState<T> {
             _t: PhantomData,
             inner,
             status: DbStatus::NotInDatabase,
+            snapshot: None,
+            tracker: None,
+            table: None,
         }
     }
 
@@ -92,46 +112,200 @@ impl<T> DbState<T> {
             _t: PhantomData,
             inner,
             status: DbStatus::NotModified,
+            snapshot: None,
+            tracker: None,
+            table: None,
+        }
+    }
+
+    /// Returns a DbState<T> that assumes its inner T already exist in the database
+    /// and keeps track of the changes made to it.
+    ///
+    /// Saving it only updates the columns that have changed. Models loaded by a query are tracked
+    pub fn db_loaded_tracked(inner: T) -> DbState<T>
+    where
+        T: HasSchema,
+    {
+        DbState {
+            tracker: T::tracker(),
+            ..DbState::db_loaded(inner)
         }
     }
 
+    /// Save and delete the inner T in another table with the same columns as the model.
+    ///
+    /// The name is `table` or `schema.table`.
+    /// Models loaded from a query that used `in_table` are already set to its table
+    ///
+    /// Returns `WeldsError::InvalidTableName` if the name is not a plain table name
+    pub fn in_table(mut self, table: impl AsRef<str>) -> Result<Self> {
+        self.table = Some(TableWriter::validate(table.as_ref())?);
+        Ok(self)
+    }
+
+    pub(crate) fn with_table(mut self, table: Option<TableIdent>) -> Self {
+        self.table = table;
+        self
+    }
+
+    /// The table the inner T is saved to, in the client's default schema if it has no schema
+    fn table_ident(&self, client: &dyn Client) -> TableIdent
+    where
+        T: HasSchema,
+    {
+        let table = match &self.table {
+            Some(table) => table.clone(),
+            None => TableIdent::from_model::<T>(),
+        };
+        table.or_schema(client.default_schema())
+    }
+
     /// Saves the inner T to the database. Results in an insert or update if needed. If no change
     /// has been detected on the inner T, No operation will occur
     ///
+    /// Updates only write the columns that have changed since the model was loaded
+    ///
+    /// Returns `WeldsError::StaleObject` if T is versioned and someone else has changed
+    /// the row since it was loaded
     pub async fn save(&mut self, client: &dyn Client) -> Result<()>
     where
         T: HasSchema + WriteToArgs + ColumnDefaultCheck,
         <T as HasSchema>::Schema: TableInfo + TableColumns,
-        T: UpdateFromRow,
+        T: UpdateFromRow + TrackChanges + HasVersion + AutoTimestamps,
         T: BeforeCreate + AfterCreate,
         T: BeforeUpdate + AfterUpdate,
     {
+        let table = self.table_ident(client);
+        if self.status == DbStatus::NotModified {
+            return Ok(());
+        }
+        let trans = audit::begin::<T>(client).await?;
+        let conn: &dyn Client = match &trans {
+            Some(trans) => trans,
+            None => client,
+        };
+        // observers are told once the change is committed
+        let mut event = None;
         match self.status {
             DbStatus::NotModified => {}
             DbStatus::Edited => {
-                update::update_one(&mut self.inner, client).await?;
+                let snapshot = self.snapshot.as_ref();
+                update::update_one(&mut self.inner, snapshot, &table, conn).await?;
+                let new_values = self.inner.audit_values()?;
+                if new_values.is_some() || observers::any() {
+                    let changes = ChangeSet::new(&self.inner, snapshot).changed_columns();
+                    if !changes.is_empty() {
+                        let old_values = match snapshot {
+                            Some(snapshot) => snapshot.audit_values.clone()?,
+                            None => None,
+                        };
+                        audit::record(conn, &table, Operation::Update, old_values, new_values)
+                            .await?;
+                        event = Some((EventKind::Updated, changes));
+                    }
+                }
             }
             DbStatus::NotInDatabase => {
-                insert::insert_one(&mut self.inner, client).await?;
+                insert::insert_into(&mut self.inner, &table, conn).await?;
+                if let Some(new_values) = self.inner.audit_values()? {
+                    audit::record(conn, &table, Operation::Insert, None, Some(new_values)).await?;
+                }
+                if observers::any() {
+                    let changes = ChangeSet::new(&self.inner, None).changed_columns();
+                    event = Some((EventKind::Created, changes));
+                }
             }
         }
+        if let Some(trans) = trans {
+            trans.commit().await?;
+        }
+        if let Some((kind, changes)) = event {
+            self.notify(kind, &table, &changes);
+        }
+        // now that it is in the database the changes to it can be tracked
+        self.tracker = T::tracker();
         self.status = DbStatus::NotModified;
+        self.snapshot = None;
         Ok(())
     }
 
+    /// Returns the changes made to the inner T since it was loaded from the database
+    ///
+    /// All columns are considered changed if the model is not in the database
+    pub fn changes(&self) -> ChangeSet<'_>
+    where
+        T: TrackChanges,
+    {
+        match self.status {
+            DbStatus::NotModified => ChangeSet::unchanged(&self.inner),
+            _ => ChangeSet::new(&self.inner, self.snapshot.as_ref()),
+        }
+    }
+
     /// Removes the inner T from the database. If T is not in the database no operation will occur
+    ///
+    /// Soft deleted models (`#[welds(soft_delete)]`) are flagged as deleted instead,
+    /// their row stays in the database
+    ///
+    /// Returns `WeldsError::StaleObject` if T is versioned and someone else has changed
+    /// the row since it was loaded
     pub async fn delete(&mut self, client: &dyn Client) -> Result<()>
     where
-        T: HasSchema + WriteToArgs,
+        T: HasSchema + WriteToArgs + HasVersion + SoftDelete + TrackChanges,
         <T as HasSchema>::Schema: TableInfo + TableColumns,
         T: BeforeDelete + AfterDelete,
     {
+        if <T as HasSchema>::Schema::soft_delete_column().is_none() {
+            return self.force_delete(client).await;
+        }
+        let table = self.table_ident(client);
         match self.status {
-            DbStatus::NotModified => {
-                delete::delete_one(&self.inner, client).await?;
+            DbStatus::NotModified | DbStatus::Edited => {
+                let old_values = self.old_audit_values()?;
+                let trans = audit::begin::<T>(client).await?;
+                let conn: &dyn Client = match &trans {
+                    Some(trans) => trans,
+                    None => client,
+                };
+                delete::soft_delete_one(&mut self.inner, &table, conn).await?;
+                if old_values.is_some() {
+                    let new_values = self.inner.audit_values()?;
+                    audit::record(conn, &table, Operation::Delete, old_values, new_values).await?;
+                }
+                if let Some(trans) = trans {
+                    trans.commit().await?;
+                }
+                self.notify(EventKind::Deleted, &table, &[]);
             }
-            DbStatus::Edited => {
-                delete::delete_one(&self.inner, client).await?;
+            DbStatus::NotInDatabase => {}
+        }
+        Ok(())
+    }
+
+    /// Removes the inner T from the database, even if it is soft deleted.
+    /// If T is not in the database no operation will occur
+    pub async fn force_delete(&mut self, client: &dyn Client) -> Result<()>
+    where
+        T: HasSchema + WriteToArgs + HasVersion + TrackChanges,
+        <T as HasSchema>::Schema: TableInfo + TableColumns,
+        T: BeforeDelete + AfterDelete,
+    {
+        let table = self.table_ident(client);
+        match self.status {
+            DbStatus::NotModified | DbStatus::Edited => {
+                let trans = audit::begin::<T>(client).await?;
+                let conn: &dyn Client = match &trans {
+                    Some(trans) => trans,
+                    None => client,
+                };
+                delete::delete_one(&self.inner, &table, conn).await?;
+                if let Some(old_values) = self.old_audit_values()? {
+                    audit::record(conn, &table, Operation::Delete, Some(old_values), None).await?;
+                }
+                if let Some(trans) = trans {
+                    trans.commit().await?;
+                }
+                self.notify(EventKind::Deleted, &table, &[]);
             }
             DbStatus::NotInDatabase => {}
         }
@@ -139,6 +313,65 @@ impl<T> DbState<T> {
         Ok(())
     }
 
+    /// Clears the soft delete flag on the inner T. `#[welds(soft_delete)]`
+    /// If T is not in the database no operation will occur
+    pub async fn restore(&mut self, client: &dyn Client) -> Result<()>
+    where
+        T: HasSchema + WriteToArgs + HasVersion + SoftDelete + TrackChanges,
+        <T as HasSchema>::Schema: TableInfo + TableColumns,
+    {
+        let table = self.table_ident(client);
+        match self.status {
+            DbStatus::NotModified | DbStatus::Edited => {
+                let old_values = self.old_audit_values()?;
+                let trans = audit::begin::<T>(client).await?;
+                let conn: &dyn Client = match &trans {
+                    Some(trans) => trans,
+                    None => client,
+                };
+                delete::restore_one(&mut self.inner, &table, conn).await?;
+                if old_values.is_some() {
+                    let new_values = self.inner.audit_values()?;
+                    audit::record(conn, &table, Operation::Update, old_values, new_values).await?;
+                }
+                if let Some(trans) = trans {
+                    trans.commit().await?;
+                }
+                if let Some(col) = <T as HasSchema>::Schema::soft_delete_column() {
+                    self.notify(EventKind::Updated, &table, &[col.name()]);
+                }
+            }
+            DbStatus::NotInDatabase => {}
+        }
+        Ok(())
+    }
+
+    /// The values of an audited T as they are in the database. `#[welds(audited)]`
+    fn old_audit_values(&self) -> Result<Option<String>>
+    where
+        T: TrackChanges,
+    {
+        let values = match &self.snapshot {
+            Some(snapshot) => snapshot.audit_values.clone(),
+            None => self.inner.audit_values(),
+        };
+        Ok(values?)
+    }
+
+    /// Tells the observers about a change to the inner T. `welds::observers`
+    fn notify(&self, kind: EventKind, table: &TableIdent, changes: &[&'static str])
+    where
+        T: TrackChanges,
+    {
+        if !observers::any() {
+            return;
+        }
+        let model = std::any::type_name::<T>();
+        let primary_key = self.inner.primary_key_snapshot();
+        let event = ModelEvent::new(kind, model, table, &primary_key, changes);
+        observers::notify(&event);
+    }
+
     /// Consumes the DbState, returning the wrapped value. The inner value is nolonger connected to
     /// welds and can nolonger be saved/created/deleted
     pub fn into_inner(self) -> T {
@@ -154,10 +387,17 @@ impl<T> DbState<T> {
     ///
     /// ⚠️ It may update the wrong row if the Primary Key is modified. Make sure to check beforehand. ⚠️
     pub fn replace_inner(&mut self, new: T) {
+        self.mark_edited();
+        self.inner = new;
+    }
+
+    // Flags the model as edited. The first edit of a loaded model
+    // snapshots its columns so save can tell what changed
+    fn mark_edited(&mut self) {
         if self.status == DbStatus::NotModified {
+            self.snapshot = self.tracker.map(|snapshot| snapshot(&self.inner));
             self.status = DbStatus::Edited
         }
-        self.inner = new;
     }
 }
 
@@ -170,9 +410,7 @@ impl<T> Deref for DbState<T> {
 
 impl<T> DerefMut for DbState<T> {
     fn deref_mut(&mut self) -> &mut Self::Target {
-        if self.status == DbStatus::NotModified {
-            self.status = DbStatus::Edited
-        }
+        self.mark_edited();
         &mut self.inner
     }
 }
@@ -185,9 +423,7 @@ impl<T> AsRef<T> for DbState<T> {
 
 impl<T> AsMut<T> for DbState<T> {
     fn as_mut(&mut self) -> &mut T {
-        if self.status == DbStatus::NotModified {
-            self.status = DbStatus::Edited
-        }
+        self.mark_edited();
         &mut self.inner
     }
 }
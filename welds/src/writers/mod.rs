@@ -4,6 +4,7 @@ pub(crate) mod count;
 pub(crate) mod insert;
 pub(crate) mod limit_skip;
 pub(crate) mod nextparam;
+pub(crate) mod returning;
 pub(crate) mod table;
 pub mod types;
 
@@ -12,4 +13,5 @@ pub use count::CountWriter;
 pub use insert::InsertWriter;
 pub use limit_skip::LimitSkipWriter;
 pub use nextparam::NextParam;
+pub use returning::{ReturningRow, ReturningWriter};
 pub use table::TableWriter;
@@ -0,0 +1,68 @@
+use super::column::ColumnWriter;
+use crate::Syntax;
+use crate::model_traits::Column;
+
+/// Writes the part of an UPDATE/DELETE that hands the affected rows back to the caller.
+///
+/// Postgres and Sqlite use a trailing `RETURNING ...`
+/// MSSQL uses an `OUTPUT INSERTED.* / DELETED.*` placed before the WHERE
+/// MySql has neither, callers need to fallback to selecting the rows themselves
+pub struct ReturningWriter {
+    syntax: Syntax,
+}
+
+/// Which version of the row MSSQL should output
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReturningRow {
+    Inserted,
+    Deleted,
+}
+
+impl ReturningWriter {
+    pub fn new(syntax: Syntax) -> Self {
+        Self { syntax }
+    }
+
+    /// True if the database can return rows directly from an UPDATE/DELETE
+    pub fn supported(&self) -> bool {
+        !matches!(self.syntax, Syntax::Mysql)
+    }
+
+    /// The `OUTPUT` clause. Goes between the `SET`/`FROM` and the `WHERE`
+    /// Only written for MSSQL
+    pub fn output(&self, row: ReturningRow, columns: &[Column]) -> Option<String> {
+        match self.syntax {
+            Syntax::Mssql => Some(Mssql::output(row, columns)),
+            _ => None,
+        }
+    }
+
+    /// The `RETURNING` clause. Goes at the very end of the statement
+    /// Only written for Postgres and Sqlite
+    pub fn returning(&self, columns: &[Column]) -> Option<String> {
+        match self.syntax {
+            Syntax::Postgres | Syntax::Sqlite => Some(returning(self.syntax, columns)),
+            _ => None,
+        }
+    }
+}
+
+fn returning(syntax: Syntax, columns: &[Column]) -> String {
+    let writer = ColumnWriter::new(syntax);
+    let cols: Vec<String> = columns.iter().map(|c| writer.excape(c.name())).collect();
+    format!("RETURNING {}", cols.join(", "))
+}
+
+struct Mssql;
+
+impl Mssql {
+    fn output(row: ReturningRow, columns: &[Column]) -> String {
+        let prefix = match row {
+            ReturningRow::Inserted => "INSERTED",
+            ReturningRow::Deleted => "DELETED",
+        };
+        let writer = ColumnWriter::new(Syntax::Mssql);
+        let cols: Vec<String> = columns.iter().map(|c| writer.write(prefix, c)).collect();
+        format!("OUTPUT {}", cols.join(", "))
+    }
+}
@@ -1,6 +1,8 @@
 use super::column::ColumnWriter;
 use crate::Syntax;
+use crate::errors::{Result, WeldsError};
 use crate::model_traits::TableIdent;
+use welds_connections::schema::is_plain_name;
 
 pub struct TableWriter {
     syntax: Syntax,
@@ -36,6 +38,17 @@ impl TableWriter {
         }
     }
 
+    /// Parses a table name given at runtime, `table` or `schema.table`
+    ///
+    /// Each part must be a plain name, see `welds_connections::schema::is_plain_name`
+    pub fn validate(raw: &str) -> Result<TableIdent> {
+        let parts: Vec<&str> = raw.split('.').collect();
+        if parts.len() > 2 || !parts.iter().all(|p| is_plain_name(p)) {
+            return Err(WeldsError::InvalidTableName(raw.to_owned()));
+        }
+        Ok(TableIdent::parse(raw))
+    }
+
     /// Returns the String that should be used when executing SQL
     pub fn write2(&self, parts: &[&str]) -> String {
         let excaper = ColumnWriter::new(self.syntax);
@@ -122,11 +122,31 @@ impl Pair {
         if self_rust_type == rust_typeonly {
             return true;
         }
+        // welds::Json<T> is stored in the same columns as a serde_json::Value
+        if self.rust_type == JSON_VALUE && !self.array && is_json_wrapper(rust) {
+            return true;
+        }
         // not a match
         false
     }
 }
 
+const JSON_VALUE: &str = "serde_json::Value";
+
+/// returns true if the rust type is a `Json<T>`
+fn is_json_wrapper(rust: &str) -> bool {
+    let rust: String = rust.chars().filter(|c| !c.is_whitespace()).collect();
+    let base = match rust.find('<') {
+        Some(index) => &rust[..index],
+        None => return false,
+    };
+    let base = match base.rfind(':') {
+        Some(index) => &base[index + 1..],
+        None => base,
+    };
+    base == "Json"
+}
+
 /// Returns a list of DB_TYPE and RUST_TYPE pairs.
 /// A pair can be assumed to be usable together in welds
 /// I.E.  INT <=> i32
@@ -379,6 +399,27 @@ mod tests {
         assert!(are_equivalent_types(&pairs, "MONEY", "PgMoney"));
     }
 
+    #[test]
+    fn json_wrapper_should_match_json_columns() {
+        let pairs = get_pairs(Syntax::Postgres);
+        assert!(are_equivalent_types(&pairs, "JSONB", "Json < Settings >"));
+        assert!(are_equivalent_types(
+            &pairs,
+            "JSONB",
+            "welds::Json<Settings>"
+        ));
+        assert!(!are_equivalent_types(&pairs, "TEXT", "Json<Settings>"));
+        let pairs = get_pairs(Syntax::Mysql);
+        assert!(are_equivalent_types(&pairs, "JSON", "Json<Settings>"));
+        let pairs = get_pairs(Syntax::Sqlite);
+        assert!(are_equivalent_types(&pairs, "TEXT", "Json<Settings>"));
+        let pairs = get_pairs(Syntax::Mssql);
+        assert!(are_equivalent_types(&pairs, "NVARCHAR", "Json<Settings>"));
+        let s = Syntax::Postgres;
+        let db_type = recommended_db_type(s, "Json<Settings>").unwrap().db_type();
+        assert_eq!(db_type, "JSONB");
+    }
+
     #[test]
     fn should_recommend_good_rust_types() {
         let s = Syntax::Postgres;